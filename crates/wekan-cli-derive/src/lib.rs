@@ -1,6 +1,6 @@
 use proc_macro2::{self};
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use syn::{parse_macro_input, Data, DeriveInput};
 
 #[proc_macro_derive(WekanArgs)]
 pub fn derive_wekan_args(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -34,15 +34,29 @@ pub fn derive_wekan_args(input: proc_macro::TokenStream) -> proc_macro::TokenStr
     proc_macro::TokenStream::from(output)
 }
 
-#[proc_macro_derive(CommonSubcommands)]
+/// Derived on the subcommand enum of an artifact. Variants marked with `#[common]` are handed
+/// to the common commands, the other ones are specific to the artifact.
+#[proc_macro_derive(CommonSubcommands, attributes(common))]
 pub fn derive_common_subcommands(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
+    let variants = match input.data {
+        Data::Enum(e) => e.variants,
+        _ => panic!("CommonSubcommands can only be derived for an enum"),
+    };
+    let common = variants
+        .iter()
+        .filter(|v| v.attrs.iter().any(|a| a.path.is_ident("common")))
+        .map(|v| &v.ident);
 
     let output: proc_macro2::TokenStream = quote! {
-        impl CommonCommandRequester<Command> for #name {
-            fn get_common_command(&self) -> Option<Command> {
-                self.command.to_owned()
+        impl CommonCommandRequester<#name> for Args {
+            #[allow(unreachable_patterns)]
+            fn get_common_command(&self) -> Option<CommonCommand> {
+                match &self.command {
+                    #(Some(#name::#common(c)) => Some(CommonCommand::#common(c.to_owned())),)*
+                    _ => None,
+                }
             }
         }
     };
@@ -0,0 +1,463 @@
+#[cfg(feature = "store")]
+use crate::store::Store;
+use crate::{
+    error::{failed, CliError, Error, Transform},
+    journal::{self, Change, Journal},
+    resolver::Query,
+    result::WekanResult,
+    search::boards,
+};
+use chrono::{SecondsFormat, Utc};
+use clap::{Args as ClapArgs, Subcommand};
+use log::{info, trace};
+use wekan_common::{
+    artifact::{
+        board::Details as BDetails,
+        common::{AType, Artifact, Base, BaseDetails},
+    },
+    http::{
+        artifact::{ArchiveArtifact, ResponseOk},
+        board::{Export, Exported},
+    },
+};
+use wekan_core::{
+    client::{BoardApi, Client, SwimlaneApi},
+    config::UserConfig,
+};
+
+#[cfg(test)]
+use crate::tests::mocks::{Artifacts, Operation};
+#[cfg(not(test))]
+use wekan_core::http::operation::{Artifacts, Operation};
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Browse and restore archived artifacts",
+    long_about = "Show archived boards, lists, swimlanes, cards and checklists. Archive or restore swimlanes"
+)]
+pub struct Args {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    Ls(ArchiveLsArgs),
+    Swimlane(SwimlaneArgs),
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "List archived artifacts",
+    long_about = "List archived boards, or the archived lists, swimlanes, cards and checklists of a board"
+)]
+pub struct ArchiveLsArgs {
+    #[clap(short = 'b', long, help = "Board name")]
+    pub board: Option<String>,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Archive a swimlane",
+    long_about = "Archive a swimlane or restore it with '--restore'"
+)]
+pub struct SwimlaneArgs {
+    /// Swimlane name
+    pub name: String,
+    #[clap(short = 'b', long, help = "Board name")]
+    pub board: String,
+    #[clap(
+        short = 'r',
+        long,
+        parse(from_flag),
+        help = "Restore an archived artifact"
+    )]
+    pub restore: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Archived {
+    pub r#type: AType,
+    pub id: String,
+    pub archived_at: String,
+    pub path: String,
+}
+
+impl Archived {
+    fn new(r#type: AType, id: &str, archived_at: Option<&String>, path: &str) -> Self {
+        Self {
+            r#type,
+            id: id.to_string(),
+            archived_at: archived_at.cloned().unwrap_or_default(),
+            path: path.to_string(),
+        }
+    }
+
+    fn from_export(r#type: AType, exported: &Exported, path: &str) -> Self {
+        Self::new(r#type, &exported._id, exported.archived_at.as_ref(), path)
+    }
+}
+
+/// Archives or restores the artifact with the id below the base of the client.
+pub async fn put(client: &mut Client, id: &str, archive: bool) -> Result<WekanResult, Error> {
+    let body = ArchiveArtifact {
+        _id: id.to_string(),
+        archive,
+        archive_at: match archive {
+            true => Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            false => String::new(),
+        },
+    };
+    match client.put::<ArchiveArtifact, ResponseOk>(&body).await {
+        Ok(_o) if archive => WekanResult::new_workflow(
+            "Successfully archived",
+            "Browse archived artifacts with 'archive ls' and restore them with 'archive --restore'",
+        )
+        .ok(),
+        Ok(_o) => WekanResult::new_msg("Successfully restored").ok(),
+        Err(e) => Err(failed("Failed to archive", e)),
+    }
+}
+
+/// Wekan lists active boards only, archived ones are looked for among the boards known to the
+/// store and the boards archived with the journal.
+async fn archived_boards(query: &mut Query<'_>) -> Result<Vec<Archived>, Error> {
+    let mut candidates = known_boards(query).await;
+    let active = boards(query, None).await?;
+    candidates.retain(|c| active.iter().all(|a| a._id != c._id));
+    candidates.dedup_by(|a, b| a._id == b._id);
+    let mut client = <Client as BoardApi>::new(query.config.clone());
+    let mut archived = Vec::new();
+    for board in candidates {
+        match client.get_one::<BDetails>(&board._id).await {
+            Ok(details) if details.is_archived() => archived.push(Archived::new(
+                AType::Board,
+                &board._id,
+                details.get_archive_at(),
+                &details.get_title(),
+            )),
+            Ok(_d) => {}
+            Err(e) => trace!("{}: {:?}", board._id, e),
+        }
+    }
+    Ok(archived)
+}
+
+async fn known_boards(query: &Query<'_>) -> Vec<Artifact> {
+    let mut known = Vec::new();
+    #[cfg(feature = "store")]
+    if let Ok(e) = query.lookup_artifacts(AType::Board, "").await {
+        known.extend(e.payload);
+    }
+    if let Ok(j) = Journal::read(&query.config).await {
+        for entry in j.entries {
+            if let Change::Archive {
+                r#type: AType::Board,
+                board_id,
+                ..
+            } = entry.change
+            {
+                known.push(Artifact {
+                    _id: board_id,
+                    title: String::new(),
+                    r#type: AType::Board,
+                });
+            }
+        }
+    }
+    known.sort_by(|a, b| a._id.cmp(&b._id));
+    known
+}
+
+/// Children of archived lists and cards are not listed, they come back with their parent.
+pub fn archived_in_export(export: &Export, board_title: &str) -> Vec<Archived> {
+    let mut archived = Vec::new();
+    for swimlane in export.swimlanes.iter().filter(|s| s.archived) {
+        archived.push(Archived::from_export(
+            AType::Swimlane,
+            swimlane,
+            &format!("{}/{}", board_title, swimlane.title),
+        ));
+    }
+    for list in &export.lists {
+        let path = format!("{}/{}", board_title, list.title);
+        if list.archived {
+            archived.push(Archived::from_export(AType::List, list, &path));
+            continue;
+        }
+        for card in export
+            .cards
+            .iter()
+            .filter(|c| c.list_id.as_deref() == Some(list._id.as_str()))
+        {
+            let path = format!("{}/{}", path, card.title);
+            if card.archived {
+                archived.push(Archived::from_export(AType::Card, card, &path));
+                continue;
+            }
+            for checklist in export
+                .checklists
+                .iter()
+                .filter(|c| c.archived && c.card_id.as_deref() == Some(card._id.as_str()))
+            {
+                archived.push(Archived::from_export(
+                    AType::Checklist,
+                    checklist,
+                    &format!("{}/{}", path, checklist.title),
+                ));
+            }
+        }
+    }
+    archived
+}
+
+async fn archived_in_board(query: &Query<'_>, board: &Artifact) -> Result<Vec<Archived>, Error> {
+    let mut client = <Client as BoardApi>::new(query.config.clone());
+    let export = client.get_export(&board._id).await?;
+    Ok(archived_in_export(&export, &board.title))
+}
+
+/// Finds an archived artifact by its exact title below its board and parent, e.g. the list of
+/// a card, to restore it.
+pub async fn find_archived_id(
+    config: &UserConfig,
+    r#type: &AType,
+    board_id: &str,
+    parent_id: &str,
+    title: &str,
+) -> Result<String, Error> {
+    info!("find_archived_id");
+    let found = match r#type {
+        AType::Board => {
+            let mut query = Query::new(config.clone(), "", false);
+            archived_boards(&mut query)
+                .await?
+                .into_iter()
+                .find(|b| b.path == title)
+                .map(|b| b.id)
+        }
+        _ => {
+            let mut client = <Client as BoardApi>::new(config.clone());
+            let export = client.get_export(board_id).await?;
+            let (artifacts, parent) = match r#type {
+                AType::List => (export.lists, None),
+                AType::Swimlane => (export.swimlanes, None),
+                AType::Card => (export.cards, Some(parent_id.to_string())),
+                _ => (export.checklists, Some(parent_id.to_string())),
+            };
+            artifacts
+                .into_iter()
+                .find(|a| {
+                    a.archived
+                        && a.title == title
+                        && match (&parent, r#type) {
+                            (Some(p), AType::Card) => a.list_id.as_ref() == Some(p),
+                            (Some(p), _) => a.card_id.as_ref() == Some(p),
+                            (None, _) => true,
+                        }
+                })
+                .map(|a| a._id)
+        }
+    };
+    match found {
+        Some(id) => Ok(id),
+        None => Err(CliError::new_msg(&format!(
+            "No archived {} '{}' found",
+            r#type.to_string(),
+            title
+        ))
+        .as_enum()),
+    }
+}
+
+pub fn render(archived: &[Archived]) -> String {
+    let mut output = String::from("TYPE       ARCHIVED_AT               PATH\n");
+    for a in archived {
+        output.push_str(&format!(
+            "{:<10} {:<25} {}\n",
+            a.r#type.to_string(),
+            a.archived_at,
+            a.path
+        ));
+    }
+    output
+}
+
+pub async fn run(args: &Args, query: &mut Query<'_>) -> Result<WekanResult, Error> {
+    info!("archive");
+    match &args.command {
+        Command::Ls(ls) => {
+            let archived = match &ls.board {
+                Some(b) => {
+                    let board = Artifact {
+                        _id: query.find_board_id(b).await?,
+                        title: b.to_owned(),
+                        r#type: AType::Board,
+                    };
+                    archived_in_board(query, &board).await?
+                }
+                None => archived_boards(query).await?,
+            };
+            if archived.is_empty() {
+                WekanResult::new_msg("Nothing archived").ok()
+            } else {
+                WekanResult::new_workflow(
+                    &render(&archived),
+                    "Restore an artifact with its 'archive --restore' subcommand",
+                )
+                .ok()
+            }
+        }
+        Command::Swimlane(s) => {
+            let board_id = query.find_board_id(&s.board).await?;
+            let id = match s.restore {
+                true => {
+                    find_archived_id(&query.config, &AType::Swimlane, &board_id, "", &s.name)
+                        .await?
+                }
+                false => query.find_swimlane_id_by_title(&board_id, &s.name).await?,
+            };
+            let mut client = <Client as SwimlaneApi>::new(query.config.clone(), &board_id);
+            let result = put(&mut client, &id, !s.restore).await?;
+            let verb = if s.restore { "restore" } else { "archive" };
+            let change = Change::Archive {
+                r#type: AType::Swimlane,
+                board_id,
+                parent_id: String::new(),
+                id,
+                archive: !s.restore,
+            };
+            journal::record(
+                &query.config,
+                &format!("{} swimlane '{}'", verb, s.name),
+                change,
+            )
+            .await;
+            Ok(result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use wekan_common::artifact::tests::MockResponse;
+
+    fn query(config: UserConfig) -> Query<'static> {
+        Query::new(config, "", true)
+    }
+
+    #[test]
+    fn render_archived() {
+        let archived = vec![
+            Archived::new(
+                AType::List,
+                "list-id",
+                Some(&String::from("2022-10-12T10:00:00.000Z")),
+                "Work/Done",
+            ),
+            Archived::new(
+                AType::Checklist,
+                "checklist-id",
+                None,
+                "Work/Todo/Release/Steps",
+            ),
+        ];
+        assert_eq!(
+            render(&archived),
+            concat!(
+                "TYPE       ARCHIVED_AT               PATH\n",
+                "list       2022-10-12T10:00:00.000Z  Work/Done\n",
+                "checklist                            Work/Todo/Release/Steps\n"
+            )
+        );
+    }
+
+    #[test]
+    fn find_archived_in_export() {
+        let archived = archived_in_export(&Export::mock(), "Work")
+            .into_iter()
+            .map(|a| (a.r#type, a.id, a.path))
+            .collect::<Vec<(AType, String, String)>>();
+        let expected = [
+            (
+                AType::Swimlane,
+                "fake-swimlane-id-1",
+                "Work/fake-swimlane-title-1",
+            ),
+            (
+                AType::Checklist,
+                "fake-checklist-id-1",
+                "Work/fake-list-title-1/fake-card-title-1/fake-checklist-title-1",
+            ),
+            (
+                AType::Card,
+                "fake-card-id-2",
+                "Work/fake-list-title-1/fake-card-title-2",
+            ),
+            (AType::List, "fake-list-id-2", "Work/fake-list-title-2"),
+        ]
+        .iter()
+        .map(|(t, id, path)| (t.clone(), id.to_string(), path.to_string()))
+        .collect::<Vec<(AType, String, String)>>();
+        assert_eq!(archived, expected);
+    }
+
+    #[tokio::test]
+    async fn find_archived_to_restore() {
+        let config = UserConfig::mock();
+        let id = find_archived_id(
+            &config,
+            &AType::List,
+            "fake-board-id-1",
+            "",
+            "fake-list-title-2",
+        )
+        .await
+        .unwrap();
+        assert_eq!(id, "fake-list-id-2");
+        let id = find_archived_id(
+            &config,
+            &AType::Card,
+            "fake-board-id-1",
+            "fake-list-id-1",
+            "fake-card-title-2",
+        )
+        .await
+        .unwrap();
+        assert_eq!(id, "fake-card-id-2");
+        assert!(find_archived_id(
+            &config,
+            &AType::Card,
+            "fake-board-id-1",
+            "fake-list-id-1",
+            "fake-card-title-1"
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn run_ls_and_swimlane() {
+        let mut query = query(UserConfig::mock());
+        let args = Args {
+            command: Command::Ls(ArchiveLsArgs {
+                board: Some(String::from("fake-board-title-1")),
+            }),
+        };
+        let res = run(&args, &mut query).await.unwrap();
+        assert!(res
+            .get_msg()
+            .contains("fake-board-title-1/fake-list-title-2\n"));
+        let args = Args {
+            command: Command::Swimlane(SwimlaneArgs {
+                name: String::from("fake-swimlane-title-1"),
+                board: String::from("fake-board-title-1"),
+                restore: false,
+            }),
+        };
+        let res = run(&args, &mut query).await.unwrap();
+        assert_eq!(res.get_msg(), "Successfully archived");
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    command::{Args as RArgs, Subcommand, WekanParser},
+    config::alias::expand,
+    display::CliDisplay,
+    error::{CliError, Error, Transform},
+    result::WekanResult,
+    runner::Runner,
+    shell::{global_argv, print_error},
+};
+use clap::{Args as ClapArgs, Parser};
+use log::{info, trace};
+use std::{future::Future, pin::Pin};
+use tokio::io::AsyncReadExt;
+use wekan_core::client::LoginClient;
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Run commands from a script",
+    long_about = "Run one wekan-cli command per line of a file, or of stdin with '-', with one login and healthcheck. Empty lines and lines starting with '#' are skipped"
+)]
+pub struct Args {
+    /// Script file or '-' for stdin
+    pub file: String,
+    #[clap(
+        short = 'c',
+        long,
+        parse(from_flag),
+        help = "Run the remaining lines after a failed one"
+    )]
+    pub continue_on_error: bool,
+}
+
+/// Runs the lines of a script with the logged in client of the batch command.
+pub struct Batch<'a> {
+    pub client: LoginClient,
+    pub global_options: &'a RArgs,
+}
+
+impl<'a> Batch<'a> {
+    pub fn new(client: LoginClient, global_options: &'a RArgs) -> Self {
+        Self {
+            client,
+            global_options,
+        }
+    }
+
+    pub async fn run(&mut self, args: &Args) -> Result<WekanResult, Error> {
+        info!("batch");
+        let script = match args.file.as_str() {
+            "-" => {
+                let mut script = String::new();
+                tokio::io::stdin().read_to_string(&mut script).await?;
+                script
+            }
+            f => match tokio::fs::read_to_string(f).await {
+                Ok(s) => s,
+                Err(e) => {
+                    return CliError::new_msg(&format!("Script '{}' not readable: {}", f, e)).err()
+                }
+            },
+        };
+        self.run_script(&script, args.continue_on_error).await
+    }
+
+    /// Prints the result of every line prefixed with its number. Stops at the first failure
+    /// unless it should continue.
+    pub async fn run_script(
+        &mut self,
+        script: &str,
+        continue_on_error: bool,
+    ) -> Result<WekanResult, Error> {
+        let mut ran = 0;
+        let mut failed = Vec::new();
+        for (i, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            ran += 1;
+            println!("{}> {}", i + 1, line);
+            match self.handle(line).await {
+                Ok(r) => {
+                    let msg = r.get_msg();
+                    if !msg.trim().is_empty() {
+                        println!("{}", msg.trim());
+                    }
+                }
+                Err(e) => {
+                    print_error(e);
+                    failed.push((i + 1).to_string());
+                    if !continue_on_error {
+                        break;
+                    }
+                }
+            }
+        }
+        match failed.len() {
+            0 => WekanResult::new_msg(&format!("Successfully ran {} lines", ran)).ok(),
+            n => WekanResult::new_exit(
+                &format!("Ran {} lines, {} failed: {}", ran, n, failed.join(", ")),
+                1,
+                None,
+            )
+            .ok(),
+        }
+    }
+
+    async fn handle(&mut self, line: &str) -> Result<WekanResult, Error> {
+        trace!("{}", line);
+        let words = match shell_words::split(line) {
+            Ok(w) => w,
+            Err(_e) => return CliError::new_msg("Missing closing quote").err(),
+        };
+        let mut argv = vec![String::from("wekan-cli")];
+        argv.extend(global_argv(self.global_options));
+        argv.extend(words);
+        let parser = match WekanParser::try_parse_from(expand(argv, &self.client.config.aliases)) {
+            Ok(p) => p,
+            Err(e) => return CliError::new_msg(e.to_string().trim()).err(),
+        };
+        match parser.command {
+            Subcommand::Shell(_)
+            | Subcommand::Serve(_)
+            | Subcommand::Tui(_)
+            | Subcommand::Batch(_) => {
+                return CliError::new_msg("Not available inside a batch").err()
+            }
+            _ => {}
+        };
+        let mut runner = Runner {
+            client: self.client.clone(),
+            format: match parser.delegate.output_format {
+                Some(ref f) => f.to_owned(),
+                None => "terminal".to_string(),
+            },
+            display: CliDisplay::new(Vec::new()),
+            subcommands: parser.command,
+            global_options: parser.delegate,
+            #[cfg(feature = "store")]
+            workspace: Vec::new(),
+        };
+        // The runner runs the batch itself, the indirection breaks the recursive future type.
+        let future: Pin<Box<dyn Future<Output = Result<WekanResult, Error>> + '_>> =
+            Box::pin(runner.run_checked());
+        future.await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use wekan_core::config::UserConfig;
+
+    #[tokio::test]
+    async fn run_script_lines() {
+        let r_args = RArgs::mock();
+        let mut batch = Batch::new(LoginClient::new(UserConfig::mock()), &r_args);
+        let script = concat!(
+            "# nightly cards\n",
+            "\n",
+            "board -n fake-board-title-1\n",
+            "nope\n",
+            "shell\n",
+            "board ls\n",
+        );
+        let res = batch.run_script(script, true).await.unwrap();
+        assert_eq!(res.get_msg(), "Ran 4 lines, 2 failed: 4, 5");
+        assert_eq!(res.get_exit_code(), 1);
+        let res = batch.run_script(script, false).await.unwrap();
+        assert_eq!(res.get_msg(), "Ran 2 lines, 1 failed: 4");
+        let res = batch.run_script("board ls", false).await.unwrap();
+        assert_eq!(res.get_msg(), "Successfully ran 1 lines");
+    }
+}
@@ -1,22 +1,60 @@
 use clap::Parser;
 use log::{debug, error, info, trace};
-use wekan_cli::{command::WekanParser, error::Error, result::WekanResult, runner::Runner};
-use wekan_core::error::Error as CoreError;
+use wekan_cli::{
+    command::WekanParser,
+    config::alias::expand,
+    display::is_elisp,
+    error::Error,
+    result::WekanResult,
+    runner::{ReadContext, Runner},
+};
+use wekan_common::artifact::common::{elisp_string, Elisp};
+use wekan_core::{
+    config::{MandatoryConfig, UserConfig},
+    error::Error as CoreError,
+};
 #[tokio::main]
 async fn main() {
-    let parser = WekanParser::parse();
+    let aliases = match UserConfig::new().read_config_context().await {
+        Ok(c) => c.aliases,
+        Err(_e) => Default::default(),
+    };
+    let argv = expand(std::env::args().collect(), &aliases);
+    let parser = WekanParser::parse_from(&argv);
     let mut c = Runner::new(parser.delegate, parser.command).await;
-    std::process::exit(<Runner as ExitCode>::transform_to_exit(c.run().await).into());
+    std::process::exit(<Runner as ExitCode>::transform_to_exit(c.run().await, &argv).into());
 }
 
 trait ExitCode {
-    fn transform_to_exit(result: Result<WekanResult, Error>) -> i8 {
+    fn transform_to_exit(result: Result<WekanResult, Error>, argv: &[String]) -> i8 {
         debug!("transform_to_exit");
         trace!("{:?}", result);
+        let parser = WekanParser::parse_from(argv);
+        let elisp = is_elisp(parser.delegate.output_format.as_ref());
         match result {
+            Ok(r) if elisp => {
+                println!("{}", r.to_elisp());
+                r.get_exit_code()
+            }
+            Err(e) if elisp => {
+                let message = match &e {
+                    Error::Cli(cli) => cli.message.to_owned(),
+                    Error::Input(i) => i.message.to_owned(),
+                    _ => String::from("Request failed, see stderr"),
+                };
+                let exit_code = match e {
+                    Error::Input(_i) => 0,
+                    e => Self::transform_error(e),
+                };
+                println!(
+                    "(:exit-code {} :error {})",
+                    exit_code,
+                    elisp_string(&message)
+                );
+                exit_code
+            }
             Ok(r) => {
                 println!("{}", r.get_msg().trim());
-                let parser = WekanParser::parse();
                 if !parser.delegate.no_recommendations {
                     match &r.get_next_workflow() {
                         Some(w) => println!("Recommended workflow: {}", w.trim()),
@@ -25,34 +63,36 @@ trait ExitCode {
                 }
                 r.get_exit_code()
             }
-            Err(e) => {
-                debug!("{:?}", e);
-                info!("Use WEKAN_LOG, verbose or WEKAN_BACKTRACE=1.");
-                match e {
-                    Error::Core(core) => Self::transform_core_error(core),
-                    Error::Cli(cli) => {
-                        eprintln!("{}", cli.message);
-                        cli.error_code
-                    }
-                    Error::Input(i) => {
-                        println!("{}", i.message);
-                        0
-                    }
-                    Error::Io(io) => {
-                        eprintln!("IO Error");
-                        eprint!("{:?}", io);
-                        3
-                    }
-                    Error::Yaml(yaml) => {
-                        eprintln!("{:?}", yaml);
-                        4
-                    }
-                    #[cfg(feature = "store")]
-                    Error::Store(store) => {
-                        eprintln!("{:?}", store);
-                        4
-                    }
-                }
+            Err(e) => Self::transform_error(e),
+        }
+    }
+
+    fn transform_error(e: Error) -> i8 {
+        debug!("{:?}", e);
+        info!("Use WEKAN_LOG, verbose or WEKAN_BACKTRACE=1.");
+        match e {
+            Error::Core(core) => Self::transform_core_error(core),
+            Error::Cli(cli) => {
+                eprintln!("{}", cli.message);
+                cli.error_code
+            }
+            Error::Input(i) => {
+                println!("{}", i.message);
+                0
+            }
+            Error::Io(io) => {
+                eprintln!("IO Error");
+                eprint!("{:?}", io);
+                3
+            }
+            Error::Yaml(yaml) => {
+                eprintln!("{:?}", yaml);
+                4
+            }
+            #[cfg(feature = "store")]
+            Error::Store(store) => {
+                eprintln!("{:?}", store);
+                4
             }
         }
     }
@@ -93,6 +133,15 @@ trait ExitCode {
                 eprintln!("Failed to load config or context. Delete WEKAN_CLI_CONFIG_PATH.");
                 2
             }
+            CoreError::DryRun(d) => {
+                eprintln!("{}", d);
+                0
+            }
+            CoreError::Json(json) => {
+                error!("{:?}", json);
+                eprintln!("Request body couldn't be serialized.");
+                2
+            }
         }
     }
 }
@@ -1,26 +1,40 @@
 use crate::{
+    calendar::Calendar,
     command::{
         Args as RArgs, ArgumentRequester, ArtifactCommand, ArtifactName, CommonCommandRequester,
         CreateSubcommand, Fulfillment, Operator, RootCommandRunner, SubCommandValidator,
     },
     display::CliDisplay,
     error::{CliError, Error, Transform},
+    markup::{BoardDocument, Markup},
     resolver::Query,
     result::WekanResult,
-    subcommand::{CommonCommand as Command, Inspect},
+    snapshot::{Restore, Snapshot},
+    subcommand::{Archive, CommonCommand, Create, Details as SDetails, Inspect, List, Remove},
 };
 use async_trait::async_trait;
-use clap::Args as ClapArgs;
+use clap::{Args as ClapArgs, Subcommand};
+use log::info;
+use std::path::PathBuf;
 use wekan_cli_derive::{CommonSubcommands, FulfilmentRunner, WekanArgs};
 use wekan_common::{
-    artifact::{board::Details, common::AType},
+    artifact::{
+        board::Details,
+        card::Details as CDetails,
+        common::{AType, Base},
+    },
     http::board::{CreateBoard, CreatedBoard},
     validation::{authentication::TokenHeader, constraint::BoardConstraint as BConstraint},
 };
 use wekan_core::client::{BoardApi, Client};
 
+#[cfg(test)]
+use crate::tests::mocks::Artifacts;
+#[cfg(not(test))]
+use wekan_core::http::operation::Artifacts;
+
 /// Board commands
-#[derive(ClapArgs, Debug, Clone, WekanArgs, CommonSubcommands)]
+#[derive(ClapArgs, Debug, Clone, WekanArgs)]
 #[clap(
     about = "Manage boards",
     long_about = "Create, remove and show details and children"
@@ -34,6 +48,69 @@ pub struct Args {
     pub command: Option<Command>,
 }
 
+/// The following commands are available:
+#[derive(Subcommand, Debug, Clone, CommonSubcommands)]
+pub enum Command {
+    #[common]
+    Ls(List),
+    #[common]
+    Details(SDetails),
+    #[common]
+    Create(Create),
+    #[common]
+    Inspect(Inspect),
+    #[common]
+    #[clap(name = "rm")]
+    Remove(Remove),
+    Import(ImportArgs),
+    Calendar(CalendarArgs),
+    Cp(CopyArgs),
+    Export(ExportArgs),
+    Archive(Archive),
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Import a board from a snapshot file",
+    long_about = "Recreate a board with its lists, swimlanes, cards and checklists from a YAML snapshot"
+)]
+pub struct ImportArgs {
+    /// Snapshot file
+    pub file: PathBuf,
+    #[clap(long = "as", help = "Title of the new board")]
+    pub title: Option<String>,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Copy a board",
+    long_about = "Create a new board with copies of all lists, swimlanes, cards and checklists"
+)]
+pub struct CopyArgs {
+    /// Title of the new board
+    pub title: String,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Export a board as snapshot file",
+    long_about = "Write the lists, swimlanes, cards and checklists of a board as YAML snapshot, as read by 'board import' and 'diff -f'"
+)]
+pub struct ExportArgs {
+    #[clap(short = 'o', long, help = "Snapshot file, prints to stdout if not set")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Export due dates as iCalendar",
+    long_about = "Write one event or todo per dated card into an RFC 5545 calendar file"
+)]
+pub struct CalendarArgs {
+    #[clap(short = 'o', long, help = "Calendar file, prints to stdout if not set")]
+    pub output: Option<PathBuf>,
+}
+
 #[cfg(test)]
 impl Args {
     pub fn mock(name: Option<String>, command: Option<Command>) -> Self {
@@ -59,23 +136,20 @@ impl<'a> Operator<'a> for Runner<'a> {
             Some(f) => filter.push_str(f),
             None => {}
         };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            filter: &filter,
-            config: self.get_client().config,
-            deny_store_usage: self.get_global_options().no_store,
-        };
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            filter: &filter,
-            config: self.get_client().config,
-        };
+        let mut query = Query::new(
+            self.get_client().config,
+            &filter,
+            self.get_global_options().deny_store(),
+        );
         query.find_board_id(name).await
     }
 
     fn get_type(&self) -> AType {
         AType::Board
     }
+    fn get_location(&self, id: &str) -> (String, String) {
+        (id.to_string(), String::new())
+    }
 
     fn get_children_type(&self) -> AType {
         AType::List
@@ -104,7 +178,28 @@ impl<'a> ArtifactCommand<'a, Args, Client, BConstraint> for Runner<'a> {
 #[async_trait]
 impl<'a> RootCommandRunner<'a, Details, Command> for Runner<'a> {
     async fn use_specific_command(&mut self) -> Result<WekanResult, Error> {
-        self.use_common_command().await
+        info!("use_specific_command");
+        match self.args.command.to_owned() {
+            Some(Command::Import(i)) => self.run_import(&i).await,
+            Some(Command::Calendar(c)) => self.run_calendar(&c).await,
+            Some(Command::Cp(c)) => self.run_copy(&c).await,
+            Some(Command::Export(e)) => self.run_export(&e).await,
+            Some(Command::Archive(a)) => self.run_archive(&a).await,
+            Some(Command::Details(_d)) => match Markup::from_format(Some(&self.format)) {
+                Some(m) => self.run_document(&m).await,
+                None => self.use_common_command().await,
+            },
+            _ => self.use_common_command().await,
+        }
+    }
+    async fn default(&mut self) -> Result<WekanResult, Error> {
+        match self.args.get_name() {
+            Ok(n) => match Markup::from_format(Some(&self.format)) {
+                Some(m) => self.run_document(&m).await,
+                None => self.details::<Details>(Some(n)).await,
+            },
+            Err(_e) => self.use_ls().await,
+        }
     }
     async fn use_ls(&mut self) -> Result<WekanResult, Error> {
         self.client
@@ -135,13 +230,142 @@ impl<'a> RootCommandRunner<'a, Details, Command> for Runner<'a> {
     }
 }
 
+impl<'a> Runner<'a> {
+    async fn run_calendar(&mut self, calendar_args: &CalendarArgs) -> Result<WekanResult, Error> {
+        info!("run_calendar");
+        let name = self.args.get_name()?;
+        let id = self.find_details_id(&name).await?;
+        let board = self.client.get_one::<Details>(&id).await?;
+        let mut filter = String::new();
+        if let Some(f) = &self.global_options.filter {
+            filter.push_str(f);
+        }
+        let query = Query::new(
+            self.get_client().config,
+            &filter,
+            self.get_global_options().deny_store(),
+        );
+        let mut calendar = Calendar::new(&board.get_title());
+        for l in query
+            .inquire(AType::List, Some(&id), None, None, true)
+            .await?
+        {
+            let mut client = <Client as wekan_core::client::CardApi>::new(
+                self.client.config.clone(),
+                &id,
+                &l._id,
+            );
+            for c in query
+                .inquire(AType::Card, Some(&id), Some(&l._id), None, true)
+                .await?
+            {
+                let card = client.get_one::<CDetails>(&c._id).await?;
+                calendar.add_card(&card);
+            }
+        }
+        match &calendar_args.output {
+            Some(path) => {
+                tokio::fs::write(path, calendar.to_ics()).await?;
+                WekanResult::new_msg(&format!(
+                    "Successfully wrote {} dated cards to {}",
+                    calendar.len(),
+                    path.display()
+                ))
+                .ok()
+            }
+            None => WekanResult::new_msg(&calendar.to_ics()).ok(),
+        }
+    }
+
+    async fn run_document(&mut self, markup: &Markup) -> Result<WekanResult, Error> {
+        info!("run_document");
+        let id = self.unwrap_and_find_id(self.args.name.to_owned()).await?;
+        let board = self.client.get_one::<Details>(&id).await?;
+        let mut filter = String::new();
+        if let Some(f) = &self.global_options.filter {
+            filter.push_str(f);
+        }
+        let query = Query::new(
+            self.get_client().config,
+            &filter,
+            self.get_global_options().deny_store(),
+        );
+        let lists = query
+            .inquire(AType::List, Some(&id), None, None, true)
+            .await?;
+        let document = BoardDocument::collect(&query, &id, &board.get_title(), lists).await?;
+        self.display.format_board_document(&document, markup)
+    }
+
+    async fn run_import(&mut self, import_args: &ImportArgs) -> Result<WekanResult, Error> {
+        info!("run_import");
+        let snapshot = Snapshot::read(&import_args.file).await?;
+        let mut restore = Restore::new(self.get_client().config, &self.client.get_user_id());
+        restore.run(&snapshot, import_args.title.to_owned()).await
+    }
+
+    async fn run_export(&mut self, export_args: &ExportArgs) -> Result<WekanResult, Error> {
+        info!("run_export");
+        let name = self.args.get_name()?;
+        let id = self.find_details_id(&name).await?;
+        let board = self.client.get_one::<Details>(&id).await?;
+        let query = Query::new(
+            self.get_client().config,
+            "",
+            self.get_global_options().deny_store(),
+        );
+        let lists = query
+            .inquire(AType::List, Some(&id), None, None, true)
+            .await?;
+        let snapshot = Snapshot::collect(&query, &id, &board.get_title(), lists, None).await?;
+        let content = serde_yaml::to_string(&snapshot)?;
+        match &export_args.output {
+            Some(path) => {
+                tokio::fs::write(path, content).await?;
+                WekanResult::new_msg(&format!(
+                    "Successfully exported board '{}' with {} cards to {}",
+                    snapshot.title,
+                    snapshot.cards.len(),
+                    path.display()
+                ))
+                .ok()
+            }
+            None => WekanResult::new_msg(&content).ok(),
+        }
+    }
+
+    async fn run_archive(&mut self, archive_args: &Archive) -> Result<WekanResult, Error> {
+        info!("run_archive");
+        self.archive(self.args.name.to_owned(), !archive_args.restore)
+            .await
+    }
+
+    async fn run_copy(&mut self, copy_args: &CopyArgs) -> Result<WekanResult, Error> {
+        info!("run_copy");
+        let name = self.args.get_name()?;
+        let id = self.find_details_id(&name).await?;
+        let query = Query::new(
+            self.get_client().config,
+            "",
+            self.get_global_options().deny_store(),
+        );
+        let lists = query
+            .inquire(AType::List, Some(&id), None, None, true)
+            .await?;
+        let snapshot = Snapshot::collect(&query, &id, &name, lists, None).await?;
+        let mut restore = Restore::new(self.get_client().config, &self.client.get_user_id());
+        restore.create(&snapshot, &copy_args.title).await?;
+        restore.report(
+            &format!("board '{}' to '{}'", name, copy_args.title),
+            &snapshot,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        subcommand::{Create, Details as SDetails, Remove},
-        tests::mocks::Mock,
-    };
+    use crate::tests::mocks::Mock;
     use wekan_common::validation::{authentication::Token, user::User};
 
     #[tokio::test]
@@ -246,7 +470,7 @@ mod tests {
         let mut runner = Runner::new(
             Args::mock(
                 Some(String::from("fake-board-title-1")),
-                Some(Command::Remove(Remove {})),
+                Some(Command::Remove(Remove { yes: true })),
             ),
             Client::mock(),
             BConstraint {
@@ -301,4 +525,123 @@ mod tests {
         );
         assert_eq!(res.get_msg(), expected);
     }
+
+    #[tokio::test]
+    async fn run_with_markdown_output() {
+        #[cfg(feature = "store")]
+        let r_args = RArgs::mock_with(false, false, "markdown", "");
+        #[cfg(not(feature = "store"))]
+        let r_args = RArgs::mock_with(false, "markdown", "");
+        let mut runner = Runner::new(
+            Args::mock(Some(String::from("fake-board-title-2")), None),
+            Client::mock(),
+            BConstraint {
+                user: Ok(User {
+                    name: *Token::mock().id,
+                    token: Some(*Token::mock().token),
+                }),
+            },
+            String::from("markdown"),
+            CliDisplay::new(Vec::new()),
+            &r_args,
+        );
+        let res = runner.run().await.unwrap();
+        let expected = concat!(
+            "# fake-board-title\n\n",
+            "## fake-list-title-1\n\n",
+            "- [x] fake-card-title (due 2020-10-12)\n",
+            "  - fake-checklist-title\n",
+            "    - [x] fake-item-title-1\n",
+            "    - [ ] fake-item-title-2\n",
+        );
+        assert!(res.get_msg().starts_with(expected));
+        assert!(res.get_msg().contains("## fake-list-title-2\n"));
+    }
+
+    #[tokio::test]
+    async fn run_calendar() {
+        #[cfg(feature = "store")]
+        let r_args = RArgs::mock_with(false, false, "", "");
+        #[cfg(not(feature = "store"))]
+        let r_args = RArgs::mock_with(false, "", "");
+        let mut runner = Runner::new(
+            Args::mock(
+                Some(String::from("fake-board-title-2")),
+                Some(Command::Calendar(CalendarArgs { output: None })),
+            ),
+            Client::mock(),
+            BConstraint {
+                user: Ok(User {
+                    name: *Token::mock().id,
+                    token: Some(*Token::mock().token),
+                }),
+            },
+            String::new(),
+            CliDisplay::new(Vec::new()),
+            &r_args,
+        );
+        let res = runner.run().await.unwrap();
+        assert!(res.get_msg().starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(res.get_msg().contains("X-WR-CALNAME:fake-board-title\r\n"));
+        assert_eq!(res.get_msg().matches("BEGIN:VTODO").count(), 4);
+    }
+
+    #[tokio::test]
+    async fn run_copy() {
+        #[cfg(feature = "store")]
+        let r_args = RArgs::mock_with(false, false, "", "");
+        #[cfg(not(feature = "store"))]
+        let r_args = RArgs::mock_with(false, "", "");
+        let mut runner = Runner::new(
+            Args::mock(
+                Some(String::from("fake-board-title-1")),
+                Some(Command::Cp(CopyArgs {
+                    title: String::from("new-board"),
+                })),
+            ),
+            Client::mock(),
+            BConstraint {
+                user: Ok(User {
+                    name: *Token::mock().id,
+                    token: Some(*Token::mock().token),
+                }),
+            },
+            String::new(),
+            CliDisplay::new(Vec::new()),
+            &r_args,
+        );
+        let res = runner.run().await.unwrap();
+        assert_eq!(
+            res.get_msg(),
+            "Successfully copied board 'fake-board-title-1' to 'new-board' with 4 cards and 8 checklists"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_export() {
+        #[cfg(feature = "store")]
+        let r_args = RArgs::mock_with(false, false, "", "");
+        #[cfg(not(feature = "store"))]
+        let r_args = RArgs::mock_with(false, "", "");
+        let mut runner = Runner::new(
+            Args::mock(
+                Some(String::from("fake-board-title-1")),
+                Some(Command::Export(ExportArgs { output: None })),
+            ),
+            Client::mock(),
+            BConstraint {
+                user: Ok(User {
+                    name: *Token::mock().id,
+                    token: Some(*Token::mock().token),
+                }),
+            },
+            String::new(),
+            CliDisplay::new(Vec::new()),
+            &r_args,
+        );
+        let res = runner.run().await.unwrap();
+        let snapshot = serde_yaml::from_str::<Snapshot>(&res.get_msg()).unwrap();
+        assert_eq!(snapshot.title, "fake-board-title");
+        assert_eq!(snapshot.cards.len(), 4);
+    }
 }
@@ -0,0 +1,302 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use wekan_common::artifact::{
+    card::Details,
+    common::{Base, BaseDetails, IdReturner, MostDetails},
+};
+
+/// Builds an RFC 5545 calendar with one component per dated card.
+pub struct Calendar {
+    name: String,
+    components: Vec<String>,
+}
+
+impl Calendar {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            components: Vec::new(),
+        }
+    }
+
+    /// Cards with a start date become events, cards with only a due or end date become todos.
+    pub fn add_card(&mut self, card: &Details) -> bool {
+        let start_at = card.get_start_at().and_then(|d| ics_date(&d));
+        let due_at = ics_date(&card.get_due_at());
+        let end_at = ics_date(&card.get_end_at());
+        let mut lines = Vec::new();
+        match (start_at, due_at, end_at) {
+            (None, None, None) => return false,
+            (Some(start), due, end) => {
+                lines.push(String::from("BEGIN:VEVENT"));
+                self.push_common(&mut lines, card);
+                match end.or(due) {
+                    Some(e) => {
+                        let (start, e) = same_type(&start, &e);
+                        lines.push(format!("DTSTART{}", start));
+                        if let Some(e) = exclusive_end(&start, &e) {
+                            lines.push(format!("DTEND{}", e));
+                        }
+                    }
+                    None => lines.push(format!("DTSTART{}", start)),
+                }
+                lines.push(String::from("END:VEVENT"));
+            }
+            (None, due, end) => {
+                lines.push(String::from("BEGIN:VTODO"));
+                self.push_common(&mut lines, card);
+                let received = card.get_received_at().and_then(|d| ics_date(&d));
+                let (received, due) = todo_dates(received, due);
+                if let Some(r) = received {
+                    lines.push(format!("DTSTART{}", r));
+                }
+                if let Some(d) = due {
+                    lines.push(format!("DUE{}", d));
+                }
+                match end {
+                    Some(e) => {
+                        // COMPLETED only accepts a date with time
+                        if e.starts_with(':') {
+                            lines.push(format!("COMPLETED{}", e));
+                        }
+                        lines.push(String::from("STATUS:COMPLETED"));
+                    }
+                    None => lines.push(String::from("STATUS:NEEDS-ACTION")),
+                }
+                lines.push(String::from("END:VTODO"));
+            }
+        }
+        self.components
+            .push(lines.iter().map(|l| fold(l)).collect::<String>());
+        true
+    }
+
+    fn push_common(&self, lines: &mut Vec<String>, card: &Details) {
+        lines.push(format!("UID:{}@wekan-cli", card.get_id()));
+        let stamp = match ics_date(&card.get_modified_at()) {
+            Some(s) if s.starts_with(':') => s,
+            _ => format!(":{}", Utc::now().format("%Y%m%dT%H%M%SZ")),
+        };
+        lines.push(format!("DTSTAMP{}", stamp));
+        lines.push(format!("SUMMARY:{}", escape(&card.get_title())));
+        let description = card.get_description();
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape(&description)));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    pub fn to_ics(&self) -> String {
+        let mut output = String::new();
+        for l in [
+            "BEGIN:VCALENDAR",
+            "VERSION:2.0",
+            "PRODID:-//wekan-cli//EN",
+            "CALSCALE:GREGORIAN",
+        ] {
+            output.push_str(&fold(l));
+        }
+        output.push_str(&fold(&format!("X-WR-CALNAME:{}", escape(&self.name))));
+        for c in &self.components {
+            output.push_str(c);
+        }
+        output.push_str(&fold("END:VCALENDAR"));
+        output
+    }
+}
+
+/// Returns the value including the property parameter separator, e.g. ':20221012T100000Z'.
+fn ics_date(date: &str) -> Option<String> {
+    match date.parse::<DateTime<Utc>>() {
+        Ok(d) => Some(format!(":{}", d.format("%Y%m%dT%H%M%SZ"))),
+        Err(_e) => {
+            let day = match date.split_once('T') {
+                Some(d) => d.0,
+                None => date,
+            };
+            NaiveDate::parse_from_str(day, "%Y-%m-%d")
+                .ok()
+                .map(|d| format!(";VALUE=DATE:{}", d.format("%Y%m%d")))
+        }
+    }
+}
+
+const DATE: &str = ";VALUE=DATE:";
+
+/// DTSTART and DTEND or DUE need the same value type, if one is a date both become dates.
+fn same_type(start: &str, end: &str) -> (String, String) {
+    let day = |d: &str| match d.strip_prefix(':') {
+        Some(t) => format!("{}{}", DATE, &t[..8]),
+        None => d.to_string(),
+    };
+    if start.starts_with(DATE) || end.starts_with(DATE) {
+        (day(start), day(end))
+    } else {
+        (start.to_string(), end.to_string())
+    }
+}
+
+/// DTEND of a whole day event is the day after its last day,
+/// a DTEND with time which isn't later than DTSTART is dropped.
+/// Both values have the same type and width, so they compare as text.
+fn exclusive_end(start: &str, end: &str) -> Option<String> {
+    let day = |d: &str| {
+        d.strip_prefix(DATE)
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+    };
+    match (day(start), day(end)) {
+        (Some(s), Some(e)) if e <= s => s
+            .succ_opt()
+            .map(|n| format!("{}{}", DATE, n.format("%Y%m%d"))),
+        _ if end <= start => None,
+        _ => Some(end.to_string()),
+    }
+}
+
+/// DTSTART and DUE of a todo get the same type, a DUE before DTSTART is dropped.
+fn todo_dates(received: Option<String>, due: Option<String>) -> (Option<String>, Option<String>) {
+    match (received, due) {
+        (Some(r), Some(d)) => {
+            let (r, d) = same_type(&r, &d);
+            if d < r {
+                (Some(r), None)
+            } else {
+                (Some(r), Some(d))
+            }
+        }
+        other => other,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Content lines are folded after 75 octets without splitting a character.
+fn fold(line: &str) -> String {
+    let mut output = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            output.push_str("\r\n ");
+            length = 1;
+        }
+        output.push(c);
+        length += c.len_utf8();
+    }
+    output.push_str("\r\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wekan_common::artifact::tests::MockDetails;
+
+    #[test]
+    fn escape_text() {
+        assert_eq!(escape("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn fold_long_lines() {
+        let folded = fold(&"a".repeat(80));
+        assert_eq!(folded, "a".repeat(75) + "\r\n " + &"a".repeat(5) + "\r\n");
+        assert_eq!(fold("äö"), "äö\r\n");
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(
+            ics_date("2022-10-12T10:00:00.000Z"),
+            Some(String::from(":20221012T100000Z"))
+        );
+        assert_eq!(
+            ics_date("2022-10-12T"),
+            Some(String::from(";VALUE=DATE:20221012"))
+        );
+        assert_eq!(ics_date(""), None);
+        assert_eq!(
+            same_type(":20221012T100000Z", ";VALUE=DATE:20221014"),
+            (
+                String::from(";VALUE=DATE:20221012"),
+                String::from(";VALUE=DATE:20221014")
+            )
+        );
+        assert_eq!(
+            same_type(":20221012T100000Z", ":20221014T100000Z").1,
+            ":20221014T100000Z"
+        );
+        assert_eq!(
+            exclusive_end(";VALUE=DATE:20221012", ";VALUE=DATE:20221012"),
+            Some(String::from(";VALUE=DATE:20221013"))
+        );
+        assert_eq!(
+            exclusive_end(";VALUE=DATE:20221012", ";VALUE=DATE:20221011"),
+            Some(String::from(";VALUE=DATE:20221013"))
+        );
+        assert_eq!(
+            exclusive_end(":20221012T100000Z", ":20221012T090000Z"),
+            None
+        );
+        assert_eq!(
+            exclusive_end(":20221012T100000Z", ":20221012T110000Z"),
+            Some(String::from(":20221012T110000Z"))
+        );
+        assert_eq!(
+            todo_dates(
+                Some(String::from(":20221012T100000Z")),
+                Some(String::from(":20221011T100000Z"))
+            ),
+            (Some(String::from(":20221012T100000Z")), None)
+        );
+        assert_eq!(
+            todo_dates(
+                Some(String::from(":20221012T100000Z")),
+                Some(String::from(";VALUE=DATE:20221012"))
+            ),
+            (
+                Some(String::from(";VALUE=DATE:20221012")),
+                Some(String::from(";VALUE=DATE:20221012"))
+            )
+        );
+    }
+
+    #[test]
+    fn card_as_todo() {
+        let mut calendar = Calendar::new("my board");
+        assert!(calendar.add_card(&<Details as MockDetails>::mock(
+            "my-id",
+            "my, title",
+            "2022-10-12T10:00:00.000Z"
+        )));
+        let expected = concat!(
+            "BEGIN:VCALENDAR\r\n",
+            "VERSION:2.0\r\n",
+            "PRODID:-//wekan-cli//EN\r\n",
+            "CALSCALE:GREGORIAN\r\n",
+            "X-WR-CALNAME:my board\r\n",
+            "BEGIN:VTODO\r\n",
+            "UID:my-id@wekan-cli\r\n",
+            "DTSTAMP:20221012T100000Z\r\n",
+            "SUMMARY:my\\, title\r\n",
+            "DUE:20221012T100000Z\r\n",
+            "COMPLETED:20221012T100000Z\r\n",
+            "STATUS:COMPLETED\r\n",
+            "END:VTODO\r\n",
+            "END:VCALENDAR\r\n",
+        );
+        assert_eq!(calendar.to_ics(), expected);
+        assert_eq!(calendar.len(), 1);
+    }
+}
@@ -1,13 +1,15 @@
 use crate::{
+    card::filter::Selection,
     command::{
         ArgumentRequester, ArtifactName, CommonCommandRequester, CreateSubcommand,
         SubCommandValidator,
     },
     error::{CliError, Error, Transform},
-    subcommand::{Archive, CommonCommand, Details, Inspect, List, Remove},
+    subcommand::{CommonCommand, Details, Inspect, List, Remove},
 };
 use chrono::prelude::*;
 use clap::{Args as ClapArgs, Subcommand};
+use std::path::PathBuf;
 use wekan_cli_derive::WekanArgs;
 use wekan_common::http::common::Create;
 
@@ -21,7 +23,13 @@ pub struct Args {
     pub name: Option<String>,
     #[clap(short = 'b', long, help = "Board name")]
     pub board: String,
-    #[clap(short = 'l', long, help = "List name")]
+    #[clap(
+        short = 'l',
+        long,
+        default_value = "",
+        hide_default_value = true,
+        help = "List name"
+    )]
     pub list: String,
     #[clap(short, long, parse(from_flag), help = "Show the details of the object")]
     raw: bool,
@@ -35,7 +43,9 @@ impl CommonCommandRequester<Command> for Args {
         match &self.command {
             Some(c) => match c {
                 Command::Ls(ls) => Some(CommonCommand::Ls(ls.to_owned())),
-                Command::Remove(r) => Some(CommonCommand::Remove(r.to_owned())),
+                Command::Remove(r) => Some(CommonCommand::Remove(Remove {
+                    yes: r.selection.yes,
+                })),
                 Command::Inspect(i) => Some(CommonCommand::Inspect(i.to_owned())),
                 _ => None,
             },
@@ -50,14 +60,63 @@ pub enum Command {
     Ls(List),
     Create(CardCreateArgs),
     #[clap(name = "rm")]
-    Remove(Remove),
+    Remove(CardRemoveArgs),
     #[clap(name = "update")]
     Update(UpdateArgs),
     #[clap(name = "mv")]
     Move(CardMoveArgs),
-    Archive(Archive),
+    Cp(CardCopyArgs),
+    Archive(CardArchiveArgs),
     Inspect(Inspect),
     Details(Details),
+    Export(ExportArgs),
+    Import(ImportArgs),
+    Edit(EditArgs),
+}
+
+impl Args {
+    /// Selection of a bulk operation, if any option of it is given.
+    pub fn get_selection(&self) -> Option<&Selection> {
+        let selection = match &self.command {
+            Some(Command::Remove(r)) => &r.selection,
+            Some(Command::Archive(a)) => &a.selection,
+            Some(Command::Move(m)) => &m.selection,
+            Some(Command::Update(u)) => &u.selection,
+            _ => return None,
+        };
+        if selection.is_active() {
+            Some(selection)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Remove cards",
+    long_about = "Remove the card named with '-n' or every card of a selection. Revert it with 'undo' while the local journal has it"
+)]
+pub struct CardRemoveArgs {
+    #[clap(flatten)]
+    pub selection: Selection,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Archive cards",
+    long_about = "Archive or restore the card named with '-n' or every card of a selection"
+)]
+pub struct CardArchiveArgs {
+    #[clap(
+        short = 'r',
+        long,
+        parse(from_flag),
+        help = "Restore an archived artifact"
+    )]
+    pub restore: bool,
+    #[clap(flatten)]
+    pub selection: Selection,
 }
 
 #[derive(ClapArgs, Debug, Clone)]
@@ -68,7 +127,7 @@ pub enum Command {
 pub struct CardCreateArgs {
     /// Card name
     title: String,
-    #[clap(short = 'd', long)]
+    #[clap(short = 'd', long, default_value = "", hide_default_value = true)]
     description: String,
     #[clap(short, long)]
     swimlane_name: Option<String>,
@@ -85,11 +144,125 @@ impl Create for CardCreateArgs {
 
 #[derive(ClapArgs, Debug, Clone)]
 #[clap(
-    about = "Move card to the next list",
-    long_about = "Move a card between list of the same board"
+    about = "Move card to another list",
+    long_about = "Move a card to another list, swimlane or board and place it at a position"
 )]
 pub struct CardMoveArgs {
-    pub list: String,
+    /// Destination list, same as '--to-list'
+    pub list: Option<String>,
+    #[clap(long, help = "Destination list")]
+    pub to_list: Option<String>,
+    #[clap(long, help = "Destination board, by default the current board")]
+    pub to_board: Option<String>,
+    #[clap(
+        long,
+        help = "Destination swimlane, by default 'Default' on another board"
+    )]
+    pub swimlane: Option<String>,
+    #[clap(
+        long,
+        help = "Position in the destination list: top, bottom or the 1-based index"
+    )]
+    pub position: Option<Position>,
+    #[clap(flatten)]
+    pub selection: Selection,
+}
+
+impl CardMoveArgs {
+    pub fn get_list(&self) -> Result<String, Error> {
+        match self.to_list.as_ref().or(self.list.as_ref()) {
+            Some(l) => Ok(l.to_owned()),
+            None => Err(CliError::new_msg("Destination list needs to be supplied").as_enum()),
+        }
+    }
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Copy card",
+    long_about = "Create a copy of a card with description, dates, labels and checklists"
+)]
+pub struct CardCopyArgs {
+    /// Title of the copy, by default the title of the copied card
+    pub title: Option<String>,
+    #[clap(long, help = "Destination list, by default the current list")]
+    pub to_list: Option<String>,
+    #[clap(long, help = "Destination board, by default the current board")]
+    pub to_board: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Position {
+    Top,
+    Bottom,
+    Index(usize),
+}
+
+impl std::str::FromStr for Position {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(Position::Top),
+            "bottom" => Ok(Position::Bottom),
+            n => match n.parse::<usize>() {
+                Ok(i) if i > 0 => Ok(Position::Index(i)),
+                _ => Err(String::from(
+                    "Position needs to be top, bottom or a number from 1",
+                )),
+            },
+        }
+    }
+}
+
+impl Position {
+    /// Sort value to place a card at the position between cards with the given sort values.
+    pub fn sort(&self, sorts: &[f32]) -> f32 {
+        let mut sorts = sorts.to_vec();
+        sorts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        match (self, sorts.first(), sorts.last()) {
+            (_p, None, _l) => 0.0,
+            (Position::Top, Some(f), _l) | (Position::Index(1), Some(f), _l) => f - 1.0,
+            (Position::Bottom, _f, Some(l)) => l + 1.0,
+            (Position::Index(i), _f, Some(l)) if *i > sorts.len() => l + 1.0,
+            (Position::Index(i), _f, _l) => (sorts[i - 2] + sorts[i - 1]) / 2.0,
+            (Position::Bottom, _f, None) => 0.0,
+        }
+    }
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Export cards",
+    long_about = "Export all cards of a board or of one list with '-l'"
+)]
+pub struct ExportArgs {
+    #[clap(long, parse(from_flag), help = "Export as CSV instead of YAML")]
+    pub csv: bool,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Edit card in your editor",
+    long_about = "Open title, description, dates, labels and sort in $EDITOR and update the changed fields"
+)]
+pub struct EditArgs {
+    #[clap(
+        long,
+        parse(from_flag),
+        help = "Update even if the card was changed on the server while editing"
+    )]
+    pub force: bool,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Import cards from CSV",
+    long_about = "Create or update cards row by row. Existing cards are matched by their exact title in every list of the board and moved to the list of the row. Labels are matched by name and need to exist on the board"
+)]
+pub struct ImportArgs {
+    /// CSV file with the columns of 'card export --csv'
+    pub file: PathBuf,
 }
 
 #[derive(ClapArgs, Debug, Clone)]
@@ -110,8 +283,10 @@ pub struct UpdateArgs {
     pub due_at: Option<String>,
     #[clap(short, long, validator = valid_time, help = "Format: Gregorian in format (YYYY-MM-DD)")]
     pub end_at: Option<String>,
+    #[clap(flatten)]
+    pub selection: Selection,
 }
-fn valid_time(s: &str) -> Result<Date<Utc>, String> {
+pub fn valid_time(s: &str) -> Result<Date<Utc>, String> {
     if s.len() > 10 {
         Err(String::from("Day format is too long"))
     } else {
@@ -121,3 +296,74 @@ fn valid_time(s: &str) -> Result<Date<Utc>, String> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn position_sort() {
+        assert_eq!(Position::from_str("top"), Ok(Position::Top));
+        assert_eq!(Position::from_str("2"), Ok(Position::Index(2)));
+        assert!(Position::from_str("0").is_err());
+        let sorts = [2.0, 0.0, 1.0];
+        assert_eq!(Position::Top.sort(&sorts), -1.0);
+        assert_eq!(Position::Bottom.sort(&sorts), 3.0);
+        assert_eq!(Position::Index(1).sort(&sorts), -1.0);
+        assert_eq!(Position::Index(2).sort(&sorts), 0.5);
+        assert_eq!(Position::Index(4).sort(&sorts), 3.0);
+        assert_eq!(Position::Bottom.sort(&[]), 0.0);
+    }
+
+    #[test]
+    fn bulk_selection() {
+        use crate::command::{Subcommand, WekanParser};
+        use clap::Parser;
+        let parse = |args: &[&str]| match WekanParser::try_parse_from(args).unwrap().command {
+            Subcommand::Card(c) => c,
+            _ => panic!("Not a card"),
+        };
+        let args = parse(&[
+            "wekan-cli",
+            "card",
+            "-b",
+            "B",
+            "-l",
+            "Done",
+            "archive",
+            "--older-than",
+            "30d",
+        ]);
+        let selection = args.get_selection().unwrap();
+        assert_eq!(
+            selection.older_than,
+            Some(std::time::Duration::from_secs(30 * 24 * 60 * 60))
+        );
+        let args = parse(&[
+            "wekan-cli",
+            "card",
+            "-b",
+            "B",
+            "mv",
+            "--label",
+            "bug",
+            "--to-list",
+            "Triage",
+            "-y",
+        ]);
+        assert!(args.get_selection().unwrap().yes);
+        let args = parse(&["wekan-cli", "card", "-b", "B", "-l", "L", "-n", "C", "rm"]);
+        assert!(args.get_selection().is_none());
+        assert!(WekanParser::try_parse_from([
+            "wekan-cli",
+            "card",
+            "-b",
+            "B",
+            "update",
+            "--where",
+            "colour = red"
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    card::argument::valid_time,
+    error::{CliError, Error, Transform},
+};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use wekan_common::{
+    artifact::{
+        card::Details,
+        common::{Base, MostDetails, SortedArtifact},
+    },
+    http::card::UpdateCard,
+};
+
+/// The editable fields of a card as YAML front matter, followed by the description.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Document {
+    pub title: String,
+    #[serde(default)]
+    pub due_at: String,
+    #[serde(default)]
+    pub end_at: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub sort: f32,
+    #[serde(skip)]
+    pub description: String,
+}
+
+impl Document {
+    pub fn new(details: &Details, labels: Vec<String>) -> Self {
+        Self {
+            title: details.get_title(),
+            due_at: details.get_due_at(),
+            end_at: details.get_end_at(),
+            labels,
+            sort: *details.get_sort(),
+            description: details.get_description(),
+        }
+    }
+
+    pub fn to_text(&self) -> Result<String, Error> {
+        let front_matter = serde_yaml::to_string(self)?;
+        Ok(format!(
+            "---\n{}\n---\n{}\n",
+            front_matter.trim_start_matches("---\n").trim_end(),
+            self.description
+        ))
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, Error> {
+        let rest = match text.trim_start().strip_prefix("---\n") {
+            Some(r) => r,
+            None => return Err(CliError::new_msg("Front matter has to start with '---'").as_enum()),
+        };
+        let (front_matter, description) = match rest.split_once("\n---") {
+            Some((f, d)) => (f, d.split_once('\n').map(|d| d.1).unwrap_or("")),
+            None => return Err(CliError::new_msg("Front matter has to end with '---'").as_enum()),
+        };
+        let mut document = serde_yaml::from_str::<Document>(front_matter)?;
+        document.description = description.trim_start_matches('\n').trim_end().to_string();
+        Ok(document)
+    }
+
+    /// Only fields which differ from this document are set, None if nothing changed.
+    pub fn changes(&self, edited: &Document, id: &str) -> Option<UpdateCard> {
+        let changed = |original: &String, edited: &String| {
+            if original.trim_end() == edited.trim_end() {
+                None
+            } else {
+                Some(edited.to_owned())
+            }
+        };
+        let update = UpdateCard {
+            _id: id.to_string(),
+            title: changed(&self.title, &edited.title),
+            description: changed(&self.description, &edited.description),
+            due_at: changed(&self.due_at, &edited.due_at),
+            end_at: changed(&self.end_at, &edited.end_at),
+            sort: if (self.sort - edited.sort).abs() > f32::EPSILON {
+                Some(edited.sort)
+            } else {
+                None
+            },
+            labels: if self.labels != edited.labels {
+                Some(edited.labels.to_owned())
+            } else {
+                None
+            },
+            assignees: None,
+        };
+        match update {
+            UpdateCard {
+                title: None,
+                description: None,
+                due_at: None,
+                end_at: None,
+                sort: None,
+                labels: None,
+                ..
+            } => None,
+            u => Some(u),
+        }
+    }
+}
+
+/// Changed dates have to be days as for 'update' or timestamps as Wekan returns them.
+pub fn check_dates(update: &UpdateCard) -> Result<(), String> {
+    for (field, date) in [("due_at", &update.due_at), ("end_at", &update.end_at)] {
+        if let Some(d) = date {
+            if DateTime::parse_from_rfc3339(d).is_err() {
+                if let Err(e) = valid_time(d) {
+                    return Err(format!("{}: {}", field, e));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wekan_common::artifact::tests::MockDetails;
+
+    fn document() -> Document {
+        let details =
+            <Details as MockDetails>::mock("my-id", "my-title", "2022-10-12T10:00:00.000Z");
+        Document::new(&details, vec![String::from("bug")])
+    }
+
+    #[test]
+    fn write_and_read_document() {
+        let mut document = document();
+        document.description = String::from("first line\n\n- second line");
+        let text = document.to_text().unwrap();
+        assert!(text.starts_with("---\ntitle: my-title\n"));
+        assert!(text.ends_with("\n---\nfirst line\n\n- second line\n"));
+        assert_eq!(Document::from_text(&text).unwrap(), document);
+        assert!(Document::from_text("title: no front matter").is_err());
+    }
+
+    #[test]
+    fn only_changed_fields() {
+        let original = document();
+        assert_eq!(original.changes(&original.clone(), "my-id"), None);
+        let mut edited = original.clone();
+        edited.title = String::from("new title");
+        edited.labels.push(String::from("urgent"));
+        let update = original.changes(&edited, "my-id").unwrap();
+        assert_eq!(update.title, Some(String::from("new title")));
+        assert_eq!(
+            update.labels,
+            Some(vec![String::from("bug"), String::from("urgent")])
+        );
+        assert_eq!(update.description, None);
+        assert_eq!(update.due_at, None);
+        assert_eq!(update.sort, None);
+    }
+
+    #[test]
+    fn check_edited_dates() {
+        let original = document();
+        let mut edited = original.clone();
+        edited.due_at = String::from("2022-11-01");
+        edited.end_at = String::from("2022-11-02T10:00:00.000Z");
+        assert!(check_dates(&original.changes(&edited, "my-id").unwrap()).is_ok());
+        edited.due_at = String::from("tomorrow");
+        assert!(check_dates(&original.changes(&edited, "my-id").unwrap()).is_err());
+        edited.due_at = String::new();
+        assert!(check_dates(&original.changes(&edited, "my-id").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{
+    search::{datetime, Hit},
+    watch::parse_duration,
+};
+use chrono::{DateTime, Utc};
+use clap::Args as ClapArgs;
+use std::{str::FromStr, time::Duration};
+use wekan_common::artifact::common::{BaseDetails, MostDetails};
+
+/// Selects the cards of a bulk operation. Without any option the card named with '-n' is used.
+#[derive(ClapArgs, Debug, Clone, Default)]
+pub struct Selection {
+    #[clap(
+        long = "where",
+        help = "Filter expression, e.g. \"label = bug and due_at < 2022-10-12\""
+    )]
+    pub expression: Option<Expression>,
+    #[clap(long, help = "Select cards with this label name or color")]
+    pub label: Option<String>,
+    #[clap(
+        long,
+        parse(try_from_str = parse_duration),
+        help = "Select cards not modified within this time, e.g. 30d"
+    )]
+    pub older_than: Option<Duration>,
+    #[clap(long, help = "Select every card of this list")]
+    pub all_in_list: Option<String>,
+    #[clap(
+        short = 'y',
+        long,
+        parse(from_flag),
+        help = "Change the selected cards without confirmation"
+    )]
+    pub yes: bool,
+}
+
+impl Selection {
+    pub fn is_active(&self) -> bool {
+        self.expression.is_some()
+            || self.label.is_some()
+            || self.older_than.is_some()
+            || self.all_in_list.is_some()
+    }
+
+    /// Hits need details and label names.
+    pub fn matches(&self, hit: &Hit, now: DateTime<Utc>) -> bool {
+        let expression = match &self.expression {
+            Some(e) => e.matches(hit, now),
+            None => true,
+        };
+        let label = match &self.label {
+            Some(l) => hit.labels.contains(l),
+            None => true,
+        };
+        let older = match (self.older_than, &hit.details) {
+            (Some(d), Some(details)) => {
+                match (
+                    chrono::Duration::from_std(d),
+                    datetime(&details.get_modified_at()),
+                ) {
+                    (Ok(d), Some(modified_at)) => modified_at < now - d,
+                    _ => false,
+                }
+            }
+            (Some(_d), None) => false,
+            (None, _details) => true,
+        };
+        let list = match &self.all_in_list {
+            Some(l) => &hit.list.title == l,
+            None => true,
+        };
+        expression && label && older && list
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Description,
+    Label,
+    Assignee,
+    Member,
+    DueAt,
+    EndAt,
+    CreatedAt,
+    ModifiedAt,
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "title" => Ok(Field::Title),
+            "description" => Ok(Field::Description),
+            "label" => Ok(Field::Label),
+            "assignee" => Ok(Field::Assignee),
+            "member" => Ok(Field::Member),
+            "due_at" => Ok(Field::DueAt),
+            "end_at" => Ok(Field::EndAt),
+            "created_at" => Ok(Field::CreatedAt),
+            "modified_at" => Ok(Field::ModifiedAt),
+            f => Err(format!(
+                "Unknown field '{}', use one of title, description, label, assignee, member, due_at, end_at, created_at, modified_at",
+                f
+            )),
+        }
+    }
+}
+
+impl Field {
+    fn is_date(&self) -> bool {
+        matches!(
+            self,
+            Field::DueAt | Field::EndAt | Field::CreatedAt | Field::ModifiedAt
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Contains,
+    Lt,
+    Gt,
+}
+
+/// One comparison like 'due_at < +7d'. Dates are compared with YYYY-MM-DD or relative to now
+/// with a signed duration, text case insensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    pub field: Field,
+    pub operator: Operator,
+    pub value: String,
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let i = match s.find(|c| "!=~<>".contains(c)) {
+            Some(i) => i,
+            None => return Err(format!("No operator in '{}', use =, !=, ~, < or >", s)),
+        };
+        let (operator, length) = match &s[i..] {
+            o if o.starts_with("!=") => (Operator::Ne, 2),
+            o if o.starts_with('=') => (Operator::Eq, 1),
+            o if o.starts_with('~') => (Operator::Contains, 1),
+            o if o.starts_with('<') => (Operator::Lt, 1),
+            o if o.starts_with('>') => (Operator::Gt, 1),
+            _ => return Err(format!("No operator in '{}', use =, !=, ~, < or >", s)),
+        };
+        let field = Field::from_str(s[..i].trim())?;
+        let value = s[i + length..]
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string();
+        if field.is_date() {
+            if operator == Operator::Contains {
+                return Err(format!("Dates can not be compared with '~' in '{}'", s));
+            }
+            if instant(&value, Utc::now()).is_none() {
+                return Err(format!(
+                    "Not a date in '{}', use YYYY-MM-DD or a duration like -30d",
+                    s
+                ));
+            }
+        } else if matches!(operator, Operator::Lt | Operator::Gt) {
+            return Err(format!(
+                "Only dates can be compared with '<' or '>' in '{}'",
+                s
+            ));
+        }
+        Ok(Self {
+            field,
+            operator,
+            value,
+        })
+    }
+}
+
+impl Condition {
+    pub fn matches(&self, hit: &Hit, now: DateTime<Utc>) -> bool {
+        let details = match &hit.details {
+            Some(d) => d,
+            None => return self.field == Field::Title && self.text(&hit.card.title),
+        };
+        match self.field {
+            Field::Title => self.text(&hit.card.title),
+            Field::Description => self.text(&details.get_description()),
+            Field::Label => self.any(&hit.labels),
+            Field::Assignee => self.any(&details.get_assignees()),
+            Field::Member => self.any(&details.get_members()),
+            Field::DueAt => self.date(&details.get_due_at(), now),
+            Field::EndAt => self.date(&details.get_end_at(), now),
+            Field::CreatedAt => self.date(&details.get_created_at(), now),
+            Field::ModifiedAt => self.date(&details.get_modified_at(), now),
+        }
+    }
+
+    fn text(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        let value = self.value.to_lowercase();
+        match self.operator {
+            Operator::Eq => text == value,
+            Operator::Ne => text != value,
+            Operator::Contains => text.contains(&value),
+            Operator::Lt | Operator::Gt => false,
+        }
+    }
+
+    /// Equal if one of the values is equal, not equal if none is.
+    fn any(&self, values: &[String]) -> bool {
+        match self.operator {
+            Operator::Ne => !values.iter().any(|v| v.eq_ignore_ascii_case(&self.value)),
+            _ => values.iter().any(|v| self.text(v)),
+        }
+    }
+
+    /// Days are equal for '=' and '!=', cards without the date only match '!='.
+    fn date(&self, date: &str, now: DateTime<Utc>) -> bool {
+        match (datetime(date), instant(&self.value, now)) {
+            (Some(d), Some(v)) => match self.operator {
+                Operator::Eq => d.date_naive() == v.date_naive(),
+                Operator::Ne => d.date_naive() != v.date_naive(),
+                Operator::Lt => d < v,
+                Operator::Gt => d > v,
+                Operator::Contains => false,
+            },
+            _ => self.operator == Operator::Ne,
+        }
+    }
+}
+
+/// A date or a duration with sign relative to now, e.g. -30d.
+fn instant(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let relative = |d: &str| {
+        parse_duration(d)
+            .ok()
+            .and_then(|d| chrono::Duration::from_std(d).ok())
+    };
+    match value.chars().next() {
+        Some('+') => relative(&value[1..]).and_then(|d| now.checked_add_signed(d)),
+        Some('-') => relative(&value[1..]).and_then(|d| now.checked_sub_signed(d)),
+        _ => datetime(value),
+    }
+}
+
+/// Conditions joined with 'and'.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Expression {
+    pub conditions: Vec<Condition>,
+}
+
+impl FromStr for Expression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(String::from("Filter expression is empty"));
+        }
+        let conditions = s
+            .split(" and ")
+            .map(Condition::from_str)
+            .collect::<Result<Vec<Condition>, String>>()?;
+        Ok(Self { conditions })
+    }
+}
+
+impl Expression {
+    pub fn matches(&self, hit: &Hit, now: DateTime<Utc>) -> bool {
+        self.conditions.iter().all(|c| c.matches(hit, now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wekan_common::artifact::{card::Details, common::Artifact, tests::MockDetails};
+
+    fn hit(title: &str, list: &str, due: &str) -> Hit {
+        let mut details = <Details as MockDetails>::mock(title, title, due);
+        details.assignees = Some(vec![String::from("me-id")]);
+        Hit {
+            board: <Artifact as MockDetails>::mock("b", "Work", "board"),
+            list: <Artifact as MockDetails>::mock(list, list, "list"),
+            card: <Artifact as MockDetails>::mock(title, title, "card"),
+            details: Some(details),
+            labels: vec![String::from("bug")],
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2022-10-12T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    }
+
+    #[test]
+    fn parse_expression() {
+        let e = Expression::from_str("label = bug and due_at < '2022-10-12'").unwrap();
+        assert_eq!(
+            e.conditions,
+            vec![
+                Condition {
+                    field: Field::Label,
+                    operator: Operator::Eq,
+                    value: String::from("bug"),
+                },
+                Condition {
+                    field: Field::DueAt,
+                    operator: Operator::Lt,
+                    value: String::from("2022-10-12"),
+                },
+            ]
+        );
+        assert_eq!(
+            Condition::from_str("title != x").unwrap().operator,
+            Operator::Ne
+        );
+        assert!(Expression::from_str("").is_err());
+        assert!(Expression::from_str("title x").is_err());
+        assert!(Expression::from_str("colour = red").is_err());
+        assert!(Expression::from_str("title < x").is_err());
+        assert!(Expression::from_str("due_at ~ 2022").is_err());
+        assert!(Expression::from_str("due_at < soon").is_err());
+    }
+
+    #[test]
+    fn match_conditions() {
+        let late = hit("Write tests", "Todo", "2022-10-10T10:00:00.000Z");
+        let soon = hit("Review", "Done", "2022-10-14T10:00:00.000Z");
+        let matches = |e: &str, h: &Hit| Expression::from_str(e).unwrap().matches(h, now());
+        assert!(matches("title ~ WRITE", &late));
+        assert!(!matches("title = write", &late));
+        assert!(matches("label = bug and assignee = me-id", &late));
+        assert!(matches("label != urgent", &late));
+        assert!(matches("due_at < -1d", &late));
+        assert!(!matches("due_at < -1d", &soon));
+        assert!(matches("due_at < +7d and due_at > 2022-10-13", &soon));
+        assert!(matches("due_at = 2022-10-14", &soon));
+        let mut empty = hit("Idle", "Todo", "");
+        empty.details.as_mut().unwrap().end_at = None;
+        assert!(matches("end_at != 2022-10-14", &empty));
+        assert!(!matches("end_at < 2022-10-14", &empty));
+    }
+
+    #[test]
+    fn select_cards() {
+        let card = hit("Write tests", "Done", "2022-10-10T10:00:00.000Z");
+        let mut selection = Selection::default();
+        assert!(!selection.is_active());
+        selection.all_in_list = Some(String::from("Done"));
+        selection.label = Some(String::from("bug"));
+        assert!(selection.is_active());
+        assert!(selection.matches(&card, now()));
+        selection.older_than = Some(Duration::from_secs(24 * 60 * 60));
+        assert!(selection.matches(&card, now()));
+        selection.all_in_list = Some(String::from("Todo"));
+        assert!(!selection.matches(&card, now()));
+    }
+}
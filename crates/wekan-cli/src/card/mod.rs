@@ -1,2 +1,5 @@
 pub mod argument;
+pub mod edit;
+pub mod filter;
+pub mod record;
 pub mod runner;
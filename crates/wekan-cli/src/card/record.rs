@@ -0,0 +1,181 @@
+use crate::error::{CliError, Error, Transform};
+use serde::{Deserialize, Serialize};
+use wekan_common::artifact::{board::Label, common::IdReturner};
+
+/// One card as a flat row for spreadsheets.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Record {
+    pub title: String,
+    pub list: String,
+    #[serde(default)]
+    pub swimlane: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub due_at: String,
+    #[serde(default)]
+    pub end_at: String,
+    #[serde(default)]
+    pub labels: String,
+    #[serde(default)]
+    pub assignees: String,
+}
+
+pub trait Csv: Sized {
+    fn to_csv(&self) -> Result<String, Error>;
+    fn from_csv(content: &[u8]) -> Result<Self, Error>;
+}
+
+impl Csv for Vec<Record> {
+    fn to_csv(&self) -> Result<String, Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for r in self {
+            if let Err(e) = writer.serialize(r) {
+                return Err(CliError::new_msg(&format!("Failed to write csv: {}", e)).as_enum());
+            }
+        }
+        match writer.into_inner() {
+            Ok(v) => Ok(String::from_utf8_lossy(&v).to_string()),
+            Err(_e) => Err(CliError::new_msg("Failed to write csv").as_enum()),
+        }
+    }
+
+    fn from_csv(content: &[u8]) -> Result<Self, Error> {
+        let mut reader = csv::Reader::from_reader(content);
+        let mut records = Vec::new();
+        for (i, r) in reader.deserialize::<Record>().enumerate() {
+            match r {
+                Ok(r) => records.push(r),
+                Err(e) => {
+                    return Err(CliError::new_msg(&format!(
+                        "Row {} can not be parsed: {}",
+                        i + 1,
+                        e
+                    ))
+                    .as_enum())
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+impl Record {
+    pub fn get_optional(value: &str) -> Option<String> {
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    }
+}
+
+/// Labels are shown by name, by color if unnamed and by id if unknown.
+pub fn label_names(label_ids: &[String], labels: &[Label]) -> Vec<String> {
+    label_ids
+        .iter()
+        .map(|id| match labels.iter().find(|l| &l.get_id() == id) {
+            Some(l) if !l.get_name().is_empty() => l.get_name(),
+            Some(l) => l.get_color(),
+            None => id.to_owned(),
+        })
+        .collect()
+}
+
+/// Names and colors of labels, for filters which accept either. Unknown labels by id.
+pub fn label_keys(label_ids: &[String], labels: &[Label]) -> Vec<String> {
+    let mut keys = Vec::new();
+    for id in label_ids {
+        match labels.iter().find(|l| &l.get_id() == id) {
+            Some(l) => {
+                if !l.get_name().is_empty() {
+                    keys.push(l.get_name());
+                }
+                keys.push(l.get_color());
+            }
+            None => keys.push(id.to_owned()),
+        }
+    }
+    keys
+}
+
+/// Looks up labels by name, unnamed labels by color. Returns the ids and the names without a match.
+pub fn label_ids(names: &[String], labels: &[Label]) -> (Vec<String>, Vec<String>) {
+    let mut ids = Vec::new();
+    let mut missing = Vec::new();
+    for name in names {
+        match labels.iter().find(|l| &l.get_name() == name).or_else(|| {
+            labels
+                .iter()
+                .find(|l| l.get_name().is_empty() && &l.get_color() == name)
+        }) {
+            Some(l) => ids.push(l.get_id()),
+            None => missing.push(name.to_owned()),
+        }
+    }
+    (ids, missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read_csv() {
+        let records = vec![Record {
+            title: String::from("Release, part 1"),
+            list: String::from("Todo"),
+            swimlane: String::from("Default"),
+            description: String::from("Say \"hello\"\nand more"),
+            due_at: String::from("2022-10-12"),
+            end_at: String::new(),
+            labels: String::from("bug,urgent"),
+            assignees: String::new(),
+        }];
+        let csv = records.to_csv().unwrap();
+        assert!(csv.starts_with("title,list,swimlane,description,due_at,end_at,labels,assignees\n"));
+        assert!(csv.contains("\"Release, part 1\""));
+        assert_eq!(Vec::<Record>::from_csv(csv.as_bytes()).unwrap(), records);
+    }
+
+    #[test]
+    fn read_csv_with_missing_columns() {
+        let records = Vec::<Record>::from_csv("title,list\nWrite,Todo\n".as_bytes()).unwrap();
+        assert_eq!(records[0].title, "Write");
+        assert_eq!(records[0].due_at, "");
+        assert!(Vec::<Record>::from_csv("list\nTodo\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn find_labels() {
+        let labels: Vec<Label> = serde_json::from_str(concat!(
+            r#"[{"_id":"l1","name":"bug","color":"red"},"#,
+            r#"{"_id":"l2","name":"","color":"green"}]"#
+        ))
+        .unwrap();
+        let names = vec![
+            String::from("green"),
+            String::from("bug"),
+            String::from("urgent"),
+        ];
+        assert_eq!(
+            label_ids(&names, &labels),
+            (
+                vec![String::from("l2"), String::from("l1")],
+                vec![String::from("urgent")]
+            )
+        );
+        assert_eq!(
+            label_names(&[String::from("l2"), String::from("l1")], &labels),
+            vec![String::from("green"), String::from("bug")]
+        );
+        assert_eq!(
+            label_keys(&[String::from("l2"), String::from("l1")], &labels),
+            vec![
+                String::from("green"),
+                String::from("bug"),
+                String::from("red")
+            ]
+        );
+    }
+}
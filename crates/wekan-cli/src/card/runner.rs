@@ -1,22 +1,38 @@
 use crate::{
-    card::argument::{Args, CardMoveArgs as Move, Command, UpdateArgs as Update},
+    archive,
+    card::{
+        argument::{
+            Args, CardArchiveArgs as Archive, CardCopyArgs as Copy, CardMoveArgs as Move, Command,
+            EditArgs as Edit, ExportArgs as Export, ImportArgs as Import, Position,
+            UpdateArgs as Update,
+        },
+        edit::{check_dates, Document},
+        filter::Selection,
+        record::{label_ids, label_keys, label_names, Csv, Record},
+    },
     command::{
         Args as RArgs, ArgumentRequester, ArtifactName, CreateSubcommand, Fulfillment, Operator,
         RootCommandRunner,
     },
     display::CliDisplay,
-    error::{CliError, Error, Transform},
+    error::{failed, CliError, Error, Transform},
+    journal::{self, CardState, Change},
+    markup::{CardDocument, Markup},
+    prompt::approve,
     resolver::Query,
     result::WekanResult,
-    subcommand::{Archive, Inspect},
+    search::{scan, scan_lists, Hit},
+    snapshot::{Restore, Snapshot},
+    subcommand::Inspect,
 };
 use async_trait::async_trait;
 use chrono::{SecondsFormat, Utc};
 use log::{info, trace};
 use wekan_common::{
     artifact::{
+        board::{Details as BDetails, Label},
         card::Details,
-        common::{AType, MostDetails},
+        common::{AType, Artifact, BaseDetails, IdReturner, MostDetails, SortedArtifact},
     },
     http::{
         artifact::ResponseOk,
@@ -24,7 +40,10 @@ use wekan_common::{
     },
     validation::{authentication::TokenHeader, constraint::CardConstraint as Constraint},
 };
-use wekan_core::client::{CardApi, Client};
+use wekan_core::{
+    client::{BoardApi, CardApi, Client},
+    error::Error as CoreError,
+};
 
 #[cfg(test)]
 use crate::tests::mocks::{Artifacts, Operation};
@@ -47,13 +66,20 @@ pub struct Runner<'a> {
 impl<'a> RootCommandRunner<'a, Details, Command> for Runner<'a> {
     async fn use_specific_command(&mut self) -> Result<WekanResult, Error> {
         info!("use_specific_command");
+        if let Some(selection) = self.args.get_selection().cloned() {
+            return self.run_bulk(&selection).await;
+        }
         match self.args.command.to_owned() {
             Some(c) => match c {
                 Command::Details(_d) => self.run_details().await,
                 Command::Update(u) => self.run_update(&u).await,
                 Command::Move(m) => self.run_move(&m).await,
+                Command::Cp(c) => self.run_copy(&c).await,
                 Command::Create(c) => self.use_create(&c).await,
                 Command::Archive(a) => self.run_archive(&a).await,
+                Command::Export(e) => self.run_export(&e).await,
+                Command::Import(i) => self.run_import(&i).await,
+                Command::Edit(e) => self.run_edit(&e).await,
                 _ => self.use_common_command().await,
             },
             None => CliError::new_msg("Subcommand not implemented").err(),
@@ -91,7 +117,7 @@ impl<'a> RootCommandRunner<'a, Details, Command> for Runner<'a> {
                         "Move card or update card",
                     )
                     .ok(),
-                    Err(_e) => CliError::new_msg("Failed to create").err(),
+                    Err(e) => Err(failed("Failed to create", e)),
                 }
             }
             Err(_e) => CliError::new_msg("List can not be matched to swimlane").err(),
@@ -101,7 +127,7 @@ impl<'a> RootCommandRunner<'a, Details, Command> for Runner<'a> {
         match &inspect.delegate.board_id {
             Some(b_id) => match &inspect.delegate.list_id {
                 Some(l_id) => {
-                    self.client.set_base(b_id, l_id);
+                    CardApi::set_base(&mut self.client, b_id, l_id);
                     self.get_one::<Details>(&inspect.id).await
                 }
                 None => WekanResult::new_msg("List id needs to be supplied").ok(),
@@ -118,9 +144,21 @@ impl<'a> Operator<'a> for Runner<'a> {
             .find_card_id(&self.constraint.board._id, &self.constraint.list._id, name)
             .await
     }
+    fn get_path(&self, name: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.constraint.board.title, self.constraint.list.title, name
+        )
+    }
     fn get_type(&self) -> AType {
         AType::Card
     }
+    fn get_location(&self, _id: &str) -> (String, String) {
+        (
+            self.constraint.board._id.to_owned(),
+            self.constraint.list._id.to_owned(),
+        )
+    }
 
     fn get_children_type(&self) -> AType {
         AType::Empty
@@ -151,8 +189,20 @@ impl<'a> Runner<'a> {
     async fn run_details(&mut self) -> Result<WekanResult, Error> {
         info!("run_details");
         let id = self.unwrap_and_find_id(self.args.name.to_owned()).await?;
+        if let Some(m) = Markup::from_format(self.global_options.output_format.as_ref()) {
+            let document = CardDocument::collect(
+                &self.client.config,
+                &self.constraint.board._id,
+                &self.constraint.list._id,
+                &id,
+            )
+            .await?;
+            return self.display.format_card_document(&document, &m);
+        }
         match self.get_client().get_one::<Details>(&id).await {
-            Ok(d) => self.get_display().format_card_details(d),
+            Ok(d) => self
+                .get_display()
+                .format_card_details(d, &self.global_options.output_format),
             Err(e) => {
                 trace!("{:?}", e);
                 CliError::new_msg("Failed to request details").err()
@@ -161,74 +211,140 @@ impl<'a> Runner<'a> {
     }
     async fn run_move(&mut self, move_args: &Move) -> Result<WekanResult, Error> {
         info!("run_move");
-        match self
-            .query
-            .find_list_id(&self.constraint.board._id, &move_args.list)
-            .await
-        {
-            Ok(l_id) => {
-                trace!("Found destination list id: {}", l_id);
-                let name = self.args.get_name()?;
-                let id = self.find_details_id(&name).await?;
-                let updated_card = MoveCard {
-                    list_id: l_id,
-                    _id: id,
+        let (board_id, list_id, swimlane_id) = self.destination(move_args).await?;
+        let name = self.args.get_name()?;
+        let id = self.find_details_id(&name).await?;
+        let card = CardState::new(&id, &self.client.get_one::<Details>(&id).await?);
+        let sort = match &move_args.position {
+            Some(p) => Some(place(&mut self.sorts(&board_id, &list_id).await?, &id, p)),
+            None => None,
+        };
+        let updated_card = MoveCard {
+            _id: id,
+            list_id: list_id.to_owned(),
+            board_id: Some(board_id.to_owned()),
+            swimlane_id,
+            sort,
+        };
+        trace!("{:?}", updated_card);
+        match self.client.put::<MoveCard, ResponseOk>(&updated_card).await {
+            Ok(_o) => {
+                let change = Change::Move {
+                    card,
+                    board_id,
+                    list_id,
                 };
-                match self.client.put::<MoveCard, ResponseOk>(&updated_card).await {
-                    Ok(_o) => WekanResult::new_workflow(
-                        "Successfully moved",
-                        "Update card with more details",
-                    )
-                    .ok(),
-                    Err(_e) => CliError::new_msg("Failed to update").err(),
-                }
+                journal::record(&self.client.config, &format!("mv card '{}'", name), change).await;
+                WekanResult::new_workflow("Successfully moved", "Update card with more details")
+                    .ok()
             }
-            _ => CliError::new_msg("Failed to find destination").err(),
+            Err(e) => Err(failed("Failed to update", e)),
         }
     }
 
+    /// Board, list and swimlane ids of a move. The swimlane is only changed if requested or
+    /// if the card moves to another board.
+    async fn destination(
+        &mut self,
+        move_args: &Move,
+    ) -> Result<(String, String, Option<String>), Error> {
+        let list = move_args.get_list()?;
+        let board_id = match &move_args.to_board {
+            Some(b) => match self.query.find_board_id(b).await {
+                Ok(id) => id,
+                Err(_e) => return Err(CliError::new_msg("Destination board not found").as_enum()),
+            },
+            None => self.constraint.board._id.to_owned(),
+        };
+        let list_id = match self.query.find_list_id(&board_id, &list).await {
+            Ok(id) => id,
+            Err(_e) => return Err(CliError::new_msg("Failed to find destination").as_enum()),
+        };
+        trace!("Found destination list id: {}", list_id);
+        let swimlane = match &move_args.swimlane {
+            Some(s) => Some(self.query.find_swimlane_id_by_title(&board_id, s).await),
+            None if board_id != self.constraint.board._id => {
+                Some(self.query.find_swimlane_id(&board_id).await)
+            }
+            None => None,
+        };
+        let swimlane_id = match swimlane {
+            Some(Ok(s)) => Some(s),
+            Some(Err(_e)) => {
+                return Err(CliError::new_msg("Destination swimlane not found").as_enum())
+            }
+            None => None,
+        };
+        Ok((board_id, list_id, swimlane_id))
+    }
+
+    async fn run_copy(&mut self, copy_args: &Copy) -> Result<WekanResult, Error> {
+        info!("run_copy");
+        let board_id = match &copy_args.to_board {
+            Some(b) => match self.query.find_board_id(b).await {
+                Ok(id) => id,
+                Err(_e) => return CliError::new_msg("Destination board not found").err(),
+            },
+            None => self.constraint.board._id.to_owned(),
+        };
+        let list = copy_args
+            .to_list
+            .to_owned()
+            .unwrap_or_else(|| self.constraint.list.title.to_owned());
+        let list_id = match self.query.find_list_id(&board_id, &list).await {
+            Ok(id) => id,
+            Err(_e) => return CliError::new_msg("Failed to find destination").err(),
+        };
+        let name = self.args.get_name()?;
+        let id = self.find_details_id(&name).await?;
+        let mut snapshot = Snapshot::collect(
+            self.query,
+            &self.constraint.board._id,
+            &self.constraint.board.title,
+            vec![self.constraint.list.to_owned()],
+            Some(&id),
+        )
+        .await?;
+        if let Some(t) = &copy_args.title {
+            snapshot.cards[0].title = t.to_owned();
+        }
+        let mut restore = Restore::new(self.client.config.clone(), &self.client.get_user_id());
+        restore.map(&self.constraint.list._id, &list_id);
+        let swimlane_id = restore
+            .map_swimlanes(self.query, &snapshot, &board_id)
+            .await?;
+        restore
+            .copy_into(&snapshot, &board_id, &swimlane_id)
+            .await?;
+        restore.report(
+            &format!("card '{}' to '{}'", name, snapshot.cards[0].title),
+            &snapshot,
+        )
+    }
+
+    /// Ids and sort values of the cards in a list, requested once per destination.
+    async fn sorts(&mut self, board_id: &str, list_id: &str) -> Result<Sorts, Error> {
+        let cards = self
+            .query
+            .inquire(AType::Card, Some(board_id), Some(list_id), None, true)
+            .await?;
+        let mut client = <Client as CardApi>::new(self.client.config.clone(), board_id, list_id);
+        let mut sorts = Vec::new();
+        for card in cards {
+            let sort = *client.get_one::<Details>(&card._id).await?.get_sort();
+            sorts.push((card._id, sort));
+        }
+        Ok(sorts)
+    }
+
     async fn run_update(&mut self, update_args: &Update) -> Result<WekanResult, Error> {
         info!("run_update");
         let name = self.args.get_name()?;
         match self.find_details_id(&name).await {
             Ok(id) => {
-                let mut description = String::new();
-                match &update_args.description {
-                    Some(d) => {
-                        if d.starts_with("k+|") {
-                            let details = self.client.get_one::<Details>(&id).await.unwrap();
-                            description.push_str(&details.get_description());
-                            description.push('\n');
-                            description.push_str(d.trim_start_matches("k+|"));
-                        } else {
-                            description.push_str(d);
-                        }
-                    }
-                    None => {}
-                };
-                let update_card = UpdateCard {
-                    _id: id.to_owned(),
-                    title: update_args.title.to_owned(),
-                    description: {
-                        if description.is_empty() {
-                            None
-                        } else {
-                            Some(description)
-                        }
-                    },
-                    due_at: update_args.due_at.as_ref().map(|d| d.to_string()),
-                    end_at: update_args.end_at.as_ref().map(|d| d.to_string()),
-                    labels: match &update_args.labels {
-                        Some(l) => {
-                            let v: Vec<&str> = l.split_terminator(',').collect();
-                            let new_v: Vec<String> =
-                                v.iter().map(|&s| s.to_string()).collect::<Vec<String>>();
-                            Some(new_v)
-                        }
-                        None => None,
-                    },
-                    sort: update_args.sort,
-                };
+                let card = CardState::new(&id, &self.client.get_one::<Details>(&id).await?);
+                let current = card.description.to_owned().unwrap_or_default();
+                let update_card = update_card(&id, &current, update_args);
                 trace!("{:?}", update_card);
                 match self
                     .client
@@ -236,10 +352,14 @@ impl<'a> Runner<'a> {
                     .await
                 {
                     Ok(_o) => {
+                        let summary = format!("update card '{}'", name);
+                        journal::record(&self.client.config, &summary, Change::Update { card })
+                            .await;
                         let card = self.client.get_one::<Details>(&id).await.unwrap();
-                        self.display.format_card_details(card)
+                        self.display
+                            .format_card_details(card, &self.global_options.output_format)
                     }
-                    Err(_e) => CliError::new_msg("Failed to update").err(),
+                    Err(e) => Err(failed("Failed to update", e)),
                 }
             }
             Err(_e) => CliError::new_msg("Failed to find card").err(),
@@ -250,18 +370,946 @@ impl<'a> Runner<'a> {
         info!("use_archive");
         // https://github.com/wekan/wekan/issues/3250
         let name = self.args.get_name()?;
+        let id = match archive_args.restore {
+            true => {
+                archive::find_archived_id(
+                    &self.client.config,
+                    &AType::Card,
+                    &self.constraint.board._id,
+                    &self.constraint.list._id,
+                    &name,
+                )
+                .await?
+            }
+            false => self.find_details_id(&name).await?,
+        };
+        let archive_card = archive_card(&id, !archive_args.restore);
+        trace!("{:?}", archive_card);
+        let result = self
+            .use_archive::<ArchiveCard, Details>(&archive_card)
+            .await?;
+        let verb = if archive_args.restore {
+            "restore"
+        } else {
+            "archive"
+        };
+        let change = Change::Archive {
+            r#type: AType::Card,
+            board_id: self.constraint.board._id.to_owned(),
+            parent_id: self.constraint.list._id.to_owned(),
+            id,
+            archive: !archive_args.restore,
+        };
+        journal::record(
+            &self.client.config,
+            &format!("{} card '{}'", verb, name),
+            change,
+        )
+        .await;
+        Ok(result)
+    }
+
+    /// Applies the subcommand to every card of the board, or of the list given with '-l',
+    /// which matches the selection. Removals and more than one card need to be confirmed.
+    async fn run_bulk(&mut self, selection: &Selection) -> Result<WekanResult, Error> {
+        info!("run_bulk");
+        let mut sorts = None;
+        let (bulk, verb, done) = match self.args.command.to_owned() {
+            Some(Command::Remove(_r)) => (Bulk::Remove, "Remove", "removed"),
+            Some(Command::Archive(a)) if a.restore => (Bulk::Archive(false), "Restore", "restored"),
+            Some(Command::Archive(_a)) => (Bulk::Archive(true), "Archive", "archived"),
+            Some(Command::Move(m)) => {
+                let (board_id, list_id, swimlane_id) = self.destination(&m).await?;
+                if m.position.is_some() {
+                    sorts = Some(self.sorts(&board_id, &list_id).await?);
+                }
+                let bulk = Bulk::Move {
+                    board_id,
+                    list_id,
+                    swimlane_id,
+                    position: m.position,
+                };
+                (bulk, "Move", "moved")
+            }
+            Some(Command::Update(u)) => (Bulk::Update(u), "Update", "updated"),
+            _ => return CliError::new_msg("Subcommand does not support a selection").err(),
+        };
+        let hits = match bulk {
+            Bulk::Archive(false) => self.select_archived(selection).await?,
+            _ => self.select(selection).await?,
+        };
+        if hits.is_empty() {
+            return CliError::new_msg("No card matches the selection").err();
+        }
+        let ask = hits.len() > 1 || matches!(bulk, Bulk::Remove);
+        if ask && !selection.yes && !self.client.config.dry_run {
+            eprint!(
+                "{}",
+                hits.iter().map(|h| h.path() + "\n").collect::<String>()
+            );
+            if !approve(&format!("{} {} cards?", verb, hits.len()), false)? {
+                return WekanResult::new_unchanged("Aborted, nothing was changed").ok();
+            }
+        }
+        let mut failed = Vec::new();
+        let mut changes = Vec::new();
+        let mut summary = String::new();
+        for hit in &hits {
+            match self.apply(hit, &bulk, &mut sorts).await {
+                Err(Error::Core(CoreError::DryRun(d))) => eprintln!("{}", d),
+                Err(e) => {
+                    trace!("{:?}", e);
+                    failed.push(hit.path());
+                }
+                Ok((action, change)) => {
+                    summary = match changes.len() {
+                        0 => format!("{} card '{}'", action, hit.card.title),
+                        n => format!("{} {} cards", action, n + 1),
+                    };
+                    changes.push(change);
+                }
+            }
+        }
+        // One undo reverts the whole selection.
+        match changes.len() {
+            0 => {}
+            1 => journal::record(&self.client.config, &summary, changes.remove(0)).await,
+            _ => journal::record(&self.client.config, &summary, Change::Batch { changes }).await,
+        }
+        bulk_report(done, hits.len(), &failed)
+    }
+
+    /// Details are only requested for the cards of the lists in scope.
+    async fn select(&mut self, selection: &Selection) -> Result<Vec<Hit>, Error> {
+        let board_id = self.constraint.board._id.to_owned();
+        let lists = self
+            .query
+            .inquire(AType::List, Some(&board_id), None, None, false)
+            .await?
+            .into_iter()
+            .filter(|l| self.in_scope(l, selection))
+            .collect();
+        let hits = match scan_lists(self.query, &self.constraint.board, lists, true).await {
+            Ok(h) => h,
+            Err(e) => {
+                trace!("{:?}", e);
+                return Err(CliError::new_msg("Failed to read the cards of the board").as_enum());
+            }
+        };
+        let now = Utc::now();
+        Ok(hits
+            .into_iter()
+            .filter(|h| selection.matches(h, now))
+            .collect())
+    }
+
+    /// Archived cards are not listed, they are taken from the export of the board.
+    async fn select_archived(&mut self, selection: &Selection) -> Result<Vec<Hit>, Error> {
+        let board = self.constraint.board.to_owned();
+        let mut board_client = <Client as BoardApi>::new(self.client.config.clone());
+        let (export, labels) = match board_client.get_export(&board._id).await {
+            Ok(e) => (e, board_client.get_one::<BDetails>(&board._id).await?),
+            Err(e) => {
+                trace!("{:?}", e);
+                return Err(CliError::new_msg("Failed to read the archived cards").as_enum());
+            }
+        };
+        let labels = labels.get_labels();
+        let archived = archive::archived_in_export(&export, &board.title);
+        let now = Utc::now();
+        let mut hits = Vec::new();
+        for list in &export.lists {
+            let list = Artifact {
+                _id: list._id.to_owned(),
+                title: list.title.to_owned(),
+                r#type: AType::List,
+            };
+            if !self.in_scope(&list, selection) {
+                continue;
+            }
+            let mut client =
+                <Client as CardApi>::new(self.client.config.clone(), &board._id, &list._id);
+            for card in export.cards.iter().filter(|c| {
+                c.list_id.as_ref() == Some(&list._id)
+                    && archived
+                        .iter()
+                        .any(|a| a.r#type == AType::Card && a.id == c._id)
+            }) {
+                let details = client.get_one::<Details>(&card._id).await?;
+                let hit = Hit {
+                    board: board.to_owned(),
+                    list: list.to_owned(),
+                    card: Artifact {
+                        _id: card._id.to_owned(),
+                        title: card.title.to_owned(),
+                        r#type: AType::Card,
+                    },
+                    labels: label_keys(&details.get_label_ids(), &labels),
+                    details: Some(details),
+                };
+                if selection.matches(&hit, now) {
+                    hits.push(hit);
+                }
+            }
+        }
+        Ok(hits)
+    }
+
+    /// Lists are limited by '-l' and '--all-in-list'.
+    fn in_scope(&self, list: &Artifact, selection: &Selection) -> bool {
+        let named = self.args.list.is_empty() || list._id == self.constraint.list._id;
+        let all_in_list = match &selection.all_in_list {
+            Some(l) => &list.title == l,
+            None => true,
+        };
+        named && all_in_list
+    }
+
+    /// Moved cards are placed with the sort values of the destination, which then include them.
+    /// Returns the action and the change to journal.
+    async fn apply(
+        &mut self,
+        hit: &Hit,
+        bulk: &Bulk,
+        sorts: &mut Option<Sorts>,
+    ) -> Result<(&'static str, Change), Error> {
+        let id = hit.card._id.to_owned();
+        let mut client =
+            <Client as CardApi>::new(self.client.config.clone(), &hit.board._id, &hit.list._id);
+        let card = match &hit.details {
+            Some(d) => CardState::new(&id, d),
+            None => CardState::new(&id, &client.get_one::<Details>(&id).await?),
+        };
+        let (summary, change) = match bulk {
+            Bulk::Remove => {
+                let change = journal::removal(
+                    &self.client.config,
+                    AType::Card,
+                    &hit.board._id,
+                    &hit.list._id,
+                    &id,
+                )
+                .await?;
+                ("rm", change)
+            }
+            Bulk::Archive(archive) => {
+                let change = Change::Archive {
+                    r#type: AType::Card,
+                    board_id: hit.board._id.to_owned(),
+                    parent_id: hit.list._id.to_owned(),
+                    id: id.to_owned(),
+                    archive: *archive,
+                };
+                (if *archive { "archive" } else { "restore" }, change)
+            }
+            Bulk::Move {
+                board_id, list_id, ..
+            } => {
+                let change = Change::Move {
+                    card,
+                    board_id: board_id.to_owned(),
+                    list_id: list_id.to_owned(),
+                };
+                ("mv", change)
+            }
+            Bulk::Update(_u) => ("update", Change::Update { card }),
+        };
+        let response = match bulk {
+            Bulk::Remove => client.delete::<ResponseOk>(&id).await,
+            Bulk::Archive(archive) => {
+                client
+                    .put::<ArchiveCard, ResponseOk>(&archive_card(&id, *archive))
+                    .await
+            }
+            Bulk::Move {
+                board_id,
+                list_id,
+                swimlane_id,
+                position,
+            } => {
+                let sort = match (position, sorts.as_mut()) {
+                    (Some(p), Some(s)) => Some(place(s, &id, p)),
+                    _ => None,
+                };
+                let body = MoveCard {
+                    _id: id,
+                    list_id: list_id.to_owned(),
+                    board_id: Some(board_id.to_owned()),
+                    swimlane_id: swimlane_id.to_owned(),
+                    sort,
+                };
+                client.put::<MoveCard, ResponseOk>(&body).await
+            }
+            Bulk::Update(update_args) => {
+                let current = match &hit.details {
+                    Some(d) => d.get_description(),
+                    None => String::new(),
+                };
+                client
+                    .put::<UpdateCard, ResponseOk>(&update_card(&id, &current, update_args))
+                    .await
+            }
+        };
+        match response {
+            Ok(_o) => Ok((summary, change)),
+            Err(e) => Err(Error::Core(e)),
+        }
+    }
+
+    async fn run_export(&mut self, export_args: &Export) -> Result<WekanResult, Error> {
+        info!("run_export");
+        let board_id = self.constraint.board._id.to_owned();
+        let lists = self
+            .query
+            .inquire(AType::List, Some(&board_id), None, None, false)
+            .await?;
+        let lists: Vec<Artifact> = if self.args.list.is_empty() {
+            lists
+        } else {
+            lists
+                .into_iter()
+                .filter(|l| l._id == self.constraint.list._id)
+                .collect()
+        };
+        let swimlanes = self
+            .query
+            .inquire(AType::Swimlane, Some(&board_id), None, None, false)
+            .await?;
+        let mut board_client = <Client as BoardApi>::new(self.client.config.clone());
+        let labels = match board_client.get_one::<BDetails>(&board_id).await {
+            Ok(b) => b.get_labels(),
+            Err(_e) => Vec::new(),
+        };
+        let mut records = Vec::new();
+        for list in &lists {
+            let cards = self
+                .query
+                .inquire(AType::Card, Some(&board_id), Some(&list._id), None, false)
+                .await?;
+            let mut client =
+                <Client as CardApi>::new(self.client.config.clone(), &board_id, &list._id);
+            for card in cards {
+                let details = match client.get_one::<Details>(&card._id).await {
+                    Ok(d) => d,
+                    Err(e) => {
+                        trace!("{:?}", e);
+                        return CliError::new_msg("Failed to request details").err();
+                    }
+                };
+                let label_names = label_names(&details.get_label_ids(), &labels);
+                records.push(Record {
+                    title: details.title.to_owned(),
+                    list: list.title.to_owned(),
+                    swimlane: match swimlanes
+                        .iter()
+                        .find(|s| s._id == details.get_swimlane_id())
+                    {
+                        Some(s) => s.title.to_owned(),
+                        None => details.get_swimlane_id(),
+                    },
+                    description: details.get_description(),
+                    due_at: details.get_due_at(),
+                    end_at: details.get_end_at(),
+                    labels: label_names.join(","),
+                    assignees: details.assignees.to_owned().unwrap_or_default().join(","),
+                });
+            }
+        }
+        if export_args.csv {
+            WekanResult::new_msg(&records.to_csv()?).ok()
+        } else {
+            WekanResult::new_msg(&serde_yaml::to_string(&records)?).ok()
+        }
+    }
+
+    async fn run_edit(&mut self, edit_args: &Edit) -> Result<WekanResult, Error> {
+        info!("run_edit");
+        let name = self.args.get_name()?;
         let id = self.find_details_id(&name).await?;
-        let mut archive_card = ArchiveCard {
-            _id: id,
-            archive: true,
-            archive_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        let details = self.client.get_one::<Details>(&id).await?;
+        let mut board_client = <Client as BoardApi>::new(self.client.config.clone());
+        let labels = match board_client
+            .get_one::<BDetails>(&self.constraint.board._id)
+            .await
+        {
+            Ok(b) => b.get_labels(),
+            Err(_e) => Vec::new(),
         };
-        if archive_args.restore {
-            archive_card.archive = false;
-            archive_card.archive_at = String::new();
+        let original = Document::new(&details, label_names(&details.get_label_ids(), &labels));
+        let path = tempfile::Builder::new()
+            .prefix(&format!("wekan-card-{}-", id))
+            .suffix(".md")
+            .tempfile()?
+            .into_temp_path();
+        tokio::fs::write(&path, original.to_text()?).await?;
+        let editor = std::env::var("VISUAL")
+            .or_else(|_e| std::env::var("EDITOR"))
+            .unwrap_or_else(|_e| String::from("vi"));
+        let words = match shell_words::split(&editor) {
+            Ok(w) if !w.is_empty() => w,
+            _ => return CliError::new_msg(&format!("Editor '{}' can not be run", editor)).err(),
+        };
+        let status = tokio::process::Command::new(&words[0])
+            .args(&words[1..])
+            .arg(&*path)
+            .status()
+            .await?;
+        if !status.success() {
+            return CliError::new_msg(&format!("Editor '{}' failed", editor)).err();
         }
-        trace!("{:?}", archive_card);
-        self.use_archive::<ArchiveCard, Details>(&archive_card)
+        let edited = match Document::from_text(&tokio::fs::read_to_string(&path).await?) {
+            Ok(d) => d,
+            Err(e) => {
+                trace!("{:?}", e);
+                return CliError::new_msg(&format!(
+                    "Edited card can not be read, your changes are kept in {}",
+                    keep(path)
+                ))
+                .err();
+            }
+        };
+        let mut update_card = match original.changes(&edited, &id) {
+            Some(u) => u,
+            None => return WekanResult::new_msg("Nothing changed").ok(),
+        };
+        if let Err(e) = check_dates(&update_card) {
+            return CliError::new_msg(&format!("{}, your changes are kept in {}", e, keep(path)))
+                .err();
+        }
+        if let Some(names) = &update_card.labels {
+            let (ids, missing) = label_ids(names, &labels);
+            if !missing.is_empty() {
+                return CliError::new_msg(&format!(
+                    "Labels not found on the board: {}, your changes are kept in {}",
+                    missing.join(", "),
+                    keep(path)
+                ))
+                .err();
+            }
+            update_card.labels = Some(ids);
+        }
+        if !edit_args.force {
+            let current = self.client.get_one::<Details>(&id).await?;
+            if current.get_modified_at() != details.get_modified_at() {
+                return CliError::new_msg(&format!(
+                    "Card was changed on the server while editing, your changes are kept in {}. Use '--force' to overwrite.",
+                    keep(path)
+                ))
+                .err();
+            }
+        }
+        trace!("{:?}", update_card);
+        match self
+            .client
+            .put::<UpdateCard, ResponseOk>(&update_card)
+            .await
+        {
+            Ok(_o) => {
+                let change = Change::Update {
+                    card: CardState::new(&id, &details),
+                };
+                journal::record(
+                    &self.client.config,
+                    &format!("edit card '{}'", name),
+                    change,
+                )
+                .await;
+                let card = self.client.get_one::<Details>(&id).await?;
+                self.display
+                    .format_card_details(card, &self.global_options.output_format)
+            }
+            Err(e) => Err(failed(
+                &format!("Failed to update, your changes are kept in {}", keep(path)),
+                e,
+            )),
+        }
+    }
+
+    async fn run_import(&mut self, import_args: &Import) -> Result<WekanResult, Error> {
+        info!("run_import");
+        let content = tokio::fs::read(&import_args.file).await?;
+        let records = Vec::<Record>::from_csv(&content)?;
+        let board_id = self.constraint.board._id.to_owned();
+        let (hits, failed) = scan(
+            self.query,
+            std::slice::from_ref(&self.constraint.board),
+            false,
+        )
+        .await;
+        if failed > 0 {
+            return CliError::new_msg("Failed to read the cards of the board").err();
+        }
+        let mut cards = hits
+            .into_iter()
+            .map(|h| (h.card.title, h.card._id, h.list._id))
+            .collect::<Vec<Placed>>();
+        let mut board_client = <Client as BoardApi>::new(self.client.config.clone());
+        let labels = match board_client.get_one::<BDetails>(&board_id).await {
+            Ok(b) => b.get_labels(),
+            Err(_e) => Vec::new(),
+        };
+        let mut preview = Vec::new();
+        let mut created = 0;
+        let mut updated = 0;
+        let mut failed = Vec::new();
+        for (i, r) in records.iter().enumerate() {
+            match self.import_record(&board_id, &labels, &mut cards, r).await {
+                Ok(Imported::Created) => created += 1,
+                Ok(Imported::Updated) => updated += 1,
+                Ok(Imported::Planned(p)) => preview.push(p),
+                // Row 1 is the header.
+                Err(e) => failed.push(format!("Row {}: {}", i + 2, e)),
+            }
+        }
+        let mut msg = if self.client.config.dry_run {
+            format!("Dry run, nothing was changed:\n{}", preview.join("\n"))
+        } else {
+            format!(
+                "Successfully created {} and updated {} cards",
+                created, updated
+            )
+        };
+        for f in &failed {
+            msg.push_str(&format!("\nFailed: {}", f));
+        }
+        match (failed.is_empty(), self.client.config.dry_run) {
+            (true, true) => {
+                WekanResult::new_workflow(&msg, "Run the import without --dry-run").ok()
+            }
+            (true, false) => WekanResult::new_msg(&msg).ok(),
+            (false, _) => WekanResult::new_exit(&msg, 1, None).ok(),
+        }
+    }
+
+    /// Creates or updates the card of one row. An existing card is found by its exact title in
+    /// every list of the board and moved if the row names another list.
+    async fn import_record(
+        &mut self,
+        board_id: &str,
+        labels: &[Label],
+        cards: &mut Vec<Placed>,
+        r: &Record,
+    ) -> Result<Imported, String> {
+        let list_id = match self.query.find_list_id(board_id, &r.list).await {
+            Ok(id) => id,
+            Err(_e) => return Err(format!("List '{}' of card '{}' not found", r.list, r.title)),
+        };
+        let label_ids = match Record::get_optional(&r.labels) {
+            Some(l) => {
+                let names = l.split(',').map(|n| n.to_string()).collect::<Vec<String>>();
+                let (ids, missing) = label_ids(&names, labels);
+                if !missing.is_empty() {
+                    return Err(format!(
+                        "Labels of card '{}' not found on the board: {}",
+                        r.title,
+                        missing.join(", ")
+                    ));
+                }
+                Some(ids)
+            }
+            None => None,
+        };
+        let existing = cards
+            .iter()
+            .find(|(title, _id, _list_id)| title == &r.title)
+            .map(|(_title, id, list_id)| (id.to_owned(), list_id.to_owned()));
+        if self.client.config.dry_run {
+            return Ok(Imported::Planned(match &existing {
+                Some((_id, l)) if l != &list_id => {
+                    format!("update '{}' and move it to '{}'", r.title, r.list)
+                }
+                Some(_e) => format!("update '{}' in '{}'", r.title, r.list),
+                None => format!("create '{}' in '{}'", r.title, r.list),
+            }));
+        }
+        let (id, current_list_id, imported) = match existing {
+            Some((id, l)) => (id, l, Imported::Updated),
+            None => {
+                let swimlane = if r.swimlane.is_empty() {
+                    self.query.find_swimlane_id(board_id).await
+                } else {
+                    self.query
+                        .find_swimlane_id_by_title(board_id, &r.swimlane)
+                        .await
+                };
+                let create_card = CreateCard {
+                    _id: String::new(),
+                    author_id: self.client.get_user_id(),
+                    members: None,
+                    assignees: None,
+                    title: r.title.to_owned(),
+                    description: String::new(),
+                    swimlane_id: match swimlane {
+                        Ok(s) => s,
+                        Err(_e) => return Err(format!("Swimlane of card '{}' not found", r.title)),
+                    },
+                };
+                let mut client =
+                    <Client as CardApi>::new(self.client.config.clone(), board_id, &list_id);
+                match client.create::<CreateCard, ResponseOk>(&create_card).await {
+                    Ok(o) => {
+                        // A later row with the same title updates this card.
+                        cards.push((r.title.to_owned(), o.get_id(), list_id.to_owned()));
+                        (o.get_id(), list_id.to_owned(), Imported::Created)
+                    }
+                    Err(e) => {
+                        trace!("{:?}", e);
+                        return Err(format!("Failed to create card '{}'", r.title));
+                    }
+                }
+            }
+        };
+        let mut client =
+            <Client as CardApi>::new(self.client.config.clone(), board_id, &current_list_id);
+        let update_card = UpdateCard {
+            _id: id.to_owned(),
+            title: None,
+            description: Record::get_optional(&r.description),
+            due_at: Record::get_optional(&r.due_at),
+            end_at: Record::get_optional(&r.end_at),
+            sort: None,
+            labels: label_ids,
+            assignees: Record::get_optional(&r.assignees)
+                .map(|a| a.split(',').map(|i| i.to_string()).collect()),
+        };
+        trace!("{:?}", update_card);
+        if let Err(e) = client.put::<UpdateCard, ResponseOk>(&update_card).await {
+            trace!("{:?}", e);
+            return Err(format!("Failed to update card '{}'", r.title));
+        }
+        if current_list_id != list_id {
+            let move_card = MoveCard {
+                _id: id.to_owned(),
+                list_id: list_id.to_owned(),
+                board_id: Some(board_id.to_string()),
+                swimlane_id: None,
+                sort: None,
+            };
+            if let Err(e) = client.put::<MoveCard, ResponseOk>(&move_card).await {
+                trace!("{:?}", e);
+                return Err(format!("Failed to move card '{}' to '{}'", r.title, r.list));
+            }
+            if let Some(c) = cards.iter_mut().find(|(_title, i, _list_id)| i == &id) {
+                c.2 = list_id;
+            }
+        }
+        Ok(imported)
+    }
+}
+
+/// Title, id and list id of a card.
+type Placed = (String, String, String);
+
+/// Outcome of one row of a CSV import.
+enum Imported {
+    Created,
+    Updated,
+    Planned(String),
+}
+
+/// Change applied to every card of a selection.
+enum Bulk {
+    Remove,
+    Archive(bool),
+    Move {
+        board_id: String,
+        list_id: String,
+        swimlane_id: Option<String>,
+        position: Option<Position>,
+    },
+    Update(Update),
+}
+
+/// Ids and sort values of the cards in a list.
+type Sorts = Vec<(String, f32)>;
+
+/// Sort value for the card at the position among the other cards, which is kept for the next card.
+fn place(sorts: &mut Sorts, id: &str, position: &Position) -> f32 {
+    sorts.retain(|(i, _s)| i != id);
+    let sort = position.sort(&sorts.iter().map(|(_i, s)| *s).collect::<Vec<f32>>());
+    sorts.push((id.to_string(), sort));
+    sort
+}
+
+/// Counts the changed cards and names the failed ones, which set the exit code.
+fn bulk_report(done: &str, total: usize, failed: &[String]) -> Result<WekanResult, Error> {
+    let mut msg = format!(
+        "Successfully {} {} of {} cards",
+        done,
+        total - failed.len(),
+        total
+    );
+    for path in failed {
+        msg.push_str(&format!("\nFailed: {}", path));
+    }
+    if failed.is_empty() {
+        WekanResult::new_msg(&msg).ok()
+    } else {
+        WekanResult::new_exit(&msg, 1, None).ok()
+    }
+}
+
+/// Keeps the edited file after a failure, otherwise it is removed when dropped.
+fn keep(path: tempfile::TempPath) -> String {
+    match path.keep() {
+        Ok(p) => p.display().to_string(),
+        Err(e) => format!("no file ({})", e),
+    }
+}
+
+fn archive_card(id: &str, archive: bool) -> ArchiveCard {
+    ArchiveCard {
+        _id: id.to_string(),
+        archive,
+        archive_at: if archive {
+            Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+        } else {
+            String::new()
+        },
+    }
+}
+
+/// A description with the prefix 'k+|' is appended to the current one.
+fn update_card(id: &str, current: &str, update_args: &Update) -> UpdateCard {
+    let description = match &update_args.description {
+        Some(d) if d.starts_with("k+|") => {
+            Some(format!("{}\n{}", current, d.trim_start_matches("k+|")))
+        }
+        Some(d) if !d.is_empty() => Some(d.to_owned()),
+        _ => None,
+    };
+    UpdateCard {
+        _id: id.to_string(),
+        title: update_args.title.to_owned(),
+        description,
+        due_at: update_args.due_at.as_ref().map(|d| d.to_string()),
+        end_at: update_args.end_at.as_ref().map(|d| d.to_string()),
+        labels: update_args
+            .labels
+            .as_ref()
+            .map(|l| l.split_terminator(',').map(|s| s.to_string()).collect()),
+        sort: update_args.sort,
+        assignees: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::{Subcommand, WekanParser},
+        tests::mocks::Mock,
+    };
+    use clap::Parser;
+    use wekan_core::config::UserConfig;
+
+    fn parse(line: &str) -> (Args, RArgs) {
+        let parser = WekanParser::try_parse_from(shell_words::split(line).unwrap()).unwrap();
+        match parser.command {
+            Subcommand::Card(a) => (a, parser.delegate),
+            c => panic!("Not a card command: {:?}", c),
+        }
+    }
+
+    /// Runner on the first board and list of the mocks.
+    fn runner<'a>(
+        args: Args,
+        config: UserConfig,
+        query: &'a mut Query<'a>,
+        global_options: &'a RArgs,
+    ) -> Runner<'a> {
+        let constraint = Constraint {
+            board: Artifact {
+                _id: String::from("fake-board-id-1"),
+                title: String::from("fake-board-title-1"),
+                r#type: AType::Board,
+            },
+            list: Artifact {
+                _id: String::from("fake-list-id-1"),
+                title: String::from("fake-list-title-1"),
+                r#type: AType::List,
+            },
+        };
+        let client = <Client as CardApi>::new(config, "fake-board-id-1", "fake-list-id-1");
+        Runner::new(
+            args,
+            client,
+            constraint,
+            query,
+            String::new(),
+            CliDisplay::new(Vec::new()),
+            global_options,
+        )
+    }
+
+    async fn run(line: &str, dry_run: bool) -> Result<WekanResult, Error> {
+        let (args, global_options) = parse(line);
+        let mut config = UserConfig::mock();
+        config.dry_run = dry_run;
+        let mut query = Query::new(config.clone(), "", true);
+        runner(args, config, &mut query, &global_options)
+            .run()
+            .await
+    }
+
+    async fn destination(line: &str) -> Result<(String, String, Option<String>), Error> {
+        let (args, global_options) = parse(line);
+        let move_args = match &args.command {
+            Some(Command::Move(m)) => m.to_owned(),
+            c => panic!("Not a move: {:?}", c),
+        };
+        let config = UserConfig::mock();
+        let mut query = Query::new(config.clone(), "", true);
+        let mut runner = runner(args, config, &mut query, &global_options);
+        runner.destination(&move_args).await
+    }
+
+    #[tokio::test]
+    async fn run_move() {
+        let res = run(
+            "wekan-cli card -b fake-board-title-1 -l fake-list-title-1 -n fake-card-title-1 mv fake-list-title-2 --position top",
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.get_msg(), "Successfully moved");
+    }
+
+    #[tokio::test]
+    async fn find_the_destination_of_a_move() {
+        let card = "wekan-cli card -b fake-board-title-1 -l fake-list-title-1 -n fake-card-title-1";
+        let res = destination(&format!("{} mv fake-list-title-2", card))
             .await
+            .unwrap();
+        assert_eq!(
+            res,
+            (
+                String::from("fake-board-id-1"),
+                String::from("fake-list-id-2"),
+                None
+            )
+        );
+        let res = destination(&format!(
+            "{} mv --to-list fake-list-title-2 --to-board fake-board-title-2 --swimlane fake-swimlane-title-2",
+            card
+        ))
+        .await
+        .unwrap();
+        assert_eq!(
+            res,
+            (
+                String::from("fake-board-id-2"),
+                String::from("fake-list-id-2"),
+                Some(String::from("fake-swimlane-id-2"))
+            )
+        );
+        // The mocks have no 'Default' swimlane to move into on another board.
+        for line in [
+            format!(
+                "{} mv fake-list-title-2 --to-board fake-board-title-2",
+                card
+            ),
+            format!("{} mv fake-list-title-2 --swimlane missing", card),
+        ] {
+            match destination(&line).await {
+                Err(Error::Cli(e)) => assert_eq!(e.message, "Destination swimlane not found"),
+                r => panic!("'{}' found a swimlane: {:?}", line, r),
+            }
+        }
+        match destination(&format!("{} mv fake-list-title-2 --to-board missing", card)).await {
+            Err(Error::Cli(e)) => assert_eq!(e.message, "Destination board not found"),
+            r => panic!("Found a board: {:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn remove_a_selection() {
+        let res = run(
+            "wekan-cli card -b fake-board-title-1 -l fake-list-title-1 rm --all-in-list fake-list-title-1 -y",
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.get_msg(), "Successfully removed 2 of 2 cards");
+        assert_eq!(res.get_exit_code(), 0);
+    }
+
+    #[tokio::test]
+    async fn move_a_selection_to_a_position() {
+        let res = run(
+            "wekan-cli card -b fake-board-title-1 mv --to-list fake-list-title-2 --position top --older-than 1d -y",
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.get_msg(), "Successfully moved 4 of 4 cards");
+    }
+
+    #[tokio::test]
+    async fn continue_a_dry_run_after_each_card() {
+        let res = run(
+            "wekan-cli card -b fake-board-title-1 archive --all-in-list fake-list-title-2",
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.get_msg(), "Successfully archived 2 of 2 cards");
+        assert_eq!(res.get_exit_code(), 0);
+    }
+
+    #[tokio::test]
+    async fn reject_a_selection_of_other_subcommands() {
+        let (args, global_options) = parse(
+            "wekan-cli card -b fake-board-title-1 -l fake-list-title-1 -n fake-card-title-1 cp",
+        );
+        let config = UserConfig::mock();
+        let mut query = Query::new(config.clone(), "", true);
+        let mut runner = runner(args, config, &mut query, &global_options);
+        let selection = Selection {
+            all_in_list: Some(String::from("fake-list-title-1")),
+            ..Default::default()
+        };
+        match runner.run_bulk(&selection).await {
+            Err(Error::Cli(e)) => {
+                assert_eq!(e.message, "Subcommand does not support a selection")
+            }
+            r => panic!("Selection was not rejected: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn report_failed_cards() {
+        let res = bulk_report("removed", 3, &[String::from("/B/L/C")]).unwrap();
+        assert_eq!(
+            res.get_msg(),
+            "Successfully removed 2 of 3 cards\nFailed: /B/L/C"
+        );
+        assert_eq!(res.get_exit_code(), 1);
+    }
+
+    #[tokio::test]
+    async fn restore_archived_cards_of_a_selection() {
+        let res = run(
+            "wekan-cli card -b fake-board-title-1 archive --restore --older-than 1d -y",
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.get_msg(), "Successfully restored 1 of 1 cards");
+        let res = run(
+            "wekan-cli card -b fake-board-title-1 archive --restore --all-in-list fake-list-title-2 -y",
+            false,
+        )
+        .await;
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn place_cards_one_after_another() {
+        let mut sorts = vec![(String::from("a"), 1.0), (String::from("b"), 2.0)];
+        assert_eq!(place(&mut sorts, "c", &Position::Bottom), 3.0);
+        assert_eq!(place(&mut sorts, "d", &Position::Bottom), 4.0);
+        assert_eq!(place(&mut sorts, "d", &Position::Top), 0.0);
+        assert_eq!(sorts.len(), 4);
     }
 }
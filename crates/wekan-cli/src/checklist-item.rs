@@ -24,7 +24,7 @@ use wekan_core::client::{ChecklistItemApi, Client};
 
 use clap::{Args as ClapArgs, Subcommand};
 
-#[derive(ClapArgs, Debug, Clone, CommonSubcommands)]
+#[derive(ClapArgs, Debug, Clone)]
 #[clap(
     about = "Manage checklists",
     long_about = "Create, remove and show details"
@@ -36,6 +36,12 @@ pub struct Args {
     pub command: Option<Command>,
 }
 
+impl CommonCommandRequester<Command> for Args {
+    fn get_common_command(&self) -> Option<Command> {
+        self.command.to_owned()
+    }
+}
+
 #[cfg(test)]
 impl Args {
     pub fn mock(
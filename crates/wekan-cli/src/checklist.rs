@@ -7,11 +7,11 @@ use crate::{
     error::{CliError, Error, Transform},
     resolver::Query,
     result::WekanResult,
-    subcommand::{CommonCommand, Details as SDetails, Inspect, List, Remove},
+    subcommand::{Archive, CommonCommand, Details as SDetails, Inspect, List, Remove},
 };
 use async_trait::async_trait;
 use log::info;
-use wekan_cli_derive::{FulfilmentRunner, WekanArgs};
+use wekan_cli_derive::{CommonSubcommands, FulfilmentRunner, WekanArgs};
 use wekan_common::{
     artifact::{checklist::Details, common::AType},
     http::{
@@ -43,14 +43,19 @@ pub struct Args {
 }
 
 /// The following commands are available:
-#[derive(Subcommand, Debug, Clone)]
+#[derive(Subcommand, Debug, Clone, CommonSubcommands)]
 pub enum Command {
+    #[common]
     Ls(List),
     Create(ChecklistCreateArgs),
+    #[common]
     #[clap(name = "rm")]
     Remove(Remove),
+    #[common]
     Inspect(Inspect),
+    #[common]
     Details(SDetails),
+    Archive(Archive),
 }
 #[cfg(test)]
 impl Args {
@@ -71,20 +76,6 @@ impl Args {
     }
 }
 
-impl CommonCommandRequester<Command> for Args {
-    fn get_common_command(&self) -> Option<CommonCommand> {
-        match &self.command {
-            Some(c) => match c {
-                Command::Ls(ls) => Some(CommonCommand::Ls(ls.to_owned())),
-                Command::Remove(r) => Some(CommonCommand::Remove(r.to_owned())),
-                Command::Inspect(i) => Some(CommonCommand::Inspect(i.to_owned())),
-                Command::Details(d) => Some(CommonCommand::Details(d.to_owned())),
-                _ => None,
-            },
-            None => None,
-        }
-    }
-}
 #[derive(ClapArgs, Debug, Clone)]
 #[clap(
     about = "Create checklist",
@@ -125,24 +116,33 @@ impl<'a> Operator<'a> for Runner<'a> {
             Some(f) => filter.push_str(f),
             None => {}
         };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            filter: &filter,
-            config: self.get_client().config,
-            deny_store_usage: self.get_global_options().no_store,
-        };
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            filter: &filter,
-            config: self.get_client().config,
-        };
+        let mut query = Query::new(
+            self.get_client().config,
+            &filter,
+            self.get_global_options().deny_store(),
+        );
         query
             .find_checklist_id(&self.constraint.board._id, &self.constraint.card._id, name)
             .await
     }
+    fn get_path(&self, name: &str) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.constraint.board.title,
+            self.constraint.list.title,
+            self.constraint.card.title,
+            name
+        )
+    }
     fn get_type(&self) -> AType {
         AType::Checklist
     }
+    fn get_location(&self, _id: &str) -> (String, String) {
+        (
+            self.constraint.board._id.to_owned(),
+            self.constraint.card._id.to_owned(),
+        )
+    }
 
     fn get_children_type(&self) -> AType {
         AType::Empty
@@ -176,6 +176,7 @@ impl<'a> RootCommandRunner<'a, Details, Command> for Runner<'a> {
         match self.args.command.to_owned() {
             Some(c) => match c {
                 Command::Create(c) => self.use_create(&c).await,
+                Command::Archive(a) => self.archive(self.args.name.to_owned(), !a.restore).await,
                 _ => self.use_common_command().await,
             },
             None => CliError::new_msg("Subcommand not implemented").err(),
@@ -217,6 +218,7 @@ mod tests {
         tests::mocks::Mock,
     };
     use wekan_common::artifact::common::Artifact;
+    use wekan_core::config::UserConfig;
 
     #[tokio::test]
     async fn run_no_options_specified() {
@@ -365,7 +367,7 @@ mod tests {
                 String::from("fake-board-title-1"),
                 String::from("fake-list-title-2"),
                 String::from("fake-card-title-2"),
-                Some(Command::Remove(Remove {})),
+                Some(Command::Remove(Remove { yes: true })),
             ),
             Client::mock(),
             ChConstraint {
@@ -393,6 +395,53 @@ mod tests {
         assert_eq!(res.get_msg(), "Successfully deleted");
     }
 
+    #[tokio::test]
+    async fn run_archive_and_restore() {
+        let r_args = RArgs::mock();
+        let constraint = ChConstraint {
+            board: Artifact {
+                _id: String::from("fake-board-id-1"),
+                title: String::from("fake-board-title-1"),
+                r#type: AType::Board,
+            },
+            list: Artifact {
+                _id: String::from("fake-list-id-2"),
+                title: String::from("fake-card-title-2"),
+                r#type: AType::List,
+            },
+            card: Artifact {
+                _id: String::from("fake-card-id-2"),
+                title: String::from("fake-card-title-2"),
+                r#type: AType::Card,
+            },
+        };
+        for (restore, expected) in [
+            (false, "Successfully archived"),
+            (true, "Successfully restored"),
+        ] {
+            let mut runner = Runner::new(
+                Args::mock(
+                    Some(String::from("fake-checklist-title-2")),
+                    String::from("fake-board-title-1"),
+                    String::from("fake-list-title-2"),
+                    String::from("fake-card-title-2"),
+                    Some(Command::Archive(Archive { restore })),
+                ),
+                <Client as ChecklistApi>::new(
+                    UserConfig::mock(),
+                    "fake-board-id-1",
+                    "fake-card-id-2",
+                ),
+                constraint.clone(),
+                String::new(),
+                CliDisplay::new(Vec::new()),
+                &r_args,
+            );
+            let res = runner.run().await.unwrap();
+            assert_eq!(res.get_msg(), expected);
+        }
+    }
+
     #[tokio::test]
     async fn run_with_special_output() {
         #[cfg(feature = "store")]
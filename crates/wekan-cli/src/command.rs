@@ -1,22 +1,36 @@
 #[cfg(test)]
 use crate::tests::mocks::{Artifacts, Mock, Operation};
 use crate::{
+    archive::{self, Args as ArArg},
+    batch::Args as BaArg,
     board::Args as BArg,
     card::argument::Args as CArg,
     checklist::Args as ChArg,
+    completion::{Args as CoArg, CompleteArgs},
     config::argument::Args as Config,
+    diff::Args as DArg,
     display::CliDisplay,
-    error::{CliError, Error, Transform},
+    due::Args as DuArg,
+    error::{failed, CliError, Error, Transform},
+    import::Args as IArg,
+    journal::{self, Args as JArg, Change},
+    kanban::Args as KArg,
     list::Args as LArg,
+    mine::Args as MArg,
+    prompt::approve,
     resolver::Query,
     result::WekanResult,
+    search::Args as SeArg,
+    serve::Args as SArg,
+    shell::Args as ShArg,
     subcommand::{Apply, CommonCommand as Command, Describe, Get, Inspect, Table},
+    watch::Args as WArg,
 };
 use async_trait::async_trait;
 use clap::{Args as CArgs, Parser, Subcommand as CSubcommand};
 #[cfg(not(test))]
 use clap_verbosity_flag::{ErrorLevel, Verbosity};
-use log::{debug, info, trace};
+use log::{info, trace};
 use wekan_core::config::ConfigRequester;
 #[cfg(not(test))]
 use wekan_core::http::operation::{Artifacts, Operation};
@@ -53,6 +67,21 @@ pub enum Subcommand {
     Describe(Describe),
     Inspect(Inspect),
     Apply(Apply),
+    Import(IArg),
+    Serve(SArg),
+    Tui(KArg),
+    Shell(ShArg),
+    Diff(DArg),
+    Watch(WArg),
+    Search(SeArg),
+    Mine(MArg),
+    Due(DuArg),
+    Archive(ArArg),
+    Undo(JArg),
+    Batch(BaArg),
+    Completion(CoArg),
+    #[clap(name = "__complete", hide = true)]
+    Complete(CompleteArgs),
 }
 
 #[derive(CArgs, Debug)]
@@ -73,7 +102,11 @@ pub struct Args {
     )]
     #[cfg(feature = "store")]
     pub no_store: bool,
-    #[clap(short = 'o', long, help = "Output format: rust, elisp, long, extended")]
+    #[clap(
+        short = 'o',
+        long,
+        help = "Output format: rust, elisp, long, extended, markdown, org"
+    )]
     pub output_format: Option<String>,
     #[clap(
         short = 'f',
@@ -82,11 +115,27 @@ pub struct Args {
         long_help = "Filter out artifacts by id in format: b:..,l:..,c:.. This overrules name argument"
     )]
     pub filter: Option<String>,
+    #[clap(
+        long,
+        parse(from_flag),
+        help = "Print the requests which would change artifacts instead of sending them"
+    )]
+    pub dry_run: bool,
     #[clap(flatten)]
     #[cfg(not(test))]
     pub verbose: Verbosity<ErrorLevel>,
 }
 
+impl Args {
+    /// Whether the store must not be used, always without the store feature.
+    pub fn deny_store(&self) -> bool {
+        #[cfg(feature = "store")]
+        return self.no_store;
+        #[cfg(not(feature = "store"))]
+        true
+    }
+}
+
 #[cfg(test)]
 impl Mock for Args {
     fn mock() -> Self {
@@ -96,6 +145,7 @@ impl Mock for Args {
             no_store: false,
             output_format: None,
             filter: None,
+            dry_run: false,
         }
     }
 }
@@ -107,6 +157,7 @@ impl Args {
             no_recommendations: r,
             output_format: Some(o.to_string()),
             filter: Some(f.to_string()),
+            dry_run: false,
         }
     }
     #[cfg(feature = "store")]
@@ -116,6 +167,7 @@ impl Args {
             no_store: s,
             output_format: Some(o.to_string()),
             filter: Some(f.to_string()),
+            dry_run: false,
         }
     }
 }
@@ -186,8 +238,8 @@ pub trait RootCommandRunner<'a, R: DetailsResponse, C: std::marker::Send>:
             Some(c) => match c {
                 Command::Ls(_ls) => self.use_ls().await,
                 Command::Create(c) => self.use_create(&c).await,
-                Command::Remove(_r) => match args.get_name() {
-                    Ok(n) => self.remove(Some(n)).await,
+                Command::Remove(r) => match args.get_name() {
+                    Ok(n) => self.remove(Some(n), r.yes).await,
                     Err(e) => Err(e),
                 },
                 Command::Inspect(i) => self.use_inspect(&i).await,
@@ -221,9 +273,10 @@ pub trait RootCommandRunner<'a, R: DetailsResponse, C: std::marker::Send>:
                     .get_one::<MD>(&body.get_id())
                     .await
                     .unwrap();
-                self.get_display().format_most_details(details)
+                let format = Some(self.get_format().to_owned());
+                self.get_display().format_most_details(details, &format)
             }
-            Err(_e) => CliError::new_msg("Failed to update").err(),
+            Err(e) => Err(failed("Failed to update", e)),
         }
     }
 }
@@ -232,6 +285,12 @@ pub trait RootCommandRunner<'a, R: DetailsResponse, C: std::marker::Send>:
 pub trait Operator<'a>: Fulfillment<'a> + std::marker::Send + std::marker::Sync {
     fn get_type(&self) -> AType;
     fn get_children_type(&self) -> AType;
+    /// Board and parent id of an artifact of the runner, e.g. the board and list of a card.
+    fn get_location(&self, id: &str) -> (String, String);
+    /// Title path of an artifact below the parents of the runner.
+    fn get_path(&self, name: &str) -> String {
+        name.to_string()
+    }
     async fn find_details_id(&mut self, name: &str) -> Result<String, Error>;
 
     async fn unwrap_and_find_id(&mut self, name: Option<String>) -> Result<String, Error> {
@@ -266,24 +325,74 @@ pub trait Operator<'a>: Fulfillment<'a> + std::marker::Send + std::marker::Sync
                 trace!("{:?}", ok);
                 WekanResult::new_msg("Successfully created").ok()
             }
-            Err(e) => {
-                debug!("{:?}", e);
-                CliError::new_msg("Failed to create").err()
-            }
+            Err(e) => Err(failed("Failed to create", e)),
         }
     }
 
-    async fn remove(&mut self, name: Option<String>) -> Result<WekanResult, Error> {
+    async fn remove(&mut self, name: Option<String>, yes: bool) -> Result<WekanResult, Error> {
         info!("remove");
-        let id = self.unwrap_and_find_id(name).await?;
+        let n = self.unwrap_name(name)?;
+        let id = self.find_details_id(&n).await?;
+        let client = self.get_client();
+        let (board_id, parent_id) = self.get_location(&id);
+        let change =
+            journal::removal(&client.config, self.get_type(), &board_id, &parent_id, &id).await?;
+        let children = match &change {
+            Change::Remove { snapshot, .. } => snapshot.children(&self.get_type()),
+            _ => String::new(),
+        };
+        let mut question = format!(
+            "Remove {} '{}'",
+            self.get_type().to_string(),
+            self.get_path(&n)
+        );
+        if !children.is_empty() {
+            question.push_str(&format!(" with {}", children));
+        }
+        if !client.config.dry_run && !approve(&(question + "?"), yes)? {
+            return WekanResult::new_unchanged("Aborted, nothing was changed").ok();
+        }
         match self.get_client().delete::<ResponseOk>(&id).await {
-            Ok(_o) => WekanResult::new_msg("Successfully deleted").ok(),
-            Err(e) => {
-                trace!("{:?}", e);
-                CliError::new_msg("Failed to delete").err()
+            Ok(_o) => {
+                let summary = format!("rm {} '{}'", self.get_type().to_string(), n);
+                journal::record(&client.config, &summary, change).await;
+                WekanResult::new_workflow("Successfully deleted", "Revert it with 'undo'").ok()
             }
+            Err(e) => Err(failed("Failed to delete", e)),
         }
     }
+    async fn archive(&mut self, name: Option<String>, archive: bool) -> Result<WekanResult, Error> {
+        info!("archive");
+        let n = self.unwrap_name(name)?;
+        let mut client = self.get_client();
+        let (board_id, parent_id) = self.get_location("");
+        let id = match archive {
+            true => self.find_details_id(&n).await?,
+            false => {
+                archive::find_archived_id(
+                    &client.config,
+                    &self.get_type(),
+                    &board_id,
+                    &parent_id,
+                    &n,
+                )
+                .await?
+            }
+        };
+        let result = archive::put(&mut client, &id, archive).await?;
+        let (board_id, parent_id) = self.get_location(&id);
+        let change = Change::Archive {
+            r#type: self.get_type(),
+            board_id,
+            parent_id,
+            id,
+            archive,
+        };
+        let verb = if archive { "archive" } else { "restore" };
+        let summary = format!("{} {} '{}'", verb, self.get_type().to_string(), n);
+        journal::record(&client.config, &summary, change).await;
+        Ok(result)
+    }
     async fn get_all(&mut self) -> Result<WekanResult, Error> {
         info!("get_all");
         match self.get_client().get_all(self.get_type()).await {
@@ -315,17 +424,11 @@ pub trait Operator<'a>: Fulfillment<'a> + std::marker::Send + std::marker::Sync
             Some(f) => filter.push_str(f),
             None => {}
         };
-        #[cfg(feature = "store")]
-        let query = Query {
-            filter: &filter,
-            config: self.get_client().config,
-            deny_store_usage: self.get_global_options().no_store,
-        };
-        #[cfg(not(feature = "store"))]
-        let query = Query {
-            filter: &filter,
-            config: self.get_client().config,
-        };
+        let query = Query::new(
+            self.get_client().config,
+            &filter,
+            self.get_global_options().deny_store(),
+        );
         let childrens = match self.get_children_type() {
             AType::List => {
                 query
@@ -349,7 +452,11 @@ pub trait Operator<'a>: Fulfillment<'a> + std::marker::Send + std::marker::Sync
         match childrens {
             Ok(children) => {
                 trace!("{:?}", children);
-                if !children.is_empty() {
+                if o.is_elisp() {
+                    let format = Some(self.get_format().to_owned());
+                    self.get_display()
+                        .prepare_output(&o.get_msg(), children, format)
+                } else if !children.is_empty() {
                     self.get_display().prepare_output(
                         &(o.get_msg() + "Following children are available:\n"),
                         children,
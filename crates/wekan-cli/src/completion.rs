@@ -0,0 +1,362 @@
+use crate::{command::WekanParser, error::Error, resolver::Query, result::WekanResult};
+use clap::{ArgEnum, Args as ClapArgs, Command, CommandFactory};
+use log::{info, trace};
+use std::collections::HashMap;
+#[cfg(feature = "store")]
+use {crate::store::Store, wekan_common::artifact::common::AType};
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Print a shell completion script",
+    long_about = "Print a completion script which completes commands, options and the names of boards, lists, cards and checklists from the store, e.g. 'wekan-cli completion bash > /etc/bash_completion.d/wekan-cli'"
+)]
+pub struct Args {
+    #[clap(arg_enum)]
+    pub shell: Shell,
+}
+
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Entrypoint of the completion scripts.
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(about = "Complete the last of the given words")]
+pub struct CompleteArgs {
+    #[clap(
+        long,
+        arg_enum,
+        default_value = "bash",
+        help = "Shell which inserts the suggestions"
+    )]
+    pub shell: Shell,
+    /// The words after 'wekan-cli', the last one is completed
+    #[clap(multiple_values = true, allow_hyphen_values = true)]
+    pub words: Vec<String>,
+}
+
+const BASH: &str = r#"_wekan_cli() {
+    local IFS=$'\n'
+    COMPREPLY=($(wekan-cli __complete --shell bash -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null))
+}
+complete -o default -F _wekan_cli wekan-cli
+"#;
+
+const ZSH: &str = r#"#compdef wekan-cli
+_wekan_cli() {
+    local -a suggestions
+    suggestions=("${(@Q)${(@f)$(wekan-cli __complete --shell zsh -- "${(@)words[2,$CURRENT]}" 2>/dev/null)}}")
+    compadd -a suggestions
+}
+compdef _wekan_cli wekan-cli
+"#;
+
+const FISH: &str = r#"complete -c wekan-cli -f -a '(wekan-cli __complete --shell fish -- (commandline -opc)[2..-1] (commandline -ct) 2>/dev/null)'
+"#;
+
+pub fn script(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash => BASH,
+        Shell::Zsh => ZSH,
+        Shell::Fish => FISH,
+    }
+}
+
+/// The subcommand and the option values typed so far.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Typed {
+    pub path: Vec<String>,
+    pub options: HashMap<String, String>,
+    /// Long name of the option which expects the completed word as value.
+    pub pending: Option<String>,
+}
+
+impl Typed {
+    /// Walks the words through the subcommands and returns the deepest one.
+    pub fn parse(words: &[String]) -> (Self, Command<'static>) {
+        let mut command = WekanParser::command();
+        let mut typed = Typed::default();
+        for word in words {
+            if let Some(long) = typed.pending.take() {
+                typed.options.insert(long, word.to_owned());
+                continue;
+            }
+            if word.starts_with('-') {
+                let arg = command
+                    .get_arguments()
+                    .find(|a| match word.strip_prefix("--") {
+                        Some(l) => a.get_long() == Some(l),
+                        None => word.len() == 2 && a.get_short() == word.chars().nth(1),
+                    });
+                if let Some(a) = arg {
+                    if a.is_takes_value_set() {
+                        typed.pending = a.get_long().map(|l| l.to_string());
+                    }
+                }
+                continue;
+            }
+            if let Some(c) = command.find_subcommand(word).cloned() {
+                typed.path.push(c.get_name().to_string());
+                command = c;
+            }
+        }
+        (typed, command)
+    }
+
+    #[cfg(any(test, feature = "store"))]
+    fn option(&self, long: &str) -> Option<&str> {
+        self.options.get(long).map(|o| o.as_str())
+    }
+}
+
+/// Suggests subcommands, options or, after an option with a name, artifact titles.
+pub async fn complete(args: &CompleteArgs, query: &Query<'_>) -> Result<WekanResult, Error> {
+    info!("complete");
+    let words = args
+        .words
+        .iter()
+        .map(|w| unquote(w))
+        .collect::<Vec<String>>();
+    let (current, words) = match words.split_last() {
+        Some((c, w)) => (c.as_str(), w),
+        None => ("", &words[..]),
+    };
+    let (typed, command) = Typed::parse(words);
+    trace!("{:?}", typed);
+    let candidates = match &typed.pending {
+        Some(long) => names(&typed, long, query).await,
+        None if current.starts_with('-') => command
+            .get_arguments()
+            .filter(|a| !a.is_positional() && !a.is_hide_set())
+            .flat_map(|a| {
+                let mut flags = Vec::new();
+                if let Some(l) = a.get_long() {
+                    flags.push(format!("--{}", l));
+                }
+                if let Some(s) = a.get_short() {
+                    flags.push(format!("-{}", s));
+                }
+                flags
+            })
+            .collect(),
+        None => command
+            .get_subcommands()
+            .filter(|c| !c.is_hide_set())
+            .map(|c| c.get_name().to_string())
+            .collect(),
+    };
+    let suggestions = candidates
+        .into_iter()
+        .filter(|c| c.starts_with(current))
+        .map(|c| quote(args.shell, c))
+        .collect::<Vec<String>>();
+    WekanResult::new_msg(&suggestions.join("\n")).ok()
+}
+
+/// Bash and zsh insert the suggestions as they are, fish escapes them itself.
+fn quote(shell: Shell, candidate: String) -> String {
+    match shell {
+        Shell::Fish => candidate,
+        _ => shell_words::quote(&candidate).to_string(),
+    }
+}
+
+/// Words are passed as typed, the word which is completed may lack its closing quote.
+fn unquote(word: &str) -> String {
+    let closed = match word.chars().next() {
+        Some(q @ ('"' | '\'')) => {
+            shell_words::split(word).or_else(|_e| shell_words::split(&format!("{}{}", word, q)))
+        }
+        _ => shell_words::split(word),
+    };
+    match closed {
+        Ok(w) if w.len() == 1 => w[0].to_owned(),
+        _ => word.to_string(),
+    }
+}
+
+/// Titles from the store, empty if it has none for the parents typed so far.
+#[cfg(feature = "store")]
+async fn names(typed: &Typed, long: &str, query: &Query<'_>) -> Vec<String> {
+    let artifact = match (long, typed.path.first().map(|p| p.as_str())) {
+        ("board" | "to-board", _) => AType::Board,
+        ("list" | "to-list", _) => AType::List,
+        ("card", _) => AType::Card,
+        ("name", Some("board")) => AType::Board,
+        ("name", Some("list")) => AType::List,
+        ("name", Some("card")) => AType::Card,
+        ("name", Some("checklist")) => AType::Checklist,
+        _ => return Vec::new(),
+    };
+    let board = match long {
+        "to-list" => typed.option("to-board").or_else(|| typed.option("board")),
+        _ => typed.option("board"),
+    };
+    let board_id = match board {
+        Some(b) => id(query, AType::Board, "", b).await,
+        None => None,
+    };
+    let list_id = match (&board_id, typed.option("list")) {
+        (Some(b), Some(l)) => id(query, AType::List, b, l).await,
+        _ => None,
+    };
+    let parent = match (&artifact, board_id, list_id) {
+        (AType::Board, _, _) => String::new(),
+        (AType::List, Some(b), _) => b,
+        (AType::Card, Some(b), Some(l)) => b + "_" + &l,
+        (AType::Checklist, Some(b), Some(l)) => match typed.option("card") {
+            Some(c) => match id(query, AType::Card, &(b.to_owned() + "_" + &l), c).await {
+                Some(c) => b + "_" + &c,
+                None => return Vec::new(),
+            },
+            None => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    titles(query, artifact, &parent).await
+}
+
+#[cfg(not(feature = "store"))]
+async fn names(_typed: &Typed, _long: &str, _query: &Query<'_>) -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(feature = "store")]
+async fn titles(query: &Query<'_>, artifact: AType, parent: &str) -> Vec<String> {
+    match query.lookup_artifacts(artifact, parent).await {
+        Ok(e) => e.payload.into_iter().map(|a| a.title).collect(),
+        Err(_e) => Vec::new(),
+    }
+}
+
+#[cfg(feature = "store")]
+async fn id(query: &Query<'_>, artifact: AType, parent: &str, title: &str) -> Option<String> {
+    match query.lookup_artifacts(artifact, parent).await {
+        Ok(e) => e
+            .payload
+            .into_iter()
+            .find(|a| a.title == title)
+            .map(|a| a._id),
+        Err(_e) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use wekan_core::config::UserConfig;
+
+    fn query() -> Query<'static> {
+        Query::new(UserConfig::mock(), "", false)
+    }
+
+    async fn suggest(words: &[&str]) -> Vec<String> {
+        let args = CompleteArgs {
+            shell: Shell::Bash,
+            words: words.iter().map(|w| w.to_string()).collect(),
+        };
+        complete(&args, &query())
+            .await
+            .unwrap()
+            .get_msg()
+            .lines()
+            .map(|l| l.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn scripts_call_entrypoint() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            assert!(script(shell).contains("wekan-cli __complete --"));
+        }
+    }
+
+    #[test]
+    fn walk_typed_words() {
+        let words = ["-r", "card", "-b", "Team", "--list", "Today", "-n"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect::<Vec<String>>();
+        let (typed, command) = Typed::parse(&words);
+        assert_eq!(command.get_name(), "card");
+        assert_eq!(typed.path, vec![String::from("card")]);
+        assert_eq!(typed.option("board"), Some("Team"));
+        assert_eq!(typed.option("list"), Some("Today"));
+        assert_eq!(typed.pending, Some(String::from("name")));
+    }
+
+    #[test]
+    fn quote_titles_with_spaces() {
+        assert_eq!(unquote("\"Team Board\""), "Team Board");
+        assert_eq!(unquote("'Team B"), "Team B");
+        assert_eq!(unquote("Team\\ Board"), "Team Board");
+        assert_eq!(unquote(""), "");
+        assert_eq!(
+            quote(Shell::Bash, String::from("Team Board")),
+            "'Team Board'"
+        );
+        assert_eq!(quote(Shell::Zsh, String::from("Team")), "Team");
+        assert_eq!(quote(Shell::Fish, String::from("Team Board")), "Team Board");
+        let words = ["card", "-b", "\"Team Board\"", "-n"]
+            .iter()
+            .map(|w| unquote(w))
+            .collect::<Vec<String>>();
+        let (typed, _command) = Typed::parse(&words);
+        assert_eq!(typed.option("board"), Some("Team Board"));
+    }
+
+    #[tokio::test]
+    async fn suggest_commands_and_options() {
+        assert_eq!(suggest(&["ca"]).await, vec![String::from("card")]);
+        assert!(!suggest(&[""]).await.contains(&String::from("__complete")));
+        let options = suggest(&["card", "--b"]).await;
+        assert_eq!(options, vec![String::from("--board")]);
+        assert!(suggest(&["card", "-b", "B", "-l", "L", "m"])
+            .await
+            .contains(&String::from("mv")));
+    }
+
+    #[cfg(feature = "store")]
+    #[tokio::test]
+    async fn suggest_names() {
+        assert_eq!(
+            suggest(&["list", "-b", ""]).await,
+            vec![
+                String::from("store-fake-board-title-1"),
+                String::from("store-fake-board-title-2")
+            ]
+        );
+        assert_eq!(
+            suggest(&[
+                "card",
+                "-b",
+                "store-fake-board-title-1",
+                "-l",
+                "store-fake-list-t"
+            ])
+            .await,
+            vec![
+                String::from("store-fake-list-title-1"),
+                String::from("store-fake-list-title-2")
+            ]
+        );
+        assert_eq!(
+            suggest(&[
+                "card",
+                "-b",
+                "store-fake-board-title-1",
+                "-l",
+                "store-fake-list-title-2",
+                "-n",
+                "store-fake-card-title-1"
+            ])
+            .await,
+            vec![String::from("store-fake-card-title-1")]
+        );
+        assert!(suggest(&["card", "-n", ""]).await.is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+use crate::{
+    command::WekanParser,
+    error::{CliError, Error, Transform},
+    result::WekanResult,
+};
+use async_trait::async_trait;
+use clap::{Args as ClapArgs, CommandFactory, Subcommand};
+use log::{info, trace};
+use std::collections::BTreeMap;
+#[cfg(not(test))]
+use wekan_core::persistence::config::FileWriter;
+use wekan_core::{client::LoginClient as Client, config::UserConfig};
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Manage command aliases",
+    long_about = "Define shortcuts like 'today' for 'card -b Team -l Today', stored in the config of the context"
+)]
+pub struct Args {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    Set(SetAlias),
+    #[clap(name = "rm")]
+    Remove(RemoveAlias),
+    Ls,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Set an alias",
+    long_about = "Set an alias, e.g. 'config alias set today \"card -b Team -l Today\"'. Options after the alias go to the command of the alias which knows them, other words are appended"
+)]
+pub struct SetAlias {
+    /// Alias name
+    pub name: String,
+    /// Command the alias stands for
+    pub command: String,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(about = "Remove an alias")]
+pub struct RemoveAlias {
+    /// Alias name
+    pub name: String,
+}
+
+#[async_trait]
+pub trait Alias {
+    async fn alias(&mut self, args: &Args) -> Result<WekanResult, Error>;
+    async fn write_aliases(&self);
+}
+
+#[async_trait]
+impl Alias for Client {
+    async fn alias(&mut self, args: &Args) -> Result<WekanResult, Error> {
+        info!("alias");
+        match &args.command {
+            Command::Set(s) => {
+                validate(&s.name, &s.command)?;
+                self.config
+                    .aliases
+                    .insert(s.name.to_owned(), s.command.to_owned());
+                self.write_aliases().await;
+                WekanResult::new_workflow(
+                    "Alias saved",
+                    &format!("Run it with 'wekan-cli {}'", s.name),
+                )
+                .ok()
+            }
+            Command::Remove(r) => match self.config.aliases.remove(&r.name) {
+                Some(_c) => {
+                    self.write_aliases().await;
+                    WekanResult::new_msg("Alias removed").ok()
+                }
+                None => CliError::new_msg(&format!("No alias '{}'", r.name)).err(),
+            },
+            Command::Ls if self.config.aliases.is_empty() => WekanResult::new_workflow(
+                "No aliases",
+                "Add one with 'config alias set NAME COMMAND'",
+            )
+            .ok(),
+            Command::Ls => WekanResult::new_msg(&render(&self.config.aliases)).ok(),
+        }
+    }
+
+    /// The active config is a copy of the config of its context, both keep the aliases.
+    #[cfg(not(test))]
+    async fn write_aliases(&self) {
+        let mut active = self.config.clone();
+        active.context = None;
+        active
+            .write(String::from("/config"), self.config.clone())
+            .await;
+        if self.config.context.is_some() {
+            self.config
+                .write(String::from("/config"), self.config.clone())
+                .await;
+        }
+    }
+
+    #[cfg(test)]
+    async fn write_aliases(&self) {}
+}
+
+fn validate(name: &str, command: &str) -> Result<(), Error> {
+    if name.starts_with('-') || name.contains(char::is_whitespace) {
+        return Err(
+            CliError::new_msg("Alias names are single words without leading '-'").as_enum(),
+        );
+    }
+    if WekanParser::command().find_subcommand(name).is_some() {
+        return Err(CliError::new_msg(&format!("'{}' is already a command", name)).as_enum());
+    }
+    match shell_words::split(command) {
+        Ok(w) if !w.is_empty() => Ok(()),
+        Ok(_w) => Err(CliError::new_msg("Alias command is empty").as_enum()),
+        Err(_e) => Err(CliError::new_msg("Missing closing quote").as_enum()),
+    }
+}
+
+pub fn render(aliases: &BTreeMap<String, String>) -> String {
+    let width = aliases.keys().map(|k| k.len()).max().unwrap_or(0);
+    aliases
+        .iter()
+        .map(|(name, command)| format!("{:<width$}  {}\n", name, command, width = width))
+        .collect()
+}
+
+/// Replaces the first word after the global options with the words of its alias.
+/// Commands win over aliases and expanded words are not expanded again.
+pub fn expand(argv: Vec<String>, aliases: &BTreeMap<String, String>) -> Vec<String> {
+    if aliases.is_empty() {
+        return argv;
+    }
+    let command = WekanParser::command();
+    let mut i = 1;
+    while let Some(word) = argv.get(i) {
+        if !word.starts_with('-') {
+            break;
+        }
+        i += if takes_value(&command, word) == Some(true) {
+            2
+        } else {
+            1
+        };
+    }
+    let words = match argv.get(i) {
+        Some(w) if command.find_subcommand(w).is_none() => match aliases.get(w) {
+            Some(a) => a,
+            None => return argv,
+        },
+        _ => return argv,
+    };
+    trace!("{} => {}", argv[i], words);
+    match shell_words::split(words) {
+        Ok(w) => {
+            let mut expanded = argv[..i].to_vec();
+            expanded.extend(place_options(&command, w, &argv[i + 1..]));
+            expanded
+        }
+        Err(_e) => argv,
+    }
+}
+
+/// Some(true) if the command has the option and it takes a value.
+fn takes_value(command: &clap::Command, word: &str) -> Option<bool> {
+    let name = word.split('=').next().unwrap_or(word);
+    command
+        .get_arguments()
+        .find(|a| match name.strip_prefix("--") {
+            Some(l) => a.get_long() == Some(l),
+            None => name.len() == 2 && a.get_short() == name.chars().nth(1),
+        })
+        .map(|a| a.is_takes_value_set() && !word.contains('='))
+}
+
+/// Options following an alias go to the command of the alias which knows them, so after
+/// 'done = card mv Done' the call 'done -b B -n C' becomes 'card -b B -n C mv Done'.
+/// Everything from the first other word on is appended.
+fn place_options(root: &clap::Command, alias: Vec<String>, rest: &[String]) -> Vec<String> {
+    // The commands of the alias with the position after their last word.
+    let mut commands: Vec<(clap::Command, usize)> = Vec::new();
+    let mut current = root.clone();
+    for (j, word) in alias.iter().enumerate() {
+        if let Some(sub) = current.find_subcommand(word).cloned() {
+            if let Some(last) = commands.last_mut() {
+                last.1 = j;
+            }
+            commands.push((sub.clone(), alias.len()));
+            current = sub;
+        }
+    }
+    let mut inserts: Vec<Vec<String>> = vec![Vec::new(); commands.len()];
+    let mut k = 0;
+    while let Some(word) = rest.get(k) {
+        if !word.starts_with('-') {
+            break;
+        }
+        let found = commands
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(c, (command, _end))| takes_value(command, word).map(|t| (c, t)));
+        let (c, with_value) = match found {
+            Some(f) => f,
+            None => break,
+        };
+        let n = if with_value { 2 } else { 1 };
+        inserts[c].extend(rest.iter().skip(k).take(n).cloned());
+        k += n;
+    }
+    let mut placed = Vec::new();
+    for (j, word) in alias.iter().enumerate() {
+        for (c, (_command, end)) in commands.iter().enumerate() {
+            if *end == j {
+                placed.append(&mut inserts[c]);
+            }
+        }
+        placed.push(word.to_owned());
+    }
+    for i in inserts {
+        placed.extend(i);
+    }
+    placed.extend(rest.iter().skip(k).cloned());
+    placed
+}
+
+/// Expands the alias of a shell or batch line.
+pub fn expand_words(words: Vec<String>, config: &UserConfig) -> Vec<String> {
+    let mut argv = vec![String::from("wekan-cli")];
+    argv.extend(words);
+    expand(argv, &config.aliases).split_off(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use clap::Parser;
+
+    fn argv(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    fn aliases() -> BTreeMap<String, String> {
+        let mut aliases = BTreeMap::new();
+        aliases.insert(
+            String::from("today"),
+            String::from("card -b Team -l 'Today and tomorrow'"),
+        );
+        aliases.insert(String::from("card"), String::from("board"));
+        aliases
+    }
+
+    #[test]
+    fn expand_aliases() {
+        assert_eq!(
+            expand(
+                argv(&["wekan-cli", "-r", "-o", "long", "today", "-n", "Write"]),
+                &aliases()
+            ),
+            argv(&[
+                "wekan-cli",
+                "-r",
+                "-o",
+                "long",
+                "card",
+                "-b",
+                "Team",
+                "-l",
+                "Today and tomorrow",
+                "-n",
+                "Write"
+            ])
+        );
+        assert_eq!(
+            expand(argv(&["wekan-cli", "card", "ls"]), &aliases()),
+            argv(&["wekan-cli", "card", "ls"])
+        );
+        assert_eq!(
+            expand(argv(&["wekan-cli", "-o", "today"]), &aliases()),
+            argv(&["wekan-cli", "-o", "today"])
+        );
+        let mut done = BTreeMap::new();
+        done.insert(String::from("done"), String::from("card mv Done"));
+        assert_eq!(
+            expand(
+                argv(&["wekan-cli", "done", "-b", "B", "-l", "L", "-n", "X"]),
+                &done
+            ),
+            argv(&[
+                "wekan-cli",
+                "card",
+                "-b",
+                "B",
+                "-l",
+                "L",
+                "-n",
+                "X",
+                "mv",
+                "Done"
+            ])
+        );
+        assert!(WekanParser::try_parse_from(expand(
+            argv(&["wekan-cli", "done", "-b", "B", "-l", "L", "-n", "X"]),
+            &done
+        ))
+        .is_ok());
+        assert_eq!(
+            expand(
+                argv(&["wekan-cli", "done", "--board=B", "-n", "X", "extra"]),
+                &done
+            ),
+            argv(&[
+                "wekan-cli",
+                "card",
+                "--board=B",
+                "-n",
+                "X",
+                "mv",
+                "Done",
+                "extra"
+            ])
+        );
+        assert_eq!(
+            render(&aliases()),
+            "card   board\ntoday  card -b Team -l 'Today and tomorrow'\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn set_and_remove_alias() {
+        let mut client = Client::new(UserConfig::mock());
+        let set = |name: &str, command: &str| Args {
+            command: Command::Set(SetAlias {
+                name: name.to_string(),
+                command: command.to_string(),
+            }),
+        };
+        assert!(client.alias(&set("card", "board ls")).await.is_err());
+        assert!(client.alias(&set("-t", "board ls")).await.is_err());
+        assert!(client.alias(&set("done", "card 'mv")).await.is_err());
+        let res = client.alias(&set("done", "card mv Done")).await.unwrap();
+        assert_eq!(res.get_msg(), "Alias saved");
+        assert_eq!(
+            expand_words(argv(&["done", "-y"]), &client.config),
+            argv(&["card", "mv", "Done", "-y"])
+        );
+        let rm = Args {
+            command: Command::Remove(RemoveAlias {
+                name: String::from("done"),
+            }),
+        };
+        assert!(client.alias(&rm).await.is_ok());
+        assert!(client.alias(&rm).await.is_err());
+        let ls = Args {
+            command: Command::Ls,
+        };
+        assert_eq!(client.alias(&ls).await.unwrap().get_msg(), "No aliases");
+    }
+}
@@ -5,7 +5,10 @@ use super::{
 };
 use clap::{Args as ClapArgs, Subcommand};
 
-use super::credentials::{DeleteCredentials, SetCredentials};
+use super::{
+    alias::Args as AliasArgs,
+    credentials::{DeleteCredentials, SetCredentials},
+};
 
 /// Config
 #[derive(ClapArgs, Clone, Debug)]
@@ -34,6 +37,8 @@ pub enum Command {
     #[cfg(feature = "store")]
     #[clap(name = "remove-context")]
     DeleteContext(DeleteContext),
+    #[clap(name = "alias")]
+    Alias(AliasArgs),
     #[cfg(feature = "store")]
     #[clap(name = "remove")]
     Remove(RemoveConfig),
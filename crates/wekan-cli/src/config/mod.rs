@@ -1,3 +1,4 @@
+pub mod alias;
 pub mod argument;
 #[cfg(feature = "store")]
 pub mod context;
@@ -1,6 +1,7 @@
 use crate::{command::BaseCommand, error::Error, result::WekanResult};
 
 use super::{
+    alias::Alias,
     argument::{Args, Command as ConfigCommand},
     credentials::Authenticate,
 };
@@ -8,7 +9,10 @@ use super::{
 use crate::config::context::Context;
 use crate::config::credentials::ClearConfig;
 #[cfg(feature = "store")]
-use crate::error::{CliError, Transform};
+use crate::{
+    error::{CliError, Transform},
+    prompt::approve,
+};
 use async_trait::async_trait;
 #[cfg(feature = "store")]
 use clap::Args as ClapArgs;
@@ -35,12 +39,27 @@ impl Runner {
             ConfigCommand::DeleteContext(a) => self.client.delete(&a).await,
             #[cfg(feature = "store")]
             ConfigCommand::UseContext(a) => self.client.r#use(&a).await,
+            ConfigCommand::Alias(a) => self.client.alias(&a).await,
             #[cfg(feature = "store")]
             ConfigCommand::Remove(rm) => self.remove_config(&rm).await,
         }
     }
     #[cfg(feature = "store")]
     async fn remove_config(&mut self, rm_args: &RemoveConfig) -> Result<WekanResult, Error> {
+        let question = match &rm_args.context {
+            Some(context) => format!(
+                "Remove context '{}' at {}?",
+                context,
+                <UserConfig as Butler>::get_default_path() + context
+            ),
+            None => format!(
+                "Remove every context and config at {}?",
+                <UserConfig as Butler>::get_default_path()
+            ),
+        };
+        if !approve(&question, rm_args.yes)? {
+            return WekanResult::new_msg("Aborted, nothing was removed").ok();
+        }
         match &rm_args.context {
             Some(context) => {
                 let path_to_be_deleted = <UserConfig as Butler>::get_default_path() + context;
@@ -80,43 +99,16 @@ impl BaseCommand<Args, Client> for Runner {
 #[derive(ClapArgs, Debug, Clone)]
 #[clap(
     about = "Remove config or context",
-    long_about = "Remove config or context with confirmation flags "
+    long_about = "Remove the whole config or only a context after confirmation"
 )]
 pub struct RemoveConfig {
-    #[clap(
-        requires = "remove_complete",
-        validator = only_config_allowed,
-    )]
-    pub please: String,
-    #[clap(
-        short = 'f',
-        long,
-        group = "remove_complete",
-        parse(from_flag),
-        help = "Confirm deletion"
-    )]
-    pub confirm: bool,
-    #[clap(
-        short = 'c',
-        requires = "remove_context",
-        long,
-        help = "Select context to remove"
-    )]
+    #[clap(short = 'c', long, help = "Select context to remove")]
     pub context: Option<String>,
     #[clap(
         short = 'y',
         long,
         parse(from_flag),
-        group = "remove_context",
-        help = "Confirm context selection"
+        help = "Remove without confirmation"
     )]
-    pub context_confirm: bool,
-}
-#[cfg(feature = "store")]
-fn only_config_allowed(s: &str) -> Result<(), String> {
-    if s.contains("please") && s.len() == 6 {
-        Ok(())
-    } else {
-        Err(String::from("If you want to remove, say please."))
-    }
+    pub yes: bool,
 }
@@ -0,0 +1,419 @@
+use crate::{
+    card::record::label_names,
+    error::Error,
+    resolver::Query,
+    result::WekanResult,
+    snapshot::{CardEntry, Snapshot},
+};
+use chrono::{DateTime, Utc};
+use clap::Args as ClapArgs;
+use log::{info, trace};
+use std::{collections::HashMap, path::PathBuf};
+use wekan_common::artifact::{
+    board::Details as BDetails,
+    card::Details as CDetails,
+    checklist::Details as ChDetails,
+    common::{AType, Artifact, Base, MostDetails},
+};
+use wekan_core::client::{BoardApi, CardApi, ChecklistApi, Client};
+
+#[cfg(test)]
+use crate::tests::mocks::Artifacts;
+#[cfg(not(test))]
+use wekan_core::http::operation::Artifacts;
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Compare a board file with the server",
+    long_about = "Show the field-level differences between a board file and the live board, and the artifacts only one of them has, without applying anything"
+)]
+pub struct Args {
+    #[clap(short = 'f', long, help = "Board file in the format of 'board import'")]
+    pub file: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub field: String,
+    pub server: String,
+    pub file: String,
+}
+
+impl Change {
+    fn new(field: &str, server: &str, file: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            server: server.to_string(),
+            file: file.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// Named in the file but not found on the server.
+    Missing(AType, String),
+    /// Found on the server but not named in the file.
+    Extra(AType, String),
+    Changed(AType, String, Vec<Change>),
+}
+
+pub async fn run(args: &Args, query: &mut Query<'_>) -> Result<WekanResult, Error> {
+    info!("diff");
+    let snapshot = Snapshot::read(&args.file).await?;
+    let differences = match query
+        .find_exact_id(AType::Board, None, None, &snapshot.title)
+        .await
+    {
+        Ok(board_id) => compare(query, &board_id, &snapshot).await?,
+        Err(_e) => vec![Difference::Missing(AType::Board, snapshot.title.to_owned())],
+    };
+    trace!("{:?}", differences);
+    if differences.is_empty() {
+        WekanResult::new_msg(&format!(
+            "Board '{}' matches {}",
+            snapshot.title,
+            args.file.display()
+        ))
+        .ok()
+    } else {
+        WekanResult::new_exit(&render(&differences), 1, None).ok()
+    }
+}
+
+/// Matches lists, cards and checklists by id first and by title second. Lists, cards and
+/// checklists of matched cards which are left over on the server are reported as extra.
+pub async fn compare(
+    query: &Query<'_>,
+    board_id: &str,
+    snapshot: &Snapshot,
+) -> Result<Vec<Difference>, Error> {
+    let mut board_client = <Client as BoardApi>::new(query.config.clone());
+    let labels = match board_client.get_one::<BDetails>(board_id).await {
+        Ok(b) => b.get_labels(),
+        Err(_e) => Vec::new(),
+    };
+    let lists = query
+        .inquire(AType::List, Some(board_id), None, None, true)
+        .await?;
+    let mut differences = Vec::new();
+    let mut list_titles = HashMap::new();
+    let mut matched = Vec::new();
+    for list in &snapshot.lists {
+        list_titles.insert(list._id.to_owned(), list.title.to_owned());
+        match find(&lists, &list._id, &list.title) {
+            Some(l) if l.title != list.title => {
+                matched.push(l._id.to_owned());
+                differences.push(Difference::Changed(
+                    AType::List,
+                    l.title.to_owned(),
+                    vec![Change::new("title", &l.title, &list.title)],
+                ))
+            }
+            Some(l) => matched.push(l._id.to_owned()),
+            None => differences.push(Difference::Missing(AType::List, list.title.to_owned())),
+        }
+    }
+    for list in lists.iter().filter(|l| !matched.contains(&l._id)) {
+        differences.push(Difference::Extra(AType::List, list.title.to_owned()));
+    }
+    let mut cards = Vec::new();
+    for list in &lists {
+        for card in query
+            .inquire(AType::Card, Some(board_id), Some(&list._id), None, true)
+            .await?
+        {
+            cards.push((card, list.to_owned()));
+        }
+    }
+    let mut matched = Vec::new();
+    for card in &snapshot.cards {
+        let file_list = match list_titles.get(&card.list_id) {
+            Some(t) => t.to_owned(),
+            None => card.list_id.to_owned(),
+        };
+        let found = cards
+            .iter()
+            .find(|(c, _l)| c._id == card._id)
+            .or_else(|| cards.iter().find(|(c, _l)| c.title == card.title));
+        let (server_card, server_list) = match found {
+            Some(f) => f,
+            None => {
+                differences.push(Difference::Missing(AType::Card, card.title.to_owned()));
+                continue;
+            }
+        };
+        matched.push(server_card._id.to_owned());
+        let mut client = <Client as CardApi>::new(query.config.clone(), board_id, &server_list._id);
+        let details = client.get_one::<CDetails>(&server_card._id).await?;
+        let changes = compare_card(
+            card,
+            &file_list,
+            &details,
+            &server_list.title,
+            &label_names(&details.get_label_ids(), &labels),
+        );
+        if !changes.is_empty() {
+            differences.push(Difference::Changed(
+                AType::Card,
+                server_card.title.to_owned(),
+                changes,
+            ));
+        }
+        let checklists = snapshot
+            .checklists
+            .iter()
+            .filter(|c| c.card_id == card._id)
+            .collect::<Vec<_>>();
+        let mut client =
+            <Client as ChecklistApi>::new(query.config.clone(), board_id, &server_card._id);
+        let server_checklists = client.get_all(AType::Checklist).await?;
+        let mut matched_checklists = Vec::new();
+        for checklist in checklists {
+            match find(&server_checklists, &checklist._id, &checklist.title) {
+                Some(c) => {
+                    matched_checklists.push(c._id.to_owned());
+                    let items = client
+                        .get_one::<ChDetails>(&c._id)
+                        .await?
+                        .get_items()
+                        .iter()
+                        .map(|i| i.title.to_owned())
+                        .collect::<Vec<String>>();
+                    if items != checklist.items {
+                        differences.push(Difference::Changed(
+                            AType::Checklist,
+                            c.title.to_owned(),
+                            vec![Change::new(
+                                "items",
+                                &items.join(", "),
+                                &checklist.items.join(", "),
+                            )],
+                        ));
+                    }
+                }
+                None => differences.push(Difference::Missing(
+                    AType::Checklist,
+                    checklist.title.to_owned(),
+                )),
+            }
+        }
+        for c in server_checklists
+            .iter()
+            .filter(|c| !matched_checklists.contains(&c._id))
+        {
+            differences.push(Difference::Extra(AType::Checklist, c.title.to_owned()));
+        }
+    }
+    for (card, _list) in cards.iter().filter(|(c, _l)| !matched.contains(&c._id)) {
+        differences.push(Difference::Extra(AType::Card, card.title.to_owned()));
+    }
+    Ok(differences)
+}
+
+fn find<'a>(artifacts: &'a [Artifact], id: &str, title: &str) -> Option<&'a Artifact> {
+    artifacts
+        .iter()
+        .find(|a| a._id == id)
+        .or_else(|| artifacts.iter().find(|a| a.title == title))
+}
+
+/// Fields which are not set in the file are not compared.
+pub fn compare_card(
+    file: &CardEntry,
+    file_list: &str,
+    server: &CDetails,
+    server_list: &str,
+    server_labels: &[String],
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if file.title != server.get_title() {
+        changes.push(Change::new("title", &server.get_title(), &file.title));
+    }
+    if file_list != server_list {
+        changes.push(Change::new("list", server_list, file_list));
+    }
+    if let Some(d) = &file.description {
+        let description = server.get_description();
+        if d.trim_end() != description.trim_end() {
+            changes.push(Change::new("description", &description, d));
+        }
+    }
+    if let Some(d) = &file.due_at {
+        if !same_date(d, &server.get_due_at()) {
+            changes.push(Change::new("due_at", &server.get_due_at(), d));
+        }
+    }
+    if let Some(d) = &file.end_at {
+        if !same_date(d, &server.get_end_at()) {
+            changes.push(Change::new("end_at", &server.get_end_at(), d));
+        }
+    }
+    if let Some(l) = &file.labels {
+        let mut file_labels = l.to_owned();
+        let mut labels = server_labels.to_vec();
+        file_labels.sort();
+        labels.sort();
+        if file_labels != labels {
+            changes.push(Change::new(
+                "labels",
+                &server_labels.join(", "),
+                &l.join(", "),
+            ));
+        }
+    }
+    changes
+}
+
+/// A date without time in the file matches any time of that day.
+fn same_date(file: &str, server: &str) -> bool {
+    if file == server || (file.len() == 10 && server.starts_with(file)) {
+        return true;
+    }
+    match (
+        file.parse::<DateTime<Utc>>(),
+        server.parse::<DateTime<Utc>>(),
+    ) {
+        (Ok(f), Ok(s)) => f == s,
+        _ => false,
+    }
+}
+
+pub fn render(differences: &[Difference]) -> String {
+    let mut output = String::new();
+    for d in differences {
+        match d {
+            Difference::Missing(t, title) => {
+                output.push_str(&format!("+ {} '{}'\n", t.to_string(), title))
+            }
+            Difference::Extra(t, title) => {
+                output.push_str(&format!("- {} '{}'\n", t.to_string(), title))
+            }
+            Difference::Changed(t, title, changes) => {
+                output.push_str(&format!("~ {} '{}'\n", t.to_string(), title));
+                for c in changes {
+                    output.push_str(&render_value('-', &c.field, &c.server));
+                    output.push_str(&render_value('+', &c.field, &c.file));
+                }
+            }
+        }
+    }
+    output
+}
+
+fn render_value(sign: char, field: &str, value: &str) -> String {
+    if value.contains('\n') {
+        let mut output = format!("  {} {}:\n", sign, field);
+        for line in value.trim_end().lines() {
+            output.push_str(&format!("  {}   {}\n", sign, line));
+        }
+        output
+    } else {
+        format!("  {} {}: {}\n", sign, field, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use wekan_common::artifact::tests::MockDetails;
+    use wekan_core::config::UserConfig;
+
+    fn card() -> CardEntry {
+        CardEntry {
+            _id: String::from("my-id"),
+            title: String::from("my-title"),
+            list_id: String::from("done-id"),
+            swimlane_id: None,
+            description: Some(String::from("first\nsecond")),
+            due_at: Some(String::from("2022-10-12")),
+            end_at: None,
+            sort: None,
+            labels: Some(vec![String::from("bug")]),
+            members: None,
+        }
+    }
+
+    #[test]
+    fn compare_fields() {
+        let details =
+            <CDetails as MockDetails>::mock("my-id", "my-title", "2022-10-12T10:00:00.000Z");
+        let changes = compare_card(&card(), "Done", &details, "Todo", &[String::from("bug")]);
+        assert_eq!(
+            changes,
+            vec![
+                Change::new("list", "Todo", "Done"),
+                Change::new("description", &details.get_description(), "first\nsecond"),
+            ]
+        );
+        assert!(same_date(
+            "2022-10-12T10:00:00Z",
+            "2022-10-12T10:00:00.000Z"
+        ));
+        assert!(!same_date("2022-10-13", "2022-10-12T10:00:00.000Z"));
+    }
+
+    #[test]
+    fn render_differences() {
+        let differences = vec![
+            Difference::Missing(AType::List, String::from("Later")),
+            Difference::Changed(
+                AType::Card,
+                String::from("my-title"),
+                vec![
+                    Change::new("list", "Todo", "Done"),
+                    Change::new("description", "", "first\nsecond"),
+                ],
+            ),
+            Difference::Extra(AType::Card, String::from("Only on the server")),
+        ];
+        let expected = concat!(
+            "+ list 'Later'\n",
+            "~ card 'my-title'\n",
+            "  - list: Todo\n",
+            "  + list: Done\n",
+            "  - description: \n",
+            "  + description:\n",
+            "  +   first\n",
+            "  +   second\n",
+            "- card 'Only on the server'\n",
+        );
+        assert_eq!(render(&differences), expected);
+    }
+
+    #[tokio::test]
+    async fn compare_with_server() {
+        let snapshot: Snapshot = serde_yaml::from_str(concat!(
+            "title: fake-board-title-1\n",
+            "lists:\n",
+            "  - _id: fake-list-id-1\n",
+            "    title: fake-list-title-1\n",
+            "  - _id: old-list-id\n",
+            "    title: Later\n",
+            "cards:\n",
+            "  - _id: fake-card-id-1\n",
+            "    title: fake-card-title\n",
+            "    list_id: fake-list-id-1\n",
+            "  - _id: old-card-id\n",
+            "    title: New card\n",
+            "    list_id: old-list-id\n",
+        ))
+        .unwrap();
+        let query = Query::new(UserConfig::mock(), "", false);
+        let differences = compare(&query, "fake-board-id-1", &snapshot).await.unwrap();
+        assert_eq!(
+            differences,
+            vec![
+                Difference::Missing(AType::List, String::from("Later")),
+                Difference::Extra(AType::List, String::from("fake-list-title-2")),
+                Difference::Extra(AType::Checklist, String::from("fake-checklist-title-1")),
+                Difference::Extra(AType::Checklist, String::from("fake-checklist-title-2")),
+                Difference::Missing(AType::Card, String::from("New card")),
+                // The mocked lists share their card ids.
+                Difference::Extra(AType::Card, String::from("fake-card-title-2")),
+                Difference::Extra(AType::Card, String::from("fake-card-title-2")),
+            ]
+        );
+    }
+}
@@ -1,9 +1,15 @@
-use crate::{error::Error, result::WekanResult};
+use crate::{
+    error::Error,
+    markup::{BoardDocument, CardDocument, Markup},
+    result::WekanResult,
+};
 use log::info;
 use std::cmp::Ordering;
 use wekan_common::artifact::{
     card::Details as CardDetails,
-    common::{Base, BaseDetails, IdReturner, MostDetails, SortedArtifact, WekanDisplay},
+    common::{
+        elisp_list, Base, BaseDetails, Elisp, IdReturner, MostDetails, SortedArtifact, WekanDisplay,
+    },
 };
 
 #[cfg(test)]
@@ -46,11 +52,15 @@ impl CliDisplay {
         res
     }
 
-    pub fn format_most_details<T: WekanDisplay + BaseDetails + MostDetails>(
+    pub fn format_most_details<T: WekanDisplay + BaseDetails + MostDetails + Elisp>(
         &mut self,
         artifact_details: T,
+        format: &Option<String>,
     ) -> Result<WekanResult, Error> {
         info!("format_most_details");
+        if is_elisp(format.as_ref()) {
+            return WekanResult::new_elisp(&artifact_details.to_elisp()).ok();
+        }
         let mut properties_to_show = vec![
             artifact_details.get_id(),
             artifact_details.get_title(),
@@ -106,8 +116,15 @@ impl CliDisplay {
         )
         .ok()
     }
-    pub fn format_card_details(&mut self, details: CardDetails) -> Result<WekanResult, Error> {
+    pub fn format_card_details(
+        &mut self,
+        details: CardDetails,
+        format: &Option<String>,
+    ) -> Result<WekanResult, Error> {
         info!("format_card_details");
+        if is_elisp(format.as_ref()) {
+            return WekanResult::new_elisp(&details.to_elisp()).ok();
+        }
         let mut properties_to_show = vec![
             details.get_id(),
             details.get_title(),
@@ -166,12 +183,15 @@ impl CliDisplay {
         .ok()
     }
 
-    pub fn format_base_details<T: WekanDisplay + BaseDetails>(
+    pub fn format_base_details<T: WekanDisplay + BaseDetails + Elisp>(
         &mut self,
         artifact_details: T,
         format: &Option<String>,
     ) -> Result<WekanResult, Error> {
         info!("format_base_details");
+        if is_elisp(format.as_ref()) {
+            return WekanResult::new_elisp(&artifact_details.to_elisp()).ok();
+        }
         let properties_to_show = vec![
             artifact_details
                 .get_id()
@@ -220,12 +240,16 @@ impl CliDisplay {
         output = output.trim().to_string();
         WekanResult::new_msg(&output.finish_up()).ok()
     }
-    pub fn format_vec<T: IdReturner + std::fmt::Debug + Base + std::fmt::Display>(
+    pub fn format_vec<T: IdReturner + std::fmt::Debug + Base + std::fmt::Display + Elisp>(
         &mut self,
         artifacts: Vec<T>,
         format: Option<String>,
     ) -> Result<WekanResult, Error> {
         info!("format_vec");
+        if is_elisp(format.as_ref()) {
+            return WekanResult::new_elisp(&elisp_list(artifacts.iter().map(|a| a.to_elisp())))
+                .ok();
+        }
         let headlines_to_show = vec![String::from("ID"), String::from("TITLE")];
         let mut output = String::new();
         headlines_to_show
@@ -252,13 +276,21 @@ impl CliDisplay {
         WekanResult::new_workflow(&output.finish_up(), "Get or update details of an artifact").ok()
     }
 
-    pub fn prepare_output<T: IdReturner + std::fmt::Debug + Base + std::fmt::Display>(
+    pub fn prepare_output<T: IdReturner + std::fmt::Debug + Base + std::fmt::Display + Elisp>(
         &mut self,
         output: &str,
         artifacts: Vec<T>,
         format: Option<String>,
     ) -> Result<WekanResult, Error> {
         info!("prepare_output");
+        if is_elisp(format.as_ref()) {
+            let children = elisp_list(artifacts.iter().map(|a| a.to_elisp()));
+            return WekanResult::new_elisp(&format!(
+                "(:details {} :children {})",
+                output, children
+            ))
+            .ok();
+        }
         let mut full_output = String::new();
         full_output.push_str(output);
         let second_output = self.format_vec(artifacts, format).unwrap();
@@ -317,6 +349,49 @@ impl CliDisplay {
         WekanResult::new_msg(&output.finish_up()).ok()
     }
 
+    /// Lists in board order, each with its cards as 'cards' entry.
+    pub fn format_table_elisp<T: Elisp>(
+        &mut self,
+        lists: Vec<T>,
+        mut cards: Vec<Vec<T>>,
+    ) -> Result<WekanResult, Error> {
+        info!("format_table_elisp");
+        cards.iter_mut().for_each(|x| x.reverse());
+        let output = elisp_list(lists.iter().enumerate().map(|(i, l)| {
+            let list = l.to_elisp();
+            let cards = match cards.get(i) {
+                Some(c) => elisp_list(c.iter().map(|c| c.to_elisp())),
+                None => String::from("nil"),
+            };
+            // An alist is '(...)', anything else like 'nil' becomes the empty alist.
+            match list.strip_prefix('(').and_then(|l| l.strip_suffix(')')) {
+                Some(entries) if !entries.trim().is_empty() => {
+                    format!("({} (cards . {}))", entries, cards)
+                }
+                _ => format!("((cards . {}))", cards),
+            }
+        }));
+        WekanResult::new_elisp(&output).ok()
+    }
+
+    pub fn format_board_document(
+        &mut self,
+        board: &BoardDocument,
+        markup: &Markup,
+    ) -> Result<WekanResult, Error> {
+        info!("format_board_document");
+        WekanResult::new_msg(&board.render(markup)).ok()
+    }
+
+    pub fn format_card_document(
+        &mut self,
+        card: &CardDocument,
+        markup: &Markup,
+    ) -> Result<WekanResult, Error> {
+        info!("format_card_document");
+        WekanResult::new_msg(&card.render(markup, 1)).ok()
+    }
+
     fn unwrap_format<T: IdReturner>(format: Option<&String>, a: &T) -> usize {
         match format {
             Some(f) => {
@@ -330,6 +405,10 @@ impl CliDisplay {
         }
     }
 }
+pub fn is_elisp(format: Option<&String>) -> bool {
+    matches!(format.map(|f| f.as_str()), Some("elisp"))
+}
+
 fn cmp_by_length(x: &str, y: &str) -> Ordering {
     if x.len() > y.len() {
         return Ordering::Greater;
@@ -380,7 +459,11 @@ impl FinishUp for String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wekan_common::artifact::{card::Details as CDetails, tests::MockDetails};
+    use wekan_common::artifact::{
+        card::Details as CDetails,
+        common::{AType, Artifact},
+        tests::MockDetails,
+    };
 
     #[test]
     fn format_base_details_output_normal() {
@@ -441,4 +524,74 @@ mod tests {
     fn if_field_vailable_false() {
         assert_eq!(if_field_available("HEADER", ""), String::new())
     }
+
+    #[test]
+    fn format_vec_elisp_escapes_strings() {
+        let a = Artifact {
+            _id: String::from("my-id"),
+            title: String::from("say \"hi\" \\o/"),
+            r#type: AType::Card,
+        };
+        let mut display = CliDisplay::new(Vec::new());
+        let res = display
+            .format_vec(vec![a], Some(String::from("elisp")))
+            .unwrap();
+        assert!(res.is_elisp());
+        assert_eq!(
+            res.get_msg(),
+            "(((_id . \"my-id\") (title . \"say \\\"hi\\\" \\\\o/\")))"
+        );
+    }
+
+    #[test]
+    fn format_base_details_elisp() {
+        let a = CDetails::mock("my-id", "my title", "2022-10-15T208Z");
+        let mut display = CliDisplay::new(Vec::new());
+        let res = display
+            .format_base_details(a, &Some(String::from("elisp")))
+            .unwrap();
+        let msg = res.get_msg();
+        assert!(msg.starts_with("((id . \"my-id\") (title . \"my title\")"));
+        assert!(!msg.contains("(type . \"Details\")"));
+        assert!(msg.ends_with(')'));
+    }
+
+    #[test]
+    fn to_elisp_alist_keeps_type_field() {
+        let a = CDetails::mock("my-id", "my title", "2022-10-15T208Z");
+        let msg = a.to_elisp();
+        assert_eq!(msg.matches("(type . ").count(), 1);
+        assert!(!msg.contains("(type . \"Details\")"));
+    }
+
+    #[test]
+    fn format_table_elisp() {
+        let list = <Artifact as MockDetails>::mock("list-id", "Todo", "list");
+        let card = <Artifact as MockDetails>::mock("card-id", "Write", "card");
+        let mut display = CliDisplay::new(Vec::new());
+        let res = display
+            .format_table_elisp(vec![list], vec![vec![card]])
+            .unwrap();
+        assert_eq!(
+            res.get_msg(),
+            "(((_id . \"list-id\") (title . \"Todo\") (cards . (((_id . \"card-id\") (title . \"Write\"))))))"
+        );
+    }
+
+    struct Nil;
+
+    impl Elisp for Nil {
+        fn to_elisp(&self) -> String {
+            String::from("nil")
+        }
+    }
+
+    #[test]
+    fn format_table_elisp_without_alist() {
+        let mut display = CliDisplay::new(Vec::new());
+        let res = display
+            .format_table_elisp(vec![Nil, Nil], vec![Vec::new()])
+            .unwrap();
+        assert_eq!(res.get_msg(), "(((cards . ())) ((cards . nil)))");
+    }
 }
@@ -0,0 +1,196 @@
+use crate::{
+    error::Error,
+    resolver::Query,
+    result::WekanResult,
+    search::{boards, datetime, scan, Hit},
+    watch::parse_duration,
+};
+use chrono::{DateTime, Utc};
+use clap::Args as ClapArgs;
+use crossterm::{style::Stylize, tty::IsTty};
+use log::info;
+use std::time::Duration;
+use wekan_common::artifact::common::MostDetails;
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Show due and overdue cards",
+    long_about = "Show unfinished cards which are overdue or due within a time window, most urgent first. Exits with 1 if any card is shown and with 2 if a board could not be searched"
+)]
+pub struct Args {
+    #[clap(short = 'b', long, help = "Board name")]
+    pub board: Option<String>,
+    #[clap(
+        short,
+        long,
+        default_value = "7d",
+        parse(try_from_str = parse_duration),
+        help = "Time window from now, e.g. 12h or 7d"
+    )]
+    pub within: Duration,
+    #[clap(long, parse(from_flag), help = "Only cards which are overdue")]
+    pub overdue: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DueCard {
+    pub hit: Hit,
+    pub due_at: DateTime<Utc>,
+    pub overdue: bool,
+}
+
+/// Finished cards have an end date. Cards flagged as overtime by the server count as overdue.
+pub fn collect(
+    hits: Vec<Hit>,
+    now: DateTime<Utc>,
+    within: Duration,
+    overdue_only: bool,
+) -> Vec<DueCard> {
+    let until = chrono::Duration::from_std(within)
+        .ok()
+        .and_then(|w| now.checked_add_signed(w))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC);
+    let mut cards = hits
+        .into_iter()
+        .filter_map(|hit| {
+            let details = hit.details.as_ref()?;
+            if !details.get_end_at().is_empty() {
+                return None;
+            }
+            let due = datetime(&details.get_due_at())?;
+            let overdue = due < now || details.is_overtime();
+            if (overdue_only && !overdue) || due > until {
+                return None;
+            }
+            Some(DueCard {
+                hit,
+                due_at: due,
+                overdue,
+            })
+        })
+        .collect::<Vec<DueCard>>();
+    cards.sort_by(|a, b| b.overdue.cmp(&a.overdue).then(a.due_at.cmp(&b.due_at)));
+    cards
+}
+
+fn relative(due_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let days = due_at
+        .date_naive()
+        .signed_duration_since(now.date_naive())
+        .num_days();
+    match days {
+        d if d < 0 => format!("{}d overdue", -d),
+        0 if due_at < now => String::from("overdue"),
+        0 => String::from("today"),
+        d => format!("in {}d", d),
+    }
+}
+
+pub fn render(cards: &[DueCard], now: DateTime<Utc>, color: bool) -> String {
+    let mut output = String::new();
+    for card in cards {
+        let line = format!(
+            "{}  {:<12}  {}",
+            card.due_at.format("%Y-%m-%d %H:%M"),
+            relative(card.due_at, now),
+            card.hit.path()
+        );
+        if color && card.overdue {
+            output.push_str(&line.red().to_string());
+        } else {
+            output.push_str(&line);
+        }
+        output.push('\n');
+    }
+    output
+}
+
+pub async fn run(args: &Args, query: &mut Query<'_>, color: bool) -> Result<WekanResult, Error> {
+    info!("due");
+    let boards = boards(query, args.board.as_deref()).await?;
+    let (hits, failed) = scan(query, &boards, true).await;
+    let now = Utc::now();
+    let cards = collect(hits, now, args.within, args.overdue);
+    let mut output = render(&cards, now, color && std::io::stdout().is_tty());
+    if failed > 0 {
+        output.push_str(&format!("{} boards could not be searched\n", failed));
+    }
+    if cards.is_empty() {
+        output.push_str("No cards due");
+    }
+    // An incomplete search must not pass as 'nothing due'.
+    match (failed, cards.is_empty()) {
+        (f, _) if f > 0 => WekanResult::new_exit(&output, 2, None).ok(),
+        (_, true) => WekanResult::new_msg(&output).ok(),
+        (_, false) => WekanResult::new_exit(&output, 1, None).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use wekan_common::artifact::{card::Details as CDetails, common::Artifact, tests::MockDetails};
+    use wekan_core::config::UserConfig;
+
+    fn hit(title: &str, due: &str, finished: bool) -> Hit {
+        let mut details = <CDetails as MockDetails>::mock(title, title, due);
+        if !finished {
+            details.end_at = None;
+        }
+        Hit {
+            board: <Artifact as MockDetails>::mock("b", "Work", "board"),
+            list: <Artifact as MockDetails>::mock("l", "Todo", "list"),
+            card: <Artifact as MockDetails>::mock(title, title, "card"),
+            details: Some(details),
+            labels: Vec::new(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2022-10-12T12:00:00Z".parse::<DateTime<Utc>>().unwrap()
+    }
+
+    #[test]
+    fn collect_by_urgency() {
+        let hits = vec![
+            hit("Later", "2022-10-30T10:00:00.000Z", false),
+            hit("Soon", "2022-10-14T10:00:00.000Z", false),
+            hit("Done", "2022-10-01T10:00:00.000Z", true),
+            hit("Late", "2022-10-10T10:00:00.000Z", false),
+            hit("Morning", "2022-10-12T08:00:00.000Z", false),
+            hit("Undated", "", false),
+        ];
+        let week = Duration::from_secs(7 * 24 * 60 * 60);
+        let cards = collect(hits.clone(), now(), week, false);
+        assert_eq!(
+            cards
+                .iter()
+                .map(|c| c.hit.card.title.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["Late", "Morning", "Soon"]
+        );
+        let expected = concat!(
+            "2022-10-10 10:00  2d overdue    /Work/Todo/Late\n",
+            "2022-10-12 08:00  overdue       /Work/Todo/Morning\n",
+            "2022-10-14 10:00  in 2d         /Work/Todo/Soon\n",
+        );
+        assert_eq!(render(&cards, now(), false), expected);
+        assert!(render(&cards, now(), true).contains("\u{1b}["));
+        assert_eq!(collect(hits, now(), week, true).len(), 2);
+        assert_eq!(datetime("2022-10-12"), "2022-10-12T00:00:00Z".parse().ok());
+    }
+
+    #[tokio::test]
+    async fn finished_cards_are_not_due() {
+        let mut query = Query::new(UserConfig::mock(), "", true);
+        let args = Args {
+            board: None,
+            within: Duration::from_secs(60),
+            overdue: false,
+        };
+        let res = run(&args, &mut query, false).await.unwrap();
+        assert_eq!(res.get_msg(), "No cards due");
+        assert_eq!(res.get_exit_code(), 0);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::result::WekanResult;
+use log::trace;
 use wekan_common::validation::constraint::Constraint;
 use wekan_core::error::Error as CoreError;
 
@@ -101,3 +102,12 @@ impl From<CoreError> for Error {
         Error::Core(error)
     }
 }
+
+/// Keeps the request of a dry run, other core errors are traced and reported with the message.
+pub fn failed(msg: &str, e: CoreError) -> Error {
+    trace!("{:?}", e);
+    match e {
+        CoreError::DryRun(_) => Error::Core(e),
+        _ => CliError::new_msg(msg).as_enum(),
+    }
+}
@@ -0,0 +1,431 @@
+use crate::{
+    error::{CliError, Error, Transform},
+    result::WekanResult,
+    snapshot::{CardEntry, ChecklistEntry, Entry, LabelEntry, Restore, Snapshot},
+};
+use clap::{Args as ClapArgs, Subcommand};
+use log::info;
+use serde::Deserialize;
+use std::{cmp::Ordering, collections::BTreeMap, path::PathBuf};
+use wekan_core::config::UserConfig;
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Import boards from other tools",
+    long_about = "Create a new board from an export of another kanban tool"
+)]
+pub struct Args {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+/// The following commands are available:
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    Trello(TrelloArgs),
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Import a Trello board",
+    long_about = "Import lists, cards, checklists with their finished items, labels, members and due dates from a Trello JSON export"
+)]
+pub struct TrelloArgs {
+    /// Trello JSON export
+    pub file: PathBuf,
+    #[clap(short = 'b', long, help = "Title of the new board")]
+    pub board: Option<String>,
+    #[clap(
+        short = 'm',
+        long = "member",
+        value_name = "TRELLO_USERNAME=WEKAN_USER_ID",
+        multiple_occurrences = true,
+        help = "Map a Trello member onto a Wekan user, can be repeated"
+    )]
+    pub members: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloBoard {
+    pub name: String,
+    #[serde(default)]
+    pub lists: Vec<TrelloList>,
+    #[serde(default)]
+    pub cards: Vec<TrelloCard>,
+    #[serde(default)]
+    pub checklists: Vec<TrelloChecklist>,
+    #[serde(default)]
+    pub labels: Vec<TrelloLabel>,
+    #[serde(default)]
+    pub members: Vec<TrelloMember>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloList {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub pos: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloCard {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    pub id_list: String,
+    pub due: Option<String>,
+    #[serde(default)]
+    pub due_complete: bool,
+    #[serde(default)]
+    pub id_labels: Vec<String>,
+    #[serde(default)]
+    pub id_members: Vec<String>,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub pos: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloChecklist {
+    pub id: String,
+    pub id_card: String,
+    pub name: String,
+    #[serde(default)]
+    pub check_items: Vec<TrelloCheckItem>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloCheckItem {
+    pub name: String,
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub pos: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloLabel {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrelloMember {
+    pub id: String,
+    pub username: String,
+}
+
+impl TrelloBoard {
+    /// Maps the Trello board onto a snapshot and collects everything which has no counterpart.
+    /// Members are mapped from their Trello username onto a Wekan user id.
+    pub fn to_snapshot(&self, members: &BTreeMap<String, String>) -> (Snapshot, Vec<String>) {
+        let mut report = Vec::new();
+        let mut lists: Vec<&TrelloList> = self.lists.iter().filter(|l| !l.closed).collect();
+        lists.sort_by(|a, b| a.pos.partial_cmp(&b.pos).unwrap_or(Ordering::Equal));
+        for l in self.lists.iter().filter(|l| l.closed) {
+            report.push(format!("List '{}' is archived and was skipped", l.name));
+        }
+        let mut cards: Vec<&TrelloCard> = Vec::new();
+        for c in &self.cards {
+            if c.closed {
+                report.push(format!("Card '{}' is archived and was skipped", c.name));
+            } else if !lists.iter().any(|l| l.id == c.id_list) {
+                report.push(format!(
+                    "Card '{}' belongs to a skipped list and was skipped",
+                    c.name
+                ));
+            } else {
+                cards.push(c);
+            }
+        }
+        cards.sort_by(|a, b| a.pos.partial_cmp(&b.pos).unwrap_or(Ordering::Equal));
+        let mut snapshot = Snapshot {
+            title: self.name.to_owned(),
+            lists: lists
+                .iter()
+                .map(|l| Entry {
+                    _id: l.id.to_owned(),
+                    title: l.name.to_owned(),
+                })
+                .collect(),
+            labels: self
+                .labels
+                .iter()
+                .map(|l| LabelEntry {
+                    name: l.name.to_owned(),
+                    color: label_color(&l.color),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for (i, c) in cards.iter().enumerate() {
+            let labels: Vec<String> = c.id_labels.iter().map(|id| self.label_name(id)).collect();
+            let mut user_ids = Vec::new();
+            let mut unmapped = Vec::new();
+            for id in &c.id_members {
+                let username = self.member_name(id);
+                match members.get(&username) {
+                    Some(user_id) => user_ids.push(user_id.to_owned()),
+                    None => unmapped.push(username),
+                }
+            }
+            if c.due_complete {
+                report.push(format!(
+                    "Completed due date of card '{}' was not mapped",
+                    c.name
+                ));
+            }
+            if !unmapped.is_empty() {
+                report.push(format!(
+                    "Members of card '{}' were not mapped, add --member NAME=WEKAN_USER_ID: {}",
+                    c.name,
+                    unmapped.join(", ")
+                ));
+            }
+            snapshot.cards.push(CardEntry {
+                _id: c.id.to_owned(),
+                title: c.name.to_owned(),
+                list_id: c.id_list.to_owned(),
+                swimlane_id: None,
+                description: if c.desc.is_empty() {
+                    None
+                } else {
+                    Some(c.desc.to_owned())
+                },
+                due_at: c.due.to_owned(),
+                end_at: None,
+                sort: Some(i as f32),
+                labels: if labels.is_empty() {
+                    None
+                } else {
+                    Some(labels)
+                },
+                members: if user_ids.is_empty() {
+                    None
+                } else {
+                    Some(user_ids)
+                },
+            });
+        }
+        for ch in &self.checklists {
+            if !snapshot.cards.iter().any(|c| c._id == ch.id_card) {
+                report.push(format!(
+                    "Checklist '{}' belongs to a skipped card and was skipped",
+                    ch.name
+                ));
+                continue;
+            }
+            let mut items: Vec<&TrelloCheckItem> = ch.check_items.iter().collect();
+            items.sort_by(|a, b| a.pos.partial_cmp(&b.pos).unwrap_or(Ordering::Equal));
+            snapshot.checklists.push(ChecklistEntry {
+                _id: ch.id.to_owned(),
+                card_id: ch.id_card.to_owned(),
+                title: ch.name.to_owned(),
+                items: items.iter().map(|i| i.name.to_owned()).collect(),
+                finished: items
+                    .iter()
+                    .filter(|i| i.state == "complete")
+                    .map(|i| i.name.to_owned())
+                    .collect(),
+            });
+        }
+        (snapshot, report)
+    }
+
+    fn label_name(&self, id: &str) -> String {
+        match self.labels.iter().find(|l| l.id == id) {
+            Some(l) if !l.name.is_empty() => l.name.to_owned(),
+            Some(l) => label_color(&l.color),
+            None => id.to_string(),
+        }
+    }
+
+    fn member_name(&self, id: &str) -> String {
+        match self.members.iter().find(|m| m.id == id) {
+            Some(m) => m.username.to_owned(),
+            None => id.to_string(),
+        }
+    }
+}
+
+/// Trello shades like 'green_dark' fall back to the Wekan base color, labels without color are white.
+fn label_color(color: &Option<String>) -> String {
+    match color {
+        Some(c) => c.split('_').next().unwrap_or(c).to_string(),
+        None => String::from("white"),
+    }
+}
+
+fn parse_members(members: &[String]) -> Result<BTreeMap<String, String>, Error> {
+    let mut map = BTreeMap::new();
+    for m in members {
+        match m.split_once('=') {
+            Some((username, user_id)) if !username.is_empty() && !user_id.is_empty() => {
+                map.insert(username.to_string(), user_id.to_string());
+            }
+            _ => {
+                return Err(CliError::new_msg(&format!(
+                    "Member '{}' is not of the form TRELLO_USERNAME=WEKAN_USER_ID",
+                    m
+                ))
+                .as_enum())
+            }
+        }
+    }
+    Ok(map)
+}
+
+pub struct Runner {
+    pub args: Args,
+    pub config: UserConfig,
+    pub user_id: String,
+}
+
+impl Runner {
+    pub fn new(args: Args, config: UserConfig, user_id: &str) -> Self {
+        Self {
+            args,
+            config,
+            user_id: user_id.to_string(),
+        }
+    }
+
+    pub async fn run(&mut self) -> Result<WekanResult, Error> {
+        match self.args.command.to_owned() {
+            Command::Trello(t) => self.run_trello(&t).await,
+        }
+    }
+
+    async fn run_trello(&mut self, trello_args: &TrelloArgs) -> Result<WekanResult, Error> {
+        info!("run_trello");
+        let members = parse_members(&trello_args.members)?;
+        let content = tokio::fs::read(&trello_args.file).await?;
+        let board = match serde_json::from_slice::<TrelloBoard>(&content) {
+            Ok(b) => b,
+            Err(e) => {
+                return Err(
+                    CliError::new_msg(&format!("Trello export can not be parsed: {}", e)).as_enum(),
+                )
+            }
+        };
+        let (snapshot, report) = board.to_snapshot(&members);
+        let mut restore = Restore::new(self.config.clone(), &self.user_id);
+        let res = restore.run(&snapshot, trello_args.board.to_owned()).await?;
+        if report.is_empty() {
+            res.ok()
+        } else {
+            WekanResult::new_workflow(
+                &format!(
+                    "{}\nThe following could not be mapped:\n- {}",
+                    res.get_msg(),
+                    report.join("\n- ")
+                ),
+                "Show the board with the table subcommand",
+            )
+            .ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export() -> TrelloBoard {
+        serde_json::from_str(
+            r#"{
+                "name": "Trello board",
+                "lists": [
+                    {"id": "l2", "name": "Done", "closed": false, "pos": 2},
+                    {"id": "l1", "name": "Todo", "closed": false, "pos": 1},
+                    {"id": "l3", "name": "Old", "closed": true, "pos": 3}
+                ],
+                "cards": [
+                    {"id": "c1", "name": "Write", "desc": "", "idList": "l1",
+                     "due": "2022-10-12T10:00:00.000Z", "dueComplete": true,
+                     "idLabels": ["lb1", "lb2"], "idMembers": ["m1", "m2"], "closed": false, "pos": 1},
+                    {"id": "c2", "name": "Archived", "desc": "", "idList": "l1",
+                     "due": null, "idLabels": [], "closed": true, "pos": 2},
+                    {"id": "c3", "name": "Hidden", "desc": "", "idList": "l3",
+                     "due": null, "closed": false, "pos": 3}
+                ],
+                "checklists": [
+                    {"id": "ch1", "idCard": "c1", "name": "Steps", "checkItems": [
+                        {"name": "Second", "state": "incomplete", "pos": 2},
+                        {"name": "First", "state": "complete", "pos": 1}
+                    ]}
+                ],
+                "labels": [
+                    {"id": "lb1", "name": "bug", "color": "red"},
+                    {"id": "lb2", "name": "", "color": "green_dark"}
+                ],
+                "members": [
+                    {"id": "m1", "username": "alice"},
+                    {"id": "m2", "username": "bob"}
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn map_trello_export() {
+        let mut members = BTreeMap::new();
+        members.insert(String::from("alice"), String::from("wekan-alice"));
+        let (snapshot, report) = export().to_snapshot(&members);
+        assert_eq!(snapshot.title, "Trello board");
+        let lists: Vec<&str> = snapshot.lists.iter().map(|l| l.title.as_str()).collect();
+        assert_eq!(lists, vec!["Todo", "Done"]);
+        assert_eq!(snapshot.cards.len(), 1);
+        assert_eq!(snapshot.cards[0].end_at, None);
+        assert_eq!(
+            snapshot.cards[0].labels,
+            Some(vec![String::from("bug"), String::from("green")])
+        );
+        assert_eq!(
+            snapshot.labels[1],
+            LabelEntry {
+                name: String::new(),
+                color: String::from("green")
+            }
+        );
+        assert_eq!(
+            snapshot.cards[0].members,
+            Some(vec![String::from("wekan-alice")])
+        );
+        assert_eq!(snapshot.checklists[0].items, vec!["First", "Second"]);
+        assert_eq!(snapshot.checklists[0].finished, vec!["First"]);
+        assert_eq!(
+            report,
+            vec![
+                "List 'Old' is archived and was skipped",
+                "Card 'Archived' is archived and was skipped",
+                "Card 'Hidden' belongs to a skipped list and was skipped",
+                "Completed due date of card 'Write' was not mapped",
+                "Members of card 'Write' were not mapped, add --member NAME=WEKAN_USER_ID: bob",
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_member_mapping() {
+        let members = parse_members(&[String::from("alice=u1")]).unwrap();
+        assert_eq!(members.get("alice"), Some(&String::from("u1")));
+        assert!(parse_members(&[String::from("alice")]).is_err());
+        assert!(parse_members(&[String::from("=u1")]).is_err());
+    }
+}
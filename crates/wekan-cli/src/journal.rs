@@ -0,0 +1,460 @@
+use crate::{
+    archive,
+    card::record::Record,
+    error::{CliError, Error, Transform},
+    resolver::Query,
+    result::WekanResult,
+    snapshot::{ChecklistEntry, Restore, Snapshot},
+};
+use chrono::{SecondsFormat, Utc};
+use clap::Args as ClapArgs;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use wekan_common::{
+    artifact::{
+        board::Details as BDetails,
+        card::Details as CDetails,
+        checklist::Details as ChDetails,
+        common::{AType, Artifact, Base, MostDetails, SortedArtifact},
+        list::Details as LDetails,
+    },
+    http::{
+        artifact::ResponseOk,
+        card::{MoveCard, UpdateCard},
+    },
+};
+#[cfg(not(test))]
+use wekan_core::persistence::config::Butler;
+use wekan_core::{
+    client::{BoardApi, CardApi, ChecklistApi, Client, ListApi, SwimlaneApi},
+    config::UserConfig,
+    error::Error as CoreError,
+};
+
+#[cfg(test)]
+use crate::tests::mocks::{Artifacts, Operation};
+#[cfg(not(test))]
+use wekan_core::http::operation::{Artifacts, Operation};
+
+/// Older changes are dropped from the journal.
+const JOURNAL_SIZE: usize = 100;
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Undo the last change",
+    long_about = "Revert the last removal, update, move or archive recorded in the local journal"
+)]
+pub struct Args {
+    #[clap(long, parse(from_flag), help = "List the journal, latest change first")]
+    pub list: bool,
+}
+
+/// What is needed to revert one change.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Change {
+    /// A removed artifact with all its children.
+    Remove {
+        r#type: AType,
+        board_id: String,
+        snapshot: Snapshot,
+    },
+    /// A card before an update.
+    Update { card: CardState },
+    /// A card before a move and the list it was moved to.
+    Move {
+        card: CardState,
+        board_id: String,
+        list_id: String,
+    },
+    /// The parent is the list of a card or the card of a checklist.
+    Archive {
+        r#type: AType,
+        board_id: String,
+        parent_id: String,
+        id: String,
+        archive: bool,
+    },
+    /// Changes of a bulk operation, reverted together.
+    Batch { changes: Vec<Change> },
+}
+
+/// Place and fields of a card which can be updated.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CardState {
+    pub _id: String,
+    pub board_id: String,
+    pub list_id: String,
+    pub swimlane_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_at: Option<String>,
+    pub end_at: Option<String>,
+    pub sort: Option<f32>,
+    #[serde(default)]
+    pub label_ids: Vec<String>,
+}
+
+impl CardState {
+    pub fn new(id: &str, details: &CDetails) -> Self {
+        Self {
+            _id: id.to_string(),
+            board_id: details.board_id.to_owned(),
+            list_id: details.list_id.to_owned(),
+            swimlane_id: details.get_swimlane_id(),
+            title: details.get_title(),
+            description: Record::get_optional(&details.get_description()),
+            due_at: Record::get_optional(&details.get_due_at()),
+            end_at: Record::get_optional(&details.get_end_at()),
+            sort: Some(*details.get_sort()),
+            label_ids: details.get_label_ids(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Entry {
+    pub at: String,
+    pub summary: String,
+    pub change: Change,
+}
+
+/// Changes in the order they were made, stored under the config path.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Journal {
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+impl Journal {
+    #[cfg(not(test))]
+    pub async fn read(config: &UserConfig) -> Result<Self, Error> {
+        match tokio::fs::read(config.get_path() + "journal").await {
+            Ok(v) => Ok(serde_yaml::from_slice::<Journal>(&v)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Journal::default()),
+            Err(e) => Err(Error::Io(e)),
+        }
+    }
+
+    /// A dry run leaves the journal untouched.
+    #[cfg(not(test))]
+    pub async fn write(&self, config: &UserConfig) -> Result<(), Error> {
+        if config.dry_run {
+            return Ok(());
+        }
+        let config_path = config.get_path();
+        tokio::fs::create_dir_all(&config_path).await?;
+        let content = serde_yaml::to_string(self)?;
+        tokio::fs::write(config_path + "journal", content).await?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub async fn read(_config: &UserConfig) -> Result<Self, Error> {
+        Ok(Journal::default())
+    }
+
+    #[cfg(test)]
+    pub async fn write(&self, _config: &UserConfig) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn push(&mut self, summary: &str, change: Change) {
+        self.entries.push(Entry {
+            at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            summary: summary.to_string(),
+            change,
+        });
+        if self.entries.len() > JOURNAL_SIZE {
+            self.entries.drain(..self.entries.len() - JOURNAL_SIZE);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.entries
+            .iter()
+            .rev()
+            .map(|e| format!("{}  {}\n", e.at, e.summary))
+            .collect()
+    }
+}
+
+/// Appends a change which has been applied on the server. A journal which can't be
+/// written only costs the undo, so the change still counts as successful.
+pub async fn record(config: &UserConfig, summary: &str, change: Change) {
+    let written = match Journal::read(config).await {
+        Ok(mut journal) => {
+            journal.push(summary, change);
+            journal.write(config).await
+        }
+        Err(e) => Err(e),
+    };
+    if let Err(e) = written {
+        warn!(
+            "'{}' can not be undone, the journal failed: {:?}",
+            summary, e
+        );
+    }
+}
+
+/// Reads an artifact with all its children before it gets removed.
+pub async fn removal(
+    config: &UserConfig,
+    r#type: AType,
+    board_id: &str,
+    parent_id: &str,
+    id: &str,
+) -> Result<Change, Error> {
+    info!("removal");
+    let query = Query::new(config.clone(), "", true);
+    let snapshot = match r#type {
+        AType::Board => {
+            let mut client = <Client as BoardApi>::new(config.clone());
+            let title = client.get_one::<BDetails>(id).await?.get_title();
+            let lists = query
+                .inquire(AType::List, Some(id), None, None, true)
+                .await?;
+            Snapshot::collect(&query, id, &title, lists, None).await?
+        }
+        AType::List => {
+            let mut client = <Client as ListApi>::new(config.clone(), board_id);
+            let list = Artifact {
+                _id: id.to_string(),
+                title: client.get_one::<LDetails>(id).await?.get_title(),
+                r#type: AType::List,
+            };
+            Snapshot::collect(&query, board_id, "", vec![list], None).await?
+        }
+        AType::Card => {
+            let list = Artifact {
+                _id: parent_id.to_string(),
+                title: String::new(),
+                r#type: AType::List,
+            };
+            Snapshot::collect(&query, board_id, "", vec![list], Some(id)).await?
+        }
+        AType::Checklist => {
+            let mut client = <Client as ChecklistApi>::new(config.clone(), board_id, parent_id);
+            let checklist = client.get_one::<ChDetails>(id).await?;
+            Snapshot {
+                checklists: vec![ChecklistEntry {
+                    _id: id.to_string(),
+                    card_id: parent_id.to_string(),
+                    title: checklist.title.to_owned(),
+                    items: checklist.get_items().into_iter().map(|i| i.title).collect(),
+                    finished: checklist
+                        .get_items()
+                        .into_iter()
+                        .filter(|i| i.is_finished)
+                        .map(|i| i.title)
+                        .collect(),
+                }],
+                ..Default::default()
+            }
+        }
+        t => {
+            return Err(CliError::new_msg(&format!(
+                "Removing a {} can not be undone",
+                t.to_string()
+            ))
+            .as_enum())
+        }
+    };
+    Ok(Change::Remove {
+        r#type,
+        board_id: board_id.to_string(),
+        snapshot,
+    })
+}
+
+/// Removed artifacts are recreated with new ids. The changes of a bulk operation are reverted
+/// from the last to the first.
+pub async fn revert(config: &UserConfig, author_id: &str, change: &Change) -> Result<(), Error> {
+    info!("revert");
+    match change {
+        Change::Batch { changes } => {
+            for c in changes.iter().rev() {
+                match revert_change(config, author_id, c).await {
+                    Err(Error::Core(CoreError::DryRun(d))) => eprintln!("{}", d),
+                    r => r?,
+                }
+            }
+            Ok(())
+        }
+        c => revert_change(config, author_id, c).await,
+    }
+}
+
+async fn revert_change(config: &UserConfig, author_id: &str, change: &Change) -> Result<(), Error> {
+    match change {
+        Change::Remove {
+            r#type,
+            board_id,
+            snapshot,
+        } => {
+            let mut restore = Restore::new(config.clone(), author_id);
+            if r#type == &AType::Board {
+                restore.create(snapshot, &snapshot.title).await?;
+                return Ok(());
+            }
+            let query = Query::new(config.clone(), "", true);
+            for list in &snapshot.lists {
+                let id = match r#type {
+                    AType::List => {
+                        let mut client = <Client as ListApi>::new(config.clone(), board_id);
+                        restore.create_artifact(&mut client, &list.title).await?
+                    }
+                    _ => list._id.to_owned(),
+                };
+                restore.map(&list._id, &id);
+            }
+            for checklist in &snapshot.checklists {
+                if !snapshot.cards.iter().any(|c| c._id == checklist.card_id) {
+                    restore.map(&checklist.card_id, &checklist.card_id);
+                }
+            }
+            let swimlane_id = match snapshot.cards.is_empty() {
+                true => String::new(),
+                false => restore.map_swimlanes(&query, snapshot, board_id).await?,
+            };
+            restore.copy_into(snapshot, board_id, &swimlane_id).await
+        }
+        Change::Update { card } => {
+            let mut client =
+                <Client as CardApi>::new(config.clone(), &card.board_id, &card.list_id);
+            let body = UpdateCard {
+                _id: card._id.to_owned(),
+                title: Some(card.title.to_owned()),
+                description: Some(card.description.to_owned().unwrap_or_default()),
+                due_at: card.due_at.to_owned(),
+                end_at: card.end_at.to_owned(),
+                sort: card.sort,
+                labels: Some(card.label_ids.to_owned()),
+                assignees: None,
+            };
+            client.put::<UpdateCard, ResponseOk>(&body).await?;
+            Ok(())
+        }
+        Change::Move {
+            card,
+            board_id,
+            list_id,
+        } => {
+            let mut client = <Client as CardApi>::new(config.clone(), board_id, list_id);
+            let body = MoveCard {
+                _id: card._id.to_owned(),
+                list_id: card.list_id.to_owned(),
+                board_id: Some(card.board_id.to_owned()),
+                swimlane_id: Some(card.swimlane_id.to_owned()),
+                sort: card.sort,
+            };
+            client.put::<MoveCard, ResponseOk>(&body).await?;
+            Ok(())
+        }
+        Change::Archive {
+            r#type,
+            board_id,
+            parent_id,
+            id,
+            archive,
+        } => {
+            let mut client = match r#type {
+                AType::Board => <Client as BoardApi>::new(config.clone()),
+                AType::List => <Client as ListApi>::new(config.clone(), board_id),
+                AType::Swimlane => <Client as SwimlaneApi>::new(config.clone(), board_id),
+                AType::Card => <Client as CardApi>::new(config.clone(), board_id, parent_id),
+                _ => <Client as ChecklistApi>::new(config.clone(), board_id, parent_id),
+            };
+            archive::put(&mut client, id, !archive).await?;
+            Ok(())
+        }
+        Change::Batch { .. } => {
+            Err(CliError::new_msg("Nested bulk changes can not be undone").as_enum())
+        }
+    }
+}
+
+pub async fn run(args: &Args, config: &UserConfig, author_id: &str) -> Result<WekanResult, Error> {
+    info!("undo");
+    let mut journal = Journal::read(config).await?;
+    if journal.entries.is_empty() {
+        return WekanResult::new_msg("Nothing to undo").ok();
+    }
+    if args.list {
+        return WekanResult::new_workflow(&journal.render(), "Undo the latest change with 'undo'")
+            .ok();
+    }
+    let entry = journal.entries.pop().unwrap();
+    revert(config, author_id, &entry.change).await?;
+    if config.dry_run {
+        return WekanResult::new_unchanged("Dry run, nothing was sent").ok();
+    }
+    journal.write(config).await?;
+    WekanResult::new_msg(&format!("Successfully undone: {}", entry.summary)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use wekan_common::artifact::tests::MockDetails;
+
+    fn card() -> CardState {
+        CardState::new(
+            "fake-card-id-1",
+            &<CDetails as MockDetails>::mock("fake-card-id-1", "Write", "2022-10-12"),
+        )
+    }
+
+    #[test]
+    fn push_and_render() {
+        let mut journal = Journal::default();
+        for i in 0..JOURNAL_SIZE + 2 {
+            journal.push(
+                &format!("update card '{}'", i),
+                Change::Update { card: card() },
+            );
+        }
+        assert_eq!(journal.entries.len(), JOURNAL_SIZE);
+        assert_eq!(journal.entries[0].summary, "update card '2'");
+        let rendered = journal.render();
+        assert!(rendered
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with(&format!("update card '{}'", JOURNAL_SIZE + 1)));
+        let yaml = serde_yaml::to_string(&journal).unwrap();
+        assert!(yaml.contains("action: update"));
+        let read = serde_yaml::from_str::<Journal>(&yaml).unwrap();
+        assert_eq!(read.entries.len(), JOURNAL_SIZE);
+    }
+
+    #[tokio::test]
+    async fn remove_and_revert() {
+        let config = UserConfig::mock();
+        for t in [AType::Board, AType::List, AType::Card, AType::Checklist] {
+            let change = removal(&config, t, "fake-board-id-1", "fake-card-id-1", "fake-id")
+                .await
+                .unwrap();
+            revert(&config, "me", &change).await.unwrap();
+        }
+        let change = removal(&config, AType::ChecklistItem, "", "", "fake-id").await;
+        assert!(change.is_err());
+        for change in [
+            Change::Update { card: card() },
+            Change::Move {
+                card: card(),
+                board_id: String::from("fake-board-id-2"),
+                list_id: String::from("fake-list-id-2"),
+            },
+            Change::Batch {
+                changes: vec![
+                    Change::Update { card: card() },
+                    Change::Update { card: card() },
+                ],
+            },
+        ] {
+            revert(&config, "me", &change).await.unwrap();
+        }
+    }
+}
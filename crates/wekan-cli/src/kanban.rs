@@ -0,0 +1,692 @@
+use crate::{
+    command::Args as RArgs,
+    error::{CliError, Error, Transform},
+    journal::{self, CardState, Change},
+    resolver::Query,
+    result::WekanResult,
+};
+use chrono::{NaiveDate, SecondsFormat, Utc};
+use clap::Args as ClapArgs;
+use crossterm::{
+    cursor::Show,
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use log::{info, trace};
+use std::{io::Stdout, panic, sync::Arc};
+use tui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use wekan_common::{
+    artifact::{
+        card::Details as CDetails,
+        common::{AType, Artifact, Base, BaseDetails, MostDetails},
+    },
+    http::{
+        artifact::ResponseOk,
+        card::{ArchiveCard, MoveCard, UpdateCard},
+    },
+};
+use wekan_core::client::{CardApi, Client};
+
+#[cfg(test)]
+use crate::tests::mocks::{Artifacts, Operation};
+#[cfg(not(test))]
+use wekan_core::http::operation::{Artifacts, Operation};
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Open a board in the terminal",
+    long_about = "Show the lists of a board as columns to browse, move, edit and archive cards"
+)]
+pub struct Args {
+    /// Board name
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    DueAt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Details(String),
+    Edit(Field, String),
+    ConfirmArchive,
+}
+
+/// Requests which need the server, everything else is handled by the board itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    Refresh,
+    Open,
+    Move(usize),
+    Update(Field, String),
+    Archive,
+}
+
+pub struct Column {
+    pub list: Artifact,
+    pub cards: Vec<Artifact>,
+}
+
+pub struct Board {
+    pub title: String,
+    pub columns: Vec<Column>,
+    pub list: usize,
+    pub card: usize,
+    pub mode: Mode,
+    pub status: String,
+}
+
+impl Board {
+    pub fn new(title: &str, columns: Vec<Column>) -> Self {
+        Self {
+            title: title.to_string(),
+            columns,
+            list: 0,
+            card: 0,
+            mode: Mode::Normal,
+            status: String::from(HELP),
+        }
+    }
+
+    pub fn selected_card(&self) -> Option<&Artifact> {
+        self.columns
+            .get(self.list)
+            .and_then(|c| c.cards.get(self.card))
+    }
+
+    /// Ctrl-C quits in every mode, raw mode keeps it from sending SIGINT.
+    pub fn on_event(&mut self, event: KeyEvent) -> Action {
+        match event {
+            KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers,
+                ..
+            } if modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
+            KeyEvent { code, .. } => self.on_key(code),
+        }
+    }
+
+    pub fn on_key(&mut self, key: KeyCode) -> Action {
+        match self.mode.to_owned() {
+            Mode::Normal => self.on_normal_key(key),
+            Mode::Details(_d) => {
+                self.mode = Mode::Normal;
+                Action::None
+            }
+            Mode::ConfirmArchive => {
+                self.mode = Mode::Normal;
+                match key {
+                    KeyCode::Char('y') => Action::Archive,
+                    _ => {
+                        self.status = String::from(HELP);
+                        Action::None
+                    }
+                }
+            }
+            Mode::Edit(field, mut value) => match key {
+                KeyCode::Esc => {
+                    self.mode = Mode::Normal;
+                    self.status = String::from(HELP);
+                    Action::None
+                }
+                KeyCode::Enter => {
+                    if field == Field::DueAt
+                        && NaiveDate::parse_from_str(&value, "%Y-%m-%d").is_err()
+                    {
+                        self.status = String::from("Not a correct date format YYYY-MM-DD");
+                        return Action::None;
+                    }
+                    self.mode = Mode::Normal;
+                    Action::Update(field, value)
+                }
+                KeyCode::Backspace => {
+                    value.pop();
+                    self.mode = Mode::Edit(field, value);
+                    Action::None
+                }
+                KeyCode::Char(c) => {
+                    value.push(c);
+                    self.mode = Mode::Edit(field, value);
+                    Action::None
+                }
+                _ => Action::None,
+            },
+        }
+    }
+
+    fn on_normal_key(&mut self, key: KeyCode) -> Action {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => Action::Quit,
+            KeyCode::Char('r') => Action::Refresh,
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.select_list(self.list.saturating_sub(1));
+                Action::None
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.select_list(self.list + 1);
+                Action::None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.card = self.card.saturating_sub(1);
+                Action::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.card + 1 < self.column_len() {
+                    self.card += 1;
+                }
+                Action::None
+            }
+            key => match self.selected_card().map(|c| c.title.to_owned()) {
+                Some(title) => match key {
+                    KeyCode::Enter => Action::Open,
+                    KeyCode::Char('<') | KeyCode::Char('H') if self.list > 0 => {
+                        Action::Move(self.list - 1)
+                    }
+                    KeyCode::Char('>') | KeyCode::Char('L')
+                        if self.list + 1 < self.columns.len() =>
+                    {
+                        Action::Move(self.list + 1)
+                    }
+                    KeyCode::Char('e') => {
+                        self.mode = Mode::Edit(Field::Title, title);
+                        self.status = String::from("New title, Enter to save, Esc to cancel");
+                        Action::None
+                    }
+                    KeyCode::Char('d') => {
+                        self.mode = Mode::Edit(Field::DueAt, String::new());
+                        self.status = String::from("Due date YYYY-MM-DD, Enter to save");
+                        Action::None
+                    }
+                    KeyCode::Char('a') => {
+                        self.mode = Mode::ConfirmArchive;
+                        self.status = format!("Archive '{}'? (y/n)", title);
+                        Action::None
+                    }
+                    _ => Action::None,
+                },
+                None => Action::None,
+            },
+        }
+    }
+
+    fn column_len(&self) -> usize {
+        self.columns
+            .get(self.list)
+            .map(|c| c.cards.len())
+            .unwrap_or(0)
+    }
+
+    fn select_list(&mut self, list: usize) {
+        if list < self.columns.len() {
+            self.list = list;
+            self.card = std::cmp::min(self.card, self.column_len().saturating_sub(1));
+        }
+    }
+
+    /// Keeps the moved card selected in its new list.
+    pub fn move_selected(&mut self, to: usize) {
+        if self.selected_card().is_some() && to < self.columns.len() {
+            let card = self.columns[self.list].cards.remove(self.card);
+            self.columns[to].cards.push(card);
+            self.list = to;
+            self.card = self.column_len() - 1;
+        }
+    }
+
+    pub fn remove_selected(&mut self) {
+        if self.selected_card().is_some() {
+            self.columns[self.list].cards.remove(self.card);
+            self.card = std::cmp::min(self.card, self.column_len().saturating_sub(1));
+        }
+    }
+}
+
+const HELP: &str =
+    "h/l lists  j/k cards  Enter details  </> move  e title  d due  a archive  r refresh  q quit";
+
+fn restore_terminal() {
+    if let Err(e) = disable_raw_mode() {
+        trace!("{:?}", e);
+    }
+    if let Err(e) = execute!(std::io::stdout(), LeaveAlternateScreen, Show) {
+        trace!("{:?}", e);
+    }
+}
+
+pub fn draw<B: Backend>(f: &mut Frame<B>, board: &Board) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(f.size());
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            board.title.to_owned(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        rows[0],
+    );
+    if !board.columns.is_empty() {
+        let ratio = board.columns.len() as u32;
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                board
+                    .columns
+                    .iter()
+                    .map(|_c| Constraint::Ratio(1, ratio))
+                    .collect::<Vec<Constraint>>(),
+            )
+            .split(rows[1]);
+        for (i, c) in board.columns.iter().enumerate() {
+            let selected = i == board.list;
+            let items: Vec<ListItem> = c
+                .cards
+                .iter()
+                .map(|a| ListItem::new(a.title.to_owned()))
+                .collect();
+            let mut block = Block::default()
+                .borders(Borders::ALL)
+                .title(c.list.title.to_owned());
+            if selected {
+                block = block.border_style(Style::default().fg(Color::Yellow));
+            }
+            let list = List::new(items)
+                .block(block)
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            let mut state = ListState::default();
+            if selected && !c.cards.is_empty() {
+                state.select(Some(board.card));
+            }
+            f.render_stateful_widget(list, columns[i], &mut state);
+        }
+    }
+    let status = match &board.mode {
+        Mode::Edit(_f, value) => format!("> {}", value),
+        _ => board.status.to_owned(),
+    };
+    f.render_widget(Paragraph::new(Spans::from(status)), rows[2]);
+    if let Mode::Details(text) = &board.mode {
+        let area = centered(f.size());
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text.to_owned())
+                .block(Block::default().borders(Borders::ALL).title("Card"))
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+}
+
+fn centered(area: Rect) -> Rect {
+    Rect {
+        x: area.x + area.width / 6,
+        y: area.y + area.height / 6,
+        width: area.width * 2 / 3,
+        height: area.height * 2 / 3,
+    }
+}
+
+fn details_text(details: &CDetails, list: &str) -> String {
+    let mut text = format!("{}\n\nList: {}\n", details.get_title(), list);
+    for (name, value) in [
+        ("Due", details.get_due_at()),
+        ("Done", details.get_end_at()),
+        ("Modified", details.get_modified_at()),
+    ] {
+        if !value.is_empty() {
+            text.push_str(&format!("{}: {}\n", name, value));
+        }
+    }
+    let description = details.get_description();
+    if !description.is_empty() {
+        text.push('\n');
+        text.push_str(&description);
+    }
+    text
+}
+
+pub struct Runner<'a> {
+    pub args: Args,
+    pub client: Client,
+    pub global_options: &'a RArgs,
+    board_id: String,
+}
+
+impl<'a> Runner<'a> {
+    pub fn new(args: Args, client: Client, global_options: &'a RArgs) -> Self {
+        Self {
+            args,
+            client,
+            global_options,
+            board_id: String::new(),
+        }
+    }
+
+    pub async fn run(&mut self) -> Result<WekanResult, Error> {
+        info!("run_tui");
+        let mut board = self.load().await?;
+        // Restores the terminal before a panic is printed, else the message is lost
+        // in the alternate screen and the shell stays in raw mode.
+        let previous = Arc::new(panic::take_hook());
+        let hook = previous.clone();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            hook(info)
+        }));
+        let res = self.open(&mut board).await;
+        restore_terminal();
+        let _hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| previous(info)));
+        res
+    }
+
+    async fn open(&mut self, board: &mut Board) -> Result<WekanResult, Error> {
+        enable_raw_mode()?;
+        let mut stdout = std::io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+        self.event_loop(&mut terminal, board).await
+    }
+
+    async fn event_loop(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        board: &mut Board,
+    ) -> Result<WekanResult, Error> {
+        loop {
+            terminal.draw(|f| draw(f, board))?;
+            // Reading blocks, so it must not hold up a worker of the runtime.
+            let event = tokio::task::spawn_blocking(event::read)
+                .await
+                .map_err(|e| CliError::new_msg(&e.to_string()).as_enum())??;
+            if let Event::Key(key) = event {
+                let action = board.on_event(key);
+                trace!("{:?}", action);
+                if action == Action::Quit {
+                    return WekanResult::new_msg("").ok();
+                }
+                if let Err(e) = self.apply(board, action).await {
+                    board.status = format!("Request failed: {:?}", e);
+                }
+            }
+        }
+    }
+
+    /// Sends the action to the server and updates the board on success.
+    pub async fn apply(&mut self, board: &mut Board, action: Action) -> Result<(), Error> {
+        let (list_id, card) = match board.selected_card() {
+            Some(c) => (board.columns[board.list].list._id.to_owned(), c.to_owned()),
+            None => {
+                if action == Action::Refresh {
+                    *board = self.reload(board).await?;
+                }
+                return Ok(());
+            }
+        };
+        CardApi::set_base(&mut self.client, &self.board_id, &list_id);
+        match action {
+            Action::Refresh => *board = self.reload(board).await?,
+            Action::Open => {
+                let details = self.client.get_one::<CDetails>(&card._id).await?;
+                board.mode = Mode::Details(details_text(
+                    &details,
+                    &board.columns[board.list].list.title,
+                ));
+            }
+            Action::Move(to) => {
+                let before = CardState::new(
+                    &card._id,
+                    &self.client.get_one::<CDetails>(&card._id).await?,
+                );
+                let body = MoveCard {
+                    _id: card._id.to_owned(),
+                    list_id: board.columns[to].list._id.to_owned(),
+                    board_id: None,
+                    swimlane_id: None,
+                    sort: None,
+                };
+                self.client.put::<MoveCard, ResponseOk>(&body).await?;
+                let change = Change::Move {
+                    card: before,
+                    board_id: self.board_id.to_owned(),
+                    list_id: body.list_id,
+                };
+                let summary = format!("mv card '{}'", card.title);
+                journal::record(&self.client.config, &summary, change).await;
+                board.move_selected(to);
+                board.status = format!(
+                    "Moved '{}' to '{}'",
+                    card.title, board.columns[to].list.title
+                );
+            }
+            Action::Update(field, value) => {
+                let before = CardState::new(
+                    &card._id,
+                    &self.client.get_one::<CDetails>(&card._id).await?,
+                );
+                let body = UpdateCard {
+                    _id: card._id.to_owned(),
+                    title: match field {
+                        Field::Title => Some(value.to_owned()),
+                        Field::DueAt => None,
+                    },
+                    description: None,
+                    due_at: match field {
+                        Field::Title => None,
+                        Field::DueAt => Some(value.to_owned()),
+                    },
+                    end_at: None,
+                    sort: None,
+                    labels: None,
+                    assignees: None,
+                };
+                self.client.put::<UpdateCard, ResponseOk>(&body).await?;
+                let summary = format!("update card '{}'", card.title);
+                journal::record(
+                    &self.client.config,
+                    &summary,
+                    Change::Update { card: before },
+                )
+                .await;
+                if field == Field::Title {
+                    board.columns[board.list].cards[board.card].title = value;
+                }
+                board.status = format!("Updated '{}'", card.title);
+            }
+            Action::Archive => {
+                let body = ArchiveCard {
+                    _id: card._id.to_owned(),
+                    archive: true,
+                    archive_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                };
+                self.client.put::<ArchiveCard, ResponseOk>(&body).await?;
+                let change = Change::Archive {
+                    r#type: AType::Card,
+                    board_id: self.board_id.to_owned(),
+                    parent_id: list_id.to_owned(),
+                    id: card._id.to_owned(),
+                    archive: true,
+                };
+                let summary = format!("archive card '{}'", card.title);
+                journal::record(&self.client.config, &summary, change).await;
+                board.remove_selected();
+                board.status = format!("Archived '{}'", card.title);
+            }
+            Action::None | Action::Quit => {}
+        }
+        Ok(())
+    }
+
+    async fn reload(&mut self, board: &Board) -> Result<Board, Error> {
+        let mut reloaded = self.load().await?;
+        reloaded.select_list(board.list);
+        reloaded.card = std::cmp::min(board.card, reloaded.column_len().saturating_sub(1));
+        reloaded.status = String::from("Refreshed");
+        Ok(reloaded)
+    }
+
+    /// Requests the same lists and cards as the table subcommand.
+    pub async fn load(&mut self) -> Result<Board, Error> {
+        let mut filter = String::new();
+        if let Some(f) = &self.global_options.filter {
+            filter.push_str(f);
+        }
+        let mut query = Query::new(
+            self.client.config.clone(),
+            &filter,
+            self.global_options.deny_store(),
+        );
+        self.board_id = match query.find_board_id(&self.args.name).await {
+            Ok(id) => id,
+            Err(_e) => return Err(CliError::new_msg("Board name not found").as_enum()),
+        };
+        let lists = query
+            .inquire(AType::List, Some(&self.board_id), None, None, true)
+            .await?;
+        let mut columns = Vec::new();
+        for l in lists {
+            let mut cards = query
+                .inquire(AType::Card, Some(&self.board_id), Some(&l._id), None, true)
+                .await?;
+            cards.reverse();
+            columns.push(Column { list: l, cards });
+        }
+        Ok(Board::new(&self.args.name, columns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use tui::backend::TestBackend;
+    use wekan_common::artifact::tests::MockDetails;
+
+    fn board() -> Board {
+        let column = |n: &str, cards: Vec<&str>| Column {
+            list: <Artifact as MockDetails>::mock(&format!("{}-id", n), n, "list"),
+            cards: cards
+                .iter()
+                .map(|c| <Artifact as MockDetails>::mock(&format!("{}-id", c), c, "card"))
+                .collect(),
+        };
+        Board::new(
+            "my-board",
+            vec![
+                column("Todo", vec!["Write", "Test"]),
+                column("Done", Vec::new()),
+            ],
+        )
+    }
+
+    #[test]
+    fn navigate_and_move() {
+        let mut b = board();
+        assert_eq!(b.on_key(KeyCode::Char('j')), Action::None);
+        assert_eq!(b.selected_card().unwrap().title, "Test");
+        assert_eq!(b.on_key(KeyCode::Char('j')), Action::None);
+        assert_eq!(b.card, 1);
+        assert_eq!(b.on_key(KeyCode::Char('<')), Action::None);
+        assert_eq!(b.on_key(KeyCode::Char('>')), Action::Move(1));
+        b.move_selected(1);
+        assert_eq!((b.list, b.card), (1, 0));
+        assert_eq!(b.columns[0].cards.len(), 1);
+        assert_eq!(b.on_key(KeyCode::Char('h')), Action::None);
+        assert_eq!((b.list, b.card), (0, 0));
+        assert_eq!(b.on_key(KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn ctrl_c_quits() {
+        let mut b = board();
+        assert_eq!(b.on_key(KeyCode::Char('e')), Action::None);
+        assert_eq!(
+            b.on_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE)),
+            Action::None
+        );
+        assert_eq!(
+            b.on_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Action::Quit
+        );
+    }
+
+    #[test]
+    fn edit_and_archive() {
+        let mut b = board();
+        b.on_key(KeyCode::Char('e'));
+        assert_eq!(b.mode, Mode::Edit(Field::Title, String::from("Write")));
+        b.on_key(KeyCode::Backspace);
+        b.on_key(KeyCode::Char('!'));
+        assert_eq!(
+            b.on_key(KeyCode::Enter),
+            Action::Update(Field::Title, String::from("Writ!"))
+        );
+        b.on_key(KeyCode::Char('d'));
+        for c in "2022-13-01".chars() {
+            b.on_key(KeyCode::Char(c));
+        }
+        assert_eq!(b.on_key(KeyCode::Enter), Action::None);
+        assert_eq!(b.status, "Not a correct date format YYYY-MM-DD");
+        b.on_key(KeyCode::Esc);
+        b.on_key(KeyCode::Char('a'));
+        assert_eq!(b.on_key(KeyCode::Char('y')), Action::Archive);
+        b.remove_selected();
+        assert_eq!(b.selected_card().unwrap().title, "Test");
+    }
+
+    #[test]
+    fn render_columns() {
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        terminal.draw(|f| draw(f, &board())).unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol.to_owned())
+            .collect();
+        assert!(content.contains("my-board"));
+        assert!(content.contains("Todo"));
+        assert!(content.contains("Write"));
+        assert!(content.contains("Done"));
+    }
+
+    #[tokio::test]
+    async fn apply_move_and_open() {
+        #[cfg(feature = "store")]
+        let r_args = RArgs::mock_with(false, false, "", "");
+        #[cfg(not(feature = "store"))]
+        let r_args = RArgs::mock_with(false, "", "");
+        let mut runner = Runner::new(
+            Args {
+                name: String::from("fake-board-title-1"),
+            },
+            Client::mock(),
+            &r_args,
+        );
+        let mut b = runner.load().await.unwrap();
+        assert_eq!(b.columns.len(), 2);
+        runner.apply(&mut b, Action::Move(1)).await.unwrap();
+        assert_eq!(b.columns[1].cards.len(), 3);
+        runner.apply(&mut b, Action::Open).await.unwrap();
+        assert!(matches!(b.mode, Mode::Details(_)));
+    }
+}
@@ -1,18 +1,35 @@
+pub mod archive;
+pub mod batch;
 pub mod board;
+pub mod calendar;
 pub mod card;
 pub mod checklist;
 pub mod command;
+pub mod completion;
 pub mod config;
+pub mod diff;
 pub mod display;
+pub mod due;
 pub mod error;
+pub mod import;
+pub mod journal;
+pub mod kanban;
 pub mod list;
+pub mod markup;
+pub mod mine;
+pub mod prompt;
 pub mod resolver;
 pub mod result;
 pub mod runner;
+pub mod search;
+pub mod serve;
+pub mod shell;
+pub mod snapshot;
 #[cfg(feature = "store")]
 pub mod store;
 pub mod subcommand;
 #[cfg(test)]
 mod tests;
+pub mod watch;
 #[cfg(feature = "store")]
 pub mod workspace;
@@ -7,20 +7,25 @@ use crate::{
     error::{CliError, Error, Transform},
     resolver::Query,
     result::WekanResult,
-    subcommand::{CommonCommand as Command, Inspect},
+    snapshot::{Restore, Snapshot},
+    subcommand::{Archive, CommonCommand, Create, Details as SDetails, Inspect, List, Remove},
 };
 use async_trait::async_trait;
+use log::info;
 use wekan_cli_derive::{CommonSubcommands, FulfilmentRunner, WekanArgs};
 use wekan_common::{
-    artifact::{common::AType, list::Details},
+    artifact::{
+        common::{AType, Artifact},
+        list::Details,
+    },
     http::artifact::{CreateArtifact, ResponseOk},
-    validation::constraint::ListConstraint as LConstraint,
+    validation::{authentication::TokenHeader, constraint::ListConstraint as LConstraint},
 };
 use wekan_core::client::{Client, ListApi};
 
-use clap::Args as ClapArgs;
+use clap::{Args as ClapArgs, Subcommand};
 
-#[derive(ClapArgs, Debug, Clone, WekanArgs, CommonSubcommands)]
+#[derive(ClapArgs, Debug, Clone, WekanArgs)]
 #[clap(
     about = "Manage lists",
     long_about = "Create, remove and show details and children"
@@ -34,6 +39,36 @@ pub struct Args {
     pub command: Option<Command>,
 }
 
+/// The following commands are available:
+#[derive(Subcommand, Debug, Clone, CommonSubcommands)]
+pub enum Command {
+    #[common]
+    Ls(List),
+    #[common]
+    Details(SDetails),
+    #[common]
+    Create(Create),
+    #[common]
+    Inspect(Inspect),
+    #[common]
+    #[clap(name = "rm")]
+    Remove(Remove),
+    Cp(CopyArgs),
+    Archive(Archive),
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Copy a list",
+    long_about = "Create a new list with copies of all cards and their checklists"
+)]
+pub struct CopyArgs {
+    /// Title of the new list, by default the title of the copied list
+    pub title: Option<String>,
+    #[clap(long, help = "Destination board, by default the current board")]
+    pub to_board: Option<String>,
+}
+
 #[cfg(test)]
 impl Args {
     pub fn mock(name: Option<String>, board: String, command: Option<Command>) -> Self {
@@ -63,22 +98,22 @@ impl<'a> Operator<'a> for Runner<'a> {
             Some(f) => filter.push_str(f),
             None => {}
         };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            filter: &filter,
-            config: self.get_client().config,
-            deny_store_usage: self.get_global_options().no_store,
-        };
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            filter: &filter,
-            config: self.get_client().config,
-        };
+        let mut query = Query::new(
+            self.get_client().config,
+            &filter,
+            self.get_global_options().deny_store(),
+        );
         query.find_list_id(&self.constraint.board._id, name).await
     }
+    fn get_path(&self, name: &str) -> String {
+        format!("{}/{}", self.constraint.board.title, name)
+    }
     fn get_type(&self) -> AType {
         AType::List
     }
+    fn get_location(&self, _id: &str) -> (String, String) {
+        (self.constraint.board._id.to_owned(), String::new())
+    }
 
     fn get_children_type(&self) -> AType {
         AType::Card
@@ -108,7 +143,11 @@ impl<'a> ArtifactCommand<'a, Args, Client, LConstraint> for Runner<'a> {
 #[async_trait]
 impl<'a> RootCommandRunner<'a, Details, Command> for Runner<'a> {
     async fn use_specific_command(&mut self) -> Result<WekanResult, Error> {
-        self.use_common_command().await
+        match self.args.command.to_owned() {
+            Some(Command::Cp(c)) => self.run_copy(&c).await,
+            Some(Command::Archive(a)) => self.run_archive(&a).await,
+            _ => self.use_common_command().await,
+        }
     }
     async fn use_ls(&mut self) -> Result<WekanResult, Error> {
         self.get_all().await
@@ -135,14 +174,62 @@ impl<'a> RootCommandRunner<'a, Details, Command> for Runner<'a> {
     }
 }
 
+impl<'a> Runner<'a> {
+    async fn run_archive(&mut self, archive_args: &Archive) -> Result<WekanResult, Error> {
+        info!("run_archive");
+        self.archive(self.args.name.to_owned(), !archive_args.restore)
+            .await
+    }
+
+    async fn run_copy(&mut self, copy_args: &CopyArgs) -> Result<WekanResult, Error> {
+        info!("run_copy");
+        let name = self.args.get_name()?;
+        let id = self.find_details_id(&name).await?;
+        let mut query = Query::new(
+            self.get_client().config,
+            "",
+            self.get_global_options().deny_store(),
+        );
+        let board_id = match &copy_args.to_board {
+            Some(b) => match query.find_board_id(b).await {
+                Ok(id) => id,
+                Err(_e) => return CliError::new_msg("Destination board not found").err(),
+            },
+            None => self.constraint.board._id.to_owned(),
+        };
+        let list = Artifact {
+            _id: id.to_owned(),
+            title: name.to_owned(),
+            r#type: AType::List,
+        };
+        let snapshot = Snapshot::collect(
+            &query,
+            &self.constraint.board._id,
+            &self.constraint.board.title,
+            vec![list],
+            None,
+        )
+        .await?;
+        let title = copy_args
+            .title
+            .to_owned()
+            .unwrap_or_else(|| name.to_owned());
+        let mut client = <Client as ListApi>::new(self.client.config.clone(), &board_id);
+        let mut restore = Restore::new(self.client.config.clone(), &self.client.get_user_id());
+        let new_id = restore.create_artifact(&mut client, &title).await?;
+        restore.map(&id, &new_id);
+        let swimlane_id = restore.map_swimlanes(&query, &snapshot, &board_id).await?;
+        restore
+            .copy_into(&snapshot, &board_id, &swimlane_id)
+            .await?;
+        restore.report(&format!("list '{}' to '{}'", name, title), &snapshot)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        subcommand::{Create, Details as SDetails, Remove},
-        tests::mocks::Mock,
-    };
-    use wekan_common::artifact::common::Artifact;
+    use crate::tests::mocks::Mock;
 
     #[tokio::test]
     async fn run_no_options_specified() {
@@ -256,7 +343,7 @@ mod tests {
             Args::mock(
                 Some(String::from("fake-list-title-2")),
                 String::from("fake-board-title-1"),
-                Some(Command::Remove(Remove {})),
+                Some(Command::Remove(Remove { yes: true })),
             ),
             Client::mock(),
             LConstraint {
@@ -316,4 +403,38 @@ mod tests {
         let res = runner.run().await.unwrap();
         assert_eq!(res.get_msg(), expected);
     }
+
+    #[tokio::test]
+    async fn run_copy() {
+        #[cfg(feature = "store")]
+        let r_args = RArgs::mock_with(false, false, "", "");
+        #[cfg(not(feature = "store"))]
+        let r_args = RArgs::mock_with(false, "", "");
+        let mut runner = Runner::new(
+            Args::mock(
+                Some(String::from("fake-list-title-1")),
+                String::from("fake-board-title-1"),
+                Some(Command::Cp(CopyArgs {
+                    title: Some(String::from("new-list")),
+                    to_board: Some(String::from("fake-board-title-2")),
+                })),
+            ),
+            Client::mock(),
+            LConstraint {
+                board: Artifact {
+                    _id: String::from("fake-board-id-1"),
+                    title: String::from("fake-board-title-1"),
+                    r#type: AType::Board,
+                },
+            },
+            String::new(),
+            CliDisplay::new(Vec::new()),
+            &r_args,
+        );
+        let res = runner.run().await.unwrap();
+        assert_eq!(
+            res.get_msg(),
+            "Successfully copied list 'fake-list-title-1' to 'new-list' with 2 cards and 4 checklists"
+        );
+    }
 }
@@ -0,0 +1,372 @@
+use crate::{error::Error, resolver::Query};
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use log::{info, trace};
+use wekan_common::artifact::{
+    card::Details as CardDetails,
+    checklist::Details as ChecklistDetails,
+    common::{AType, Artifact, Base, MostDetails},
+};
+use wekan_core::client::{CardApi, ChecklistApi, Client};
+
+#[cfg(test)]
+use crate::tests::mocks::Artifacts;
+#[cfg(not(test))]
+use wekan_core::http::operation::Artifacts;
+
+/// Document formats which can be selected with '-o'.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Markup {
+    Markdown,
+    Org,
+}
+
+impl Markup {
+    pub fn from_format(format: Option<&String>) -> Option<Self> {
+        match format.map(|f| f.as_str()) {
+            Some("markdown") | Some("md") => Some(Markup::Markdown),
+            Some("org") => Some(Markup::Org),
+            _ => None,
+        }
+    }
+}
+
+pub struct CardDocument {
+    pub details: CardDetails,
+    pub checklists: Vec<ChecklistDetails>,
+}
+
+pub struct ListDocument {
+    pub title: String,
+    pub cards: Vec<CardDocument>,
+}
+
+pub struct BoardDocument {
+    pub title: String,
+    pub lists: Vec<ListDocument>,
+}
+
+impl CardDocument {
+    pub async fn collect(
+        config: &wekan_core::config::UserConfig,
+        board_id: &str,
+        list_id: &str,
+        card_id: &str,
+    ) -> Result<Self, Error> {
+        info!("collect_card");
+        let mut client = <Client as CardApi>::new(config.clone(), board_id, list_id);
+        let details = client.get_one::<CardDetails>(card_id).await?;
+        let mut client = <Client as ChecklistApi>::new(config.clone(), board_id, card_id);
+        let mut checklists = Vec::new();
+        for c in client.get_all(AType::Checklist).await? {
+            checklists.push(client.get_one::<ChecklistDetails>(&c._id).await?);
+        }
+        Ok(Self {
+            details,
+            checklists,
+        })
+    }
+
+    pub fn render(&self, markup: &Markup, level: usize) -> String {
+        match markup {
+            Markup::Markdown => self.render_markdown(level),
+            Markup::Org => self.render_org(level),
+        }
+    }
+
+    fn render_markdown(&self, level: usize) -> String {
+        let mut output = format!("{} {}\n\n", "#".repeat(level), self.details.get_title());
+        let due_at = self.details.get_due_at();
+        let end_at = self.details.get_end_at();
+        if !due_at.is_empty() {
+            output.push_str(&format!("Due: {}\n", markdown_date(&due_at)));
+        }
+        if !end_at.is_empty() {
+            output.push_str(&format!("Done: {}\n", markdown_date(&end_at)));
+        }
+        if !due_at.is_empty() || !end_at.is_empty() {
+            output.push('\n');
+        }
+        let description = self.details.get_description();
+        if !description.is_empty() {
+            output.push_str(description.trim());
+            output.push_str("\n\n");
+        }
+        for c in &self.checklists {
+            output.push_str(&format!("{} {}\n\n", "#".repeat(level + 1), c.title));
+            output.push_str(&checkboxes(c, ""));
+            output.push('\n');
+        }
+        output
+    }
+
+    fn render_org(&self, level: usize) -> String {
+        let mut output = self.org_headline(level);
+        let description = self.details.get_description();
+        if !description.is_empty() {
+            output.push_str(description.trim());
+            output.push('\n');
+        }
+        for c in &self.checklists {
+            output.push_str(&format!("{} {}\n", "*".repeat(level + 1), c.title));
+            output.push_str(&checkboxes(c, ""));
+        }
+        output
+    }
+
+    fn org_headline(&self, level: usize) -> String {
+        let end_at = org_timestamp(&self.details.get_end_at(), false);
+        let due_at = org_timestamp(&self.details.get_due_at(), true);
+        let mut output = match end_at {
+            Some(_) => format!("{} DONE {}\n", "*".repeat(level), self.details.get_title()),
+            None => format!("{} {}\n", "*".repeat(level), self.details.get_title()),
+        };
+        let mut planning = Vec::new();
+        if let Some(e) = end_at {
+            planning.push(format!("CLOSED: {}", e));
+        }
+        if let Some(d) = due_at {
+            planning.push(format!("DEADLINE: {}", d));
+        }
+        if !planning.is_empty() {
+            output.push_str(&planning.join(" "));
+            output.push('\n');
+        }
+        output
+    }
+
+    fn render_list_entry(&self, markup: &Markup) -> String {
+        match markup {
+            Markup::Markdown => {
+                let mut output = format!(
+                    "- [{}] {}",
+                    if self.details.get_end_at().is_empty() {
+                        " "
+                    } else {
+                        "x"
+                    },
+                    self.details.get_title()
+                );
+                let due_at = self.details.get_due_at();
+                if !due_at.is_empty() {
+                    output.push_str(&format!(" (due {})", markdown_date(&due_at)));
+                }
+                output.push('\n');
+                for c in &self.checklists {
+                    output.push_str(&format!("  - {}\n", c.title));
+                    output.push_str(&checkboxes(c, "    "));
+                }
+                output
+            }
+            Markup::Org => {
+                let mut output = self.org_headline(3);
+                for c in &self.checklists {
+                    output.push_str(&format!("- {}\n", c.title));
+                    output.push_str(&checkboxes(c, "  "));
+                }
+                output
+            }
+        }
+    }
+}
+
+impl BoardDocument {
+    /// Requests every list, card and checklist of the board, like the table view does.
+    pub async fn collect(
+        query: &Query<'_>,
+        board_id: &str,
+        title: &str,
+        lists: Vec<Artifact>,
+    ) -> Result<Self, Error> {
+        info!("collect_board");
+        let mut documents = Vec::new();
+        for l in lists {
+            let cards = query
+                .inquire(AType::Card, Some(board_id), Some(&l._id), None, true)
+                .await?;
+            trace!("{:?}", cards);
+            let mut card_documents = Vec::new();
+            for c in cards {
+                card_documents
+                    .push(CardDocument::collect(&query.config, board_id, &l._id, &c._id).await?);
+            }
+            documents.push(ListDocument {
+                title: l.title,
+                cards: card_documents,
+            });
+        }
+        Ok(Self {
+            title: title.to_string(),
+            lists: documents,
+        })
+    }
+
+    pub fn render(&self, markup: &Markup) -> String {
+        let (heading, list_heading) = match markup {
+            Markup::Markdown => ("#", "##"),
+            Markup::Org => ("*", "**"),
+        };
+        let mut output = format!("{} {}\n", heading, self.title);
+        for l in &self.lists {
+            output.push('\n');
+            output.push_str(&format!("{} {}\n", list_heading, l.title));
+            if *markup == Markup::Markdown && !l.cards.is_empty() {
+                output.push('\n');
+            }
+            for c in &l.cards {
+                output.push_str(&c.render_list_entry(markup));
+            }
+        }
+        output
+    }
+}
+
+fn checkboxes(checklist: &ChecklistDetails, indent: &str) -> String {
+    checklist
+        .get_items()
+        .iter()
+        .map(|i| {
+            format!(
+                "{}- [{}] {}\n",
+                indent,
+                if i.is_finished { "x" } else { " " },
+                i.title
+            )
+        })
+        .collect()
+}
+
+fn parse_date(date: &str) -> Option<(NaiveDate, Option<String>)> {
+    match date.parse::<DateTime<Utc>>() {
+        Ok(d) => {
+            let time = if d.hour() == 0 && d.minute() == 0 {
+                None
+            } else {
+                Some(d.format("%H:%M").to_string())
+            };
+            Some((d.date_naive(), time))
+        }
+        Err(_e) => {
+            let day = match date.split_once('T') {
+                Some(d) => d.0,
+                None => date,
+            };
+            NaiveDate::parse_from_str(day, "%Y-%m-%d")
+                .ok()
+                .map(|d| (d, None))
+        }
+    }
+}
+
+fn markdown_date(date: &str) -> String {
+    match parse_date(date) {
+        Some((d, Some(t))) => format!("{} {}", d.format("%Y-%m-%d"), t),
+        Some((d, None)) => d.format("%Y-%m-%d").to_string(),
+        None => date.to_string(),
+    }
+}
+
+/// Active timestamps show up in the agenda, inactive ones only document a date.
+pub fn org_timestamp(date: &str, active: bool) -> Option<String> {
+    let (open, close) = if active { ('<', '>') } else { ('[', ']') };
+    parse_date(date).map(|(d, time)| match time {
+        Some(t) => format!("{}{} {}{}", open, d.format("%Y-%m-%d %a"), t, close),
+        None => format!("{}{}{}", open, d.format("%Y-%m-%d %a"), close),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wekan_common::artifact::tests::{MockDetails, MockResponse};
+
+    fn card() -> CardDocument {
+        CardDocument {
+            details: <CardDetails as MockDetails>::mock(
+                "my-id",
+                "my-title",
+                "2022-10-12T00:00:00.000Z",
+            ),
+            checklists: vec![<ChecklistDetails as MockResponse>::mock()],
+        }
+    }
+
+    #[test]
+    fn org_timestamps() {
+        assert_eq!(
+            org_timestamp("2022-10-12T00:00:00.000Z", true),
+            Some(String::from("<2022-10-12 Wed>"))
+        );
+        assert_eq!(
+            org_timestamp("2022-10-12T14:30:00.000Z", false),
+            Some(String::from("[2022-10-12 Wed 14:30]"))
+        );
+        assert_eq!(
+            org_timestamp("2020-10-12T", true),
+            Some(String::from("<2020-10-12 Mon>"))
+        );
+        assert_eq!(org_timestamp("", true), None);
+    }
+
+    #[test]
+    fn render_card_markdown() {
+        let expected = concat!(
+            "# my-title\n\n",
+            "Due: 2022-10-12\n",
+            "Done: 2022-10-12\n\n",
+            "## fake-checklist-title\n\n",
+            "- [x] fake-item-title-1\n",
+            "- [ ] fake-item-title-2\n\n",
+        );
+        assert_eq!(card().render(&Markup::Markdown, 1), expected);
+    }
+
+    #[test]
+    fn render_card_org() {
+        let expected = concat!(
+            "* DONE my-title\n",
+            "CLOSED: [2022-10-12 Wed] DEADLINE: <2022-10-12 Wed>\n",
+            "** fake-checklist-title\n",
+            "- [x] fake-item-title-1\n",
+            "- [ ] fake-item-title-2\n",
+        );
+        assert_eq!(card().render(&Markup::Org, 1), expected);
+    }
+
+    #[test]
+    fn render_board() {
+        let board = BoardDocument {
+            title: String::from("my-board"),
+            lists: vec![
+                ListDocument {
+                    title: String::from("Todo"),
+                    cards: vec![card()],
+                },
+                ListDocument {
+                    title: String::from("Done"),
+                    cards: Vec::new(),
+                },
+            ],
+        };
+        let expected = concat!(
+            "# my-board\n\n",
+            "## Todo\n\n",
+            "- [x] my-title (due 2022-10-12)\n",
+            "  - fake-checklist-title\n",
+            "    - [x] fake-item-title-1\n",
+            "    - [ ] fake-item-title-2\n\n",
+            "## Done\n",
+        );
+        assert_eq!(board.render(&Markup::Markdown), expected);
+        let expected = concat!(
+            "* my-board\n\n",
+            "** Todo\n",
+            "*** DONE my-title\n",
+            "CLOSED: [2022-10-12 Wed] DEADLINE: <2022-10-12 Wed>\n",
+            "- fake-checklist-title\n",
+            "  - [x] fake-item-title-1\n",
+            "  - [ ] fake-item-title-2\n\n",
+            "** Done\n",
+        );
+        assert_eq!(board.render(&Markup::Org), expected);
+    }
+}
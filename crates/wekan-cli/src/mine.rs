@@ -0,0 +1,151 @@
+use crate::{
+    error::Error,
+    resolver::Query,
+    result::WekanResult,
+    search::{boards, day, scan, Hit},
+};
+use chrono::{Duration, NaiveDate, Utc};
+use clap::Args as ClapArgs;
+use log::info;
+use wekan_common::artifact::{card::Details as CDetails, common::MostDetails};
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Show my cards",
+    long_about = "Show every card the logged in user is assigned to or a member of, grouped by board and list"
+)]
+pub struct Args {
+    #[clap(short = 'b', long, help = "Board name")]
+    pub board: Option<String>,
+    #[clap(
+        long,
+        parse(from_flag),
+        help = "Only cards which are overdue or due within the next 7 days"
+    )]
+    pub due_soon: bool,
+}
+
+const DUE_SOON_DAYS: i64 = 7;
+
+pub fn is_mine(details: &CDetails, user_id: &str) -> bool {
+    details.get_assignees().iter().any(|a| a == user_id)
+        || details.get_members().iter().any(|m| m == user_id)
+}
+
+fn due_day(hit: &Hit) -> Option<NaiveDate> {
+    hit.details.as_ref().and_then(|d| day(&d.get_due_at()))
+}
+
+/// Hits keep the order of boards and lists, cards of one list are sorted by due date, undated last.
+pub fn render(hits: &[Hit]) -> String {
+    let mut sorted = hits.to_vec();
+    sorted.sort_by(|a, b| {
+        let position = |h: &Hit| {
+            hits.iter()
+                .position(|o| o.board._id == h.board._id && o.list._id == h.list._id)
+        };
+        position(a)
+            .cmp(&position(b))
+            .then_with(|| match (due_day(a), due_day(b)) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_x), None) => std::cmp::Ordering::Less,
+                (None, Some(_y)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+    });
+    let mut output = String::new();
+    let mut board = "";
+    let mut list = "";
+    for hit in &sorted {
+        if hit.board._id != board {
+            board = &hit.board._id;
+            list = "";
+            output.push_str(&format!("{}\n", hit.board.title));
+        }
+        if hit.list._id != list {
+            list = &hit.list._id;
+            output.push_str(&format!("  {}\n", hit.list.title));
+        }
+        let due = match due_day(hit) {
+            Some(d) => d.to_string(),
+            None => String::from("-"),
+        };
+        output.push_str(&format!("    {:<10}  {}\n", due, hit.card.title));
+    }
+    output
+}
+
+pub async fn run(args: &Args, query: &mut Query<'_>, user_id: &str) -> Result<WekanResult, Error> {
+    info!("mine");
+    let boards = boards(query, args.board.as_deref()).await?;
+    let (hits, failed) = scan(query, &boards, true).await;
+    let due_soon = Utc::now().date_naive() + Duration::days(DUE_SOON_DAYS);
+    let mine = hits
+        .into_iter()
+        .filter(|h| matches!(&h.details, Some(d) if is_mine(d, user_id)))
+        .filter(|h| !args.due_soon || matches!(due_day(h), Some(d) if d <= due_soon))
+        .collect::<Vec<Hit>>();
+    let mut output = render(&mine);
+    if failed > 0 {
+        output.push_str(&format!("{} boards could not be searched\n", failed));
+    }
+    if output.is_empty() {
+        return WekanResult::new_msg("No cards assigned to you").ok();
+    }
+    WekanResult::new_msg(&output).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use wekan_common::artifact::{common::Artifact, tests::MockDetails};
+    use wekan_core::config::UserConfig;
+
+    fn hit(board: &str, list: &str, title: &str, due: &str) -> Hit {
+        let mut details = <CDetails as MockDetails>::mock(title, title, due);
+        details.assignees = Some(vec![String::from("me-id")]);
+        Hit {
+            board: <Artifact as MockDetails>::mock(board, board, "board"),
+            list: <Artifact as MockDetails>::mock(list, list, "list"),
+            card: <Artifact as MockDetails>::mock(title, title, "card"),
+            details: Some(details),
+            labels: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn group_and_sort() {
+        let hits = vec![
+            hit("Work", "Todo", "Later", ""),
+            hit("Work", "Doing", "Review", "2022-10-14T10:00:00.000Z"),
+            hit("Work", "Todo", "Soon", "2022-10-12T10:00:00.000Z"),
+            hit("Home", "Todo", "Shop", "2022-10-13T10:00:00.000Z"),
+        ];
+        assert!(is_mine(hits[0].details.as_ref().unwrap(), "me-id"));
+        assert!(!is_mine(hits[0].details.as_ref().unwrap(), "other-id"));
+        let expected = concat!(
+            "Work\n",
+            "  Todo\n",
+            "    2022-10-12  Soon\n",
+            "    -           Later\n",
+            "  Doing\n",
+            "    2022-10-14  Review\n",
+            "Home\n",
+            "  Todo\n",
+            "    2022-10-13  Shop\n",
+        );
+        assert_eq!(render(&hits), expected);
+    }
+
+    #[tokio::test]
+    async fn no_cards() {
+        let mut query = Query::new(UserConfig::mock(), "", true);
+        let args = Args {
+            board: Some(String::from("fake-board-title-1")),
+            due_soon: false,
+        };
+        let res = run(&args, &mut query, "me-id").await.unwrap();
+        assert_eq!(res.get_msg(), "No cards assigned to you");
+    }
+}
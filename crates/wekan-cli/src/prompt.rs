@@ -0,0 +1,45 @@
+use crate::error::{CliError, Error, Transform};
+use crossterm::tty::IsTty;
+use std::io::Write;
+
+/// Asks on stderr and reads the answer from stdin. Anything but 'y' or 'yes' declines.
+pub fn confirm(question: &str) -> Result<bool, Error> {
+    eprint!("{} [y/N] ", question);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(is_yes(&answer))
+}
+
+/// Confirmation of destructive commands. '--yes' skips it, without a terminal it is required.
+pub fn approve(question: &str, yes: bool) -> Result<bool, Error> {
+    if yes {
+        return Ok(true);
+    }
+    if !std::io::stdin().is_tty() {
+        return Err(CliError::new_msg("No terminal to confirm, pass '--yes' to proceed").as_enum());
+    }
+    confirm(question)
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers() {
+        assert!(is_yes("y\n"));
+        assert!(is_yes(" Yes "));
+        assert!(!is_yes("\n"));
+        assert!(!is_yes("no"));
+    }
+
+    #[test]
+    fn approve_with_yes() {
+        assert!(approve("Remove?", true).unwrap());
+    }
+}
@@ -28,6 +28,18 @@ pub struct Query<'a> {
 }
 
 impl<'a> Query<'a> {
+    /// Without the store feature nothing is read from the store and `deny_store` has no effect.
+    pub fn new(config: UserConfig, filter: &'a str, deny_store: bool) -> Self {
+        #[cfg(not(feature = "store"))]
+        let _ = deny_store;
+        Self {
+            config,
+            filter,
+            #[cfg(feature = "store")]
+            deny_store_usage: deny_store,
+        }
+    }
+
     pub async fn find_card_id(
         &mut self,
         board_id: &str,
@@ -50,6 +62,14 @@ impl<'a> Query<'a> {
     }
 
     pub async fn find_swimlane_id(&mut self, board_id: &str) -> Result<String, Error> {
+        self.find_swimlane_id_by_title(board_id, "Default").await
+    }
+
+    pub async fn find_swimlane_id_by_title(
+        &mut self,
+        board_id: &str,
+        name: &str,
+    ) -> Result<String, Error> {
         info!("find_swimlane_id");
         let swimlane = match self
             .inquire(AType::Swimlane, Some(board_id), None, None, false)
@@ -62,8 +82,7 @@ impl<'a> Query<'a> {
                     .await
             }
         };
-        self.confirm_valid_name(swimlane, &String::from("Default"))
-            .await
+        self.confirm_valid_name(swimlane, name).await
     }
 
     pub async fn find_list_id(&mut self, board_id: &str, name: &str) -> Result<String, Error> {
@@ -140,6 +159,32 @@ impl<'a> Query<'a> {
             }
         }
     }
+    /// Unlike the other find functions the title has to match completely, e.g. before overwriting.
+    pub async fn find_exact_id(
+        &mut self,
+        artifact_variant: AType,
+        board_id: Option<&str>,
+        list_id: Option<&str>,
+        title: &str,
+    ) -> Result<String, Error> {
+        info!("find_exact_id");
+        let artifacts = match self
+            .inquire(artifact_variant.clone(), board_id, list_id, None, false)
+            .await
+        {
+            Ok(o) => o,
+            Err(e) => {
+                trace!("{:?}", e);
+                self.fulfill_inquiry(artifact_variant, board_id, list_id)
+                    .await?
+            }
+        };
+        match artifacts.into_iter().find(|a| a.title == title) {
+            Some(a) => Ok(a._id),
+            None => Err(CliError::new_msg("Artifact not found").as_enum()),
+        }
+    }
+
     #[cfg(not(feature = "store"))]
     pub async fn inquire(
         &self,
@@ -505,34 +550,28 @@ mod tests {
     use crate::tests::mocks::{Mock, Mocks};
     #[tokio::test]
     async fn find_board_id() {
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-        };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-            deny_store_usage: true,
-        };
+        let mut query = Query::new(UserConfig::mock(), "", true);
         let res = query.find_board_id("fake-board-title-1").await.unwrap();
         assert_eq!(res, "fake-board-id-1");
     }
 
+    #[tokio::test]
+    async fn find_exact_id() {
+        let mut query = Query::new(UserConfig::mock(), "", true);
+        assert!(query
+            .find_exact_id(AType::Board, None, None, "fake-board")
+            .await
+            .is_err());
+        let res = query
+            .find_exact_id(AType::Board, None, None, "fake-board-title-2")
+            .await
+            .unwrap();
+        assert_eq!(res, "fake-board-id-2");
+    }
+
     #[tokio::test]
     async fn find_list_id() {
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-        };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-            deny_store_usage: true,
-        };
+        let mut query = Query::new(UserConfig::mock(), "", true);
         let res = query
             .find_list_id("fake-board-id-1", "fake-list-title-1")
             .await
@@ -542,17 +581,7 @@ mod tests {
 
     #[tokio::test]
     async fn find_card_id() {
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-        };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-            deny_store_usage: true,
-        };
+        let mut query = Query::new(UserConfig::mock(), "", true);
 
         let res = query
             .find_card_id("fake-board-id-2", "fake-list-id-1", "fake-card-title-1")
@@ -563,17 +592,7 @@ mod tests {
 
     #[tokio::test]
     async fn find_card_id_with_order() {
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            config: UserConfig::mock(),
-            filter: &String::from("b:f"),
-        };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            config: UserConfig::mock(),
-            filter: &String::from("b:f"),
-            deny_store_usage: true,
-        };
+        let mut query = Query::new(UserConfig::mock(), "b:f", true);
         let res = query
             .find_card_id("fake-board-id-2", "fake-list-id-1", "fake-card-title-1")
             .await
@@ -582,85 +601,35 @@ mod tests {
     }
     #[tokio::test]
     async fn request_boards() {
-        #[cfg(not(feature = "store"))]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-        };
-        #[cfg(feature = "store")]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-            deny_store_usage: false,
-        };
+        let query = Query::new(UserConfig::mock(), "", false);
         let res = query.request_boards().await.unwrap();
         assert_eq!(res, Vec::mocks(AType::Board));
     }
 
     #[tokio::test]
     async fn request_lists() {
-        #[cfg(not(feature = "store"))]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-        };
-        #[cfg(feature = "store")]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-            deny_store_usage: false,
-        };
+        let query = Query::new(UserConfig::mock(), "", false);
         let res = query.request_lists("fake-id-2").await.unwrap();
         assert_eq!(res, Vec::mocks(AType::List));
     }
 
     #[tokio::test]
     async fn request_cards() {
-        #[cfg(not(feature = "store"))]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-        };
-        #[cfg(feature = "store")]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-            deny_store_usage: false,
-        };
+        let query = Query::new(UserConfig::mock(), "", false);
         let res = query.request_cards("fake-id-2", "fake-id-2").await.unwrap();
         assert_eq!(res, Vec::mocks(AType::Card));
     }
 
     #[tokio::test]
     async fn request_swimlanes() {
-        #[cfg(not(feature = "store"))]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-        };
-        #[cfg(feature = "store")]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-            deny_store_usage: false,
-        };
+        let query = Query::new(UserConfig::mock(), "", false);
         let res = query.request_swimlanes("fake-id-2").await.unwrap();
         assert_eq!(res, Vec::mocks(AType::Swimlane));
     }
 
     #[tokio::test]
     async fn inquire() {
-        #[cfg(not(feature = "store"))]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-        };
-        #[cfg(feature = "store")]
-        let query = Query {
-            config: UserConfig::mock(),
-            filter: &String::new(),
-            deny_store_usage: false,
-        };
+        let query = Query::new(UserConfig::mock(), "", false);
         let mut res = query
             .inquire(AType::Board, None, None, None, false)
             .await
@@ -1,9 +1,13 @@
 use crate::error::Error;
+use wekan_common::artifact::common::{elisp_string, Elisp};
+
 #[derive(Debug)]
 pub struct WekanResult {
     message: String,
     exit_code: i8,
     next_workflow: Option<String>,
+    elisp: bool,
+    unchanged: bool,
 }
 
 impl WekanResult {
@@ -12,6 +16,30 @@ impl WekanResult {
             message: msg.to_string(),
             exit_code: 0,
             next_workflow: None,
+            elisp: false,
+            unchanged: false,
+        }
+    }
+
+    /// The message is already an s-expression and ends up unquoted in the result plist.
+    pub fn new_elisp(form: &str) -> Self {
+        Self {
+            message: form.to_string(),
+            exit_code: 0,
+            next_workflow: None,
+            elisp: true,
+            unchanged: false,
+        }
+    }
+
+    /// The command ended without a change on the host, e.g. when it got aborted or was a dry run.
+    pub fn new_unchanged(msg: &str) -> Self {
+        Self {
+            message: msg.to_string(),
+            exit_code: 0,
+            next_workflow: None,
+            elisp: false,
+            unchanged: true,
         }
     }
 
@@ -20,6 +48,8 @@ impl WekanResult {
             message: msg.to_string(),
             exit_code: 0,
             next_workflow: Some(workflow.to_string()),
+            elisp: false,
+            unchanged: false,
         }
     }
 
@@ -28,6 +58,8 @@ impl WekanResult {
             message: msg.to_string(),
             exit_code,
             next_workflow,
+            elisp: false,
+            unchanged: false,
         }
     }
     pub fn ok(&self) -> Result<WekanResult, Error> {
@@ -45,6 +77,32 @@ impl WekanResult {
     pub fn get_exit_code(&self) -> i8 {
         self.exit_code
     }
+
+    pub fn is_elisp(&self) -> bool {
+        self.elisp
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        self.unchanged
+    }
+}
+
+impl Elisp for WekanResult {
+    fn to_elisp(&self) -> String {
+        let body = if self.elisp {
+            format!(":data {}", self.message)
+        } else {
+            format!(":message {}", elisp_string(self.message.trim()))
+        };
+        let next_workflow = match &self.next_workflow {
+            Some(w) => elisp_string(w.trim()),
+            None => String::from("nil"),
+        };
+        format!(
+            "(:exit-code {} {} :next-workflow {})",
+            self.exit_code, body, next_workflow
+        )
+    }
 }
 
 impl Clone for WekanResult {
@@ -53,11 +111,15 @@ impl Clone for WekanResult {
             message: self.message.to_owned(),
             exit_code: self.exit_code,
             next_workflow: self.next_workflow.clone(),
+            elisp: self.elisp,
+            unchanged: self.unchanged,
         }
     }
     fn clone_from(&mut self, source: &Self) {
         self.message = source.message.to_owned();
         self.exit_code = source.exit_code;
-        self.next_workflow = source.next_workflow.clone()
+        self.next_workflow = source.next_workflow.clone();
+        self.elisp = source.elisp;
+        self.unchanged = source.unchanged;
     }
 }
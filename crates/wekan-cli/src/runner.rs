@@ -3,36 +3,55 @@ use async_trait::async_trait;
 use wekan_core::{
     client::{BoardApi, CardApi, ChecklistApi, Client, ListApi, LoginClient},
     config::{MandatoryConfig, UserConfig},
+    error::Error as CoreError,
     http::preflight_request::HealthCheck,
 };
 
 #[cfg(feature = "workspace")]
 use crate::workspace::Workspace;
 use crate::{
+    archive::{self, Args as ArArgs},
+    batch::{Args as BaArgs, Batch},
     board::{Args as BArgs, Runner as BRunner},
-    card::{argument::Args as CArgs, runner::Runner as CRunner},
+    card::{
+        argument::{Args as CArgs, Command as CCommand},
+        runner::Runner as CRunner,
+    },
     checklist::{Args as ChArgs, Runner as CheckRunner},
     command::{
         Args as RArgs, ArtifactCommand, BaseCommand, RootCommandRunner, Subcommand as Command,
     },
+    completion::{self, Args as CoArgs, CompleteArgs},
     config::runner::Runner as ConfigRunner,
-    display::CliDisplay,
+    diff::{self, Args as DArgs},
+    display::{is_elisp, CliDisplay},
+    due::{self, Args as DuArgs},
     error::{CliError, Error, InputError, Transform},
+    import::{Args as IArgs, Runner as IRunner},
+    journal::{self, Args as JArgs},
+    kanban::{Args as KArgs, Runner as KRunner},
     list::{Args as LArgs, Runner as LRunner},
+    markup::{BoardDocument, Markup},
+    mine::{self, Args as MArgs},
     resolver::Query,
     result::WekanResult,
+    search::{self, Args as SeArgs},
+    serve::{self, Args as SArgs},
+    shell::{Args as ShArgs, Shell},
     subcommand::{Describe, Inspect, Table as TArgs},
+    watch::{Args as WArgs, Runner as WRunner},
 };
 #[cfg(feature = "workspace")]
 use log::trace;
 #[cfg(not(test))]
 use log::Level;
 #[cfg(feature = "workspace")]
-use wekan_common::artifact::common::{Base, SortedArtifact};
+use wekan_common::artifact::common::SortedArtifact;
 use wekan_common::{
-    artifact::common::{AType, Artifact, IdReturner},
+    artifact::common::{AType, Artifact, Base, IdReturner},
     artifact::{board::Details as BDetails, card::Details as CDetails, list::Details as LDetails},
     validation::{
+        authentication::TokenHeader,
         constraint::{
             BoardConstraint as BConstraint, CardConstraint as CConstraint, ChecklistConstraint,
             Constraint, ListConstraint as LConstraint,
@@ -66,10 +85,11 @@ impl<'a> Runner {
             None => Logger::init(true).unwrap(),
         };
         let user_config = UserConfig::new();
-        let user_config = match user_config.read_config_context().await {
+        let mut user_config = match user_config.read_config_context().await {
             Ok(c) => c,
             Err(_e) => UserConfig::new(),
         };
+        user_config.dry_run = r_args.dry_run;
         let format = match r_args.output_format {
             Some(ref f) => f.to_owned(),
             None => "terminal".to_string(),
@@ -107,24 +127,55 @@ impl<'a> Runner {
     pub async fn run(&mut self) -> Result<WekanResult, Error> {
         #[cfg(feature = "workspace")]
         self.setup_workspace().await;
+        if !matches!(
+            self.subcommands,
+            Command::Config(_) | Command::Completion(_) | Command::Complete(_)
+        ) {
+            self.client.healthcheck().await?;
+        }
+        self.run_checked().await
+    }
+
+    /// Runs the subcommand against a host which already passed the healthcheck.
+    pub async fn run_checked(&mut self) -> Result<WekanResult, Error> {
+        match self.dispatch().await {
+            // A single write ends the command, restores carry on with placeholder ids instead.
+            Err(Error::Core(CoreError::DryRun(d))) => {
+                eprintln!("{}", d);
+                WekanResult::new_unchanged("Dry run, nothing was sent").ok()
+            }
+            r => r,
+        }
+    }
+
+    async fn dispatch(&mut self) -> Result<WekanResult, Error> {
         match self.subcommands.to_owned() {
             Command::Config(c) => {
                 let mut config = ConfigRunner::new(c.clone(), self.client.clone());
                 config.use_subcommand().await
             }
-            l => {
-                self.client.healthcheck().await?;
-                match l {
-                    Command::Board(b) => self.run_board(&b).await,
-                    Command::List(l) => self.run_list(&l).await,
-                    Command::Card(c) => self.run_card(&c).await,
-                    Command::Checklist(c) => self.run_checklist(&c).await,
-                    Command::Table(t) => self.run_table(&t).await,
-                    Command::Inspect(i) => self.run_inspect(i).await,
-                    Command::Describe(d) => self.run_describe(d).await,
-                    _ => WekanResult::new_msg("Not implemented.").ok(),
-                }
-            }
+            Command::Board(b) => self.run_board(&b).await,
+            Command::List(l) => self.run_list(&l).await,
+            Command::Card(c) => self.run_card(&c).await,
+            Command::Checklist(c) => self.run_checklist(&c).await,
+            Command::Table(t) => self.run_table(&t).await,
+            Command::Inspect(i) => self.run_inspect(i).await,
+            Command::Describe(d) => self.run_describe(d).await,
+            Command::Import(i) => self.run_import(&i).await,
+            Command::Serve(s) => self.run_serve(&s).await,
+            Command::Tui(t) => self.run_tui(&t).await,
+            Command::Shell(s) => self.run_shell(&s).await,
+            Command::Diff(d) => self.run_diff(&d).await,
+            Command::Watch(w) => self.run_watch(&w).await,
+            Command::Search(s) => self.run_search(&s).await,
+            Command::Mine(m) => self.run_mine(&m).await,
+            Command::Due(d) => self.run_due(&d).await,
+            Command::Archive(a) => self.run_archive(&a).await,
+            Command::Undo(u) => self.run_undo(&u).await,
+            Command::Batch(b) => self.run_batch(&b).await,
+            Command::Completion(c) => self.run_completion(&c),
+            Command::Complete(c) => self.run_complete(&c).await,
+            _ => WekanResult::new_msg("Not implemented.").ok(),
         }
     }
 
@@ -163,17 +214,11 @@ impl<'a> Runner {
                 Some(f) => filter.push_str(f),
                 None => {}
             };
-            #[cfg(feature = "store")]
-            let mut query = Query {
-                filter: &filter,
-                config: self.client.config.clone(),
-                deny_store_usage: self.global_options.no_store,
-            };
-            #[cfg(not(feature = "store"))]
-            let mut query = Query {
-                filter: &filter,
-                config: self.client.config.clone(),
-            };
+            let mut query = Query::new(
+                self.client.config.clone(),
+                &filter,
+                self.global_options.deny_store(),
+            );
             let mut b_constraint = Artifact {
                 _id: String::new(),
                 title: l_args.board.to_string(),
@@ -255,6 +300,15 @@ impl<'a> Runner {
     }
 
     async fn run_card(&self, c_args: &CArgs) -> Result<WekanResult, Error> {
+        match c_args.command {
+            Some(CCommand::Export(_)) | Some(CCommand::Import(_)) => {}
+            _ if c_args.get_selection().is_some() => {}
+            _ => {
+                if c_args.list.is_empty() {
+                    return CliError::new_msg("List name option '-l' needs to be supplied").err();
+                }
+            }
+        };
         let mut constraint = CConstraint {
             board: Artifact {
                 _id: String::new(),
@@ -273,22 +327,16 @@ impl<'a> Runner {
             None => {}
         };
 
-        let format = String::new();
+        let format = self.format.to_owned();
         match &self.global_options.filter {
             Some(f) => filter.push_str(f),
             None => filter.push_str("Default"),
         };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            filter: &filter,
-            config: self.client.config.clone(),
-            deny_store_usage: self.global_options.no_store,
-        };
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            filter: &filter,
-            config: self.client.config.clone(),
-        };
+        let mut query = Query::new(
+            self.client.config.clone(),
+            &filter,
+            self.global_options.deny_store(),
+        );
         match query
             .fulfill_constraint(Constraint::Card(constraint.to_owned()))
             .await
@@ -341,17 +389,11 @@ impl<'a> Runner {
             Some(f) => filter.push_str(f),
             None => {}
         };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            filter: &filter,
-            config: self.client.config.clone(),
-            deny_store_usage: self.global_options.no_store,
-        };
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            filter: &filter,
-            config: self.client.config.clone(),
-        };
+        let mut query = Query::new(
+            self.client.config.clone(),
+            &filter,
+            self.global_options.deny_store(),
+        );
         match query
             .fulfill_constraint(Constraint::Checklist(constraint.to_owned()))
             .await
@@ -386,23 +428,28 @@ impl<'a> Runner {
             Some(f) => filter.push_str(f),
             None => {}
         };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            filter: &filter,
-            config: self.client.config.clone(),
-            deny_store_usage: self.global_options.no_store,
-        };
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            filter: &filter,
-            config: self.client.config.clone(),
-        };
+        let mut query = Query::new(
+            self.client.config.clone(),
+            &filter,
+            self.global_options.deny_store(),
+        );
         match query.find_board_id(&table_args.name).await {
             Ok(board_id) => match query
                 .inquire(AType::List, Some(&board_id), None, None, true)
                 .await
             {
                 Ok(lists) => {
+                    if let Some(m) = Markup::from_format(self.global_options.output_format.as_ref())
+                    {
+                        let mut client = <Client as BoardApi>::new(self.client.config.clone());
+                        let title = match client.get_one::<BDetails>(&board_id).await {
+                            Ok(b) => b.get_title(),
+                            Err(_e) => table_args.name.to_owned(),
+                        };
+                        let board =
+                            BoardDocument::collect(&query, &board_id, &title, lists).await?;
+                        return self.display.format_board_document(&board, &m);
+                    }
                     let mut iterator = lists.iter();
                     let mut cards_of_lists = Vec::new();
                     if !lists.is_empty() {
@@ -421,7 +468,12 @@ impl<'a> Runner {
                                 Err(_e) => cards_of_lists.push(Vec::new()),
                             };
                         }
+                        if is_elisp(self.global_options.output_format.as_ref()) {
+                            return self.display.format_table_elisp(lists, cards_of_lists);
+                        }
                         self.display.format_to_table_layout(lists, cards_of_lists)
+                    } else if is_elisp(self.global_options.output_format.as_ref()) {
+                        self.display.format_table_elisp(lists, Vec::new())
                     } else {
                         self.display.format_to_table_layout(lists, Vec::new())
                     }
@@ -501,17 +553,11 @@ impl<'a> Runner {
             Some(f) => filter.push_str(f),
             None => {}
         };
-        #[cfg(feature = "store")]
-        let mut query = Query {
-            filter: &filter,
-            config: self.client.config.clone(),
-            deny_store_usage: self.global_options.no_store,
-        };
-        #[cfg(not(feature = "store"))]
-        let mut query = Query {
-            filter: &filter,
-            config: self.client.config.clone(),
-        };
+        let mut query = Query::new(
+            self.client.config.clone(),
+            &filter,
+            self.global_options.deny_store(),
+        );
         if v.len() != 2 {
             WekanResult::new_msg("Format not correct resource_type/resource_name").ok()
         } else {
@@ -575,6 +621,101 @@ impl<'a> Runner {
         }
     }
 
+    async fn run_import(&mut self, i_args: &IArgs) -> Result<WekanResult, Error> {
+        let mut runner = IRunner::new(
+            i_args.clone(),
+            self.client.config.clone(),
+            &self.client.get_user_id(),
+        );
+        runner.run().await
+    }
+
+    async fn run_serve(&mut self, s_args: &SArgs) -> Result<WekanResult, Error> {
+        let client = <Client as BoardApi>::new(self.client.config.clone());
+        serve::run(s_args, client, &self.client.get_user_id()).await
+    }
+
+    async fn run_tui(&mut self, k_args: &KArgs) -> Result<WekanResult, Error> {
+        let client = <Client as BoardApi>::new(self.client.config.clone());
+        let mut runner = KRunner::new(k_args.clone(), client, &self.global_options);
+        runner.run().await
+    }
+
+    async fn run_diff(&mut self, d_args: &DArgs) -> Result<WekanResult, Error> {
+        let mut query = Query::new(
+            self.client.config.clone(),
+            "",
+            self.global_options.deny_store(),
+        );
+        diff::run(d_args, &mut query).await
+    }
+
+    async fn run_watch(&mut self, w_args: &WArgs) -> Result<WekanResult, Error> {
+        let client = <Client as BoardApi>::new(self.client.config.clone());
+        let mut runner = WRunner::new(w_args.clone(), client, &self.global_options);
+        runner.run().await
+    }
+
+    async fn run_search(&mut self, s_args: &SeArgs) -> Result<WekanResult, Error> {
+        let query = Query::new(
+            self.client.config.clone(),
+            "",
+            self.global_options.deny_store(),
+        );
+        search::run(s_args, &query, &self.client.get_user_id()).await
+    }
+
+    async fn run_mine(&mut self, m_args: &MArgs) -> Result<WekanResult, Error> {
+        let mut query = Query::new(
+            self.client.config.clone(),
+            "",
+            self.global_options.deny_store(),
+        );
+        mine::run(m_args, &mut query, &self.client.get_user_id()).await
+    }
+
+    async fn run_due(&mut self, d_args: &DuArgs) -> Result<WekanResult, Error> {
+        let mut query = Query::new(
+            self.client.config.clone(),
+            "",
+            self.global_options.deny_store(),
+        );
+        let color = self.global_options.output_format.is_none();
+        due::run(d_args, &mut query, color).await
+    }
+
+    async fn run_archive(&mut self, a_args: &ArArgs) -> Result<WekanResult, Error> {
+        let mut query = Query::new(
+            self.client.config.clone(),
+            "",
+            self.global_options.deny_store(),
+        );
+        archive::run(a_args, &mut query).await
+    }
+
+    async fn run_undo(&mut self, u_args: &JArgs) -> Result<WekanResult, Error> {
+        journal::run(u_args, &self.client.config, &self.client.get_user_id()).await
+    }
+
+    async fn run_batch(&mut self, b_args: &BaArgs) -> Result<WekanResult, Error> {
+        let mut batch = Batch::new(self.client.clone(), &self.global_options);
+        batch.run(b_args).await
+    }
+
+    fn run_completion(&self, c_args: &CoArgs) -> Result<WekanResult, Error> {
+        WekanResult::new_msg(completion::script(c_args.shell)).ok()
+    }
+
+    async fn run_complete(&self, c_args: &CompleteArgs) -> Result<WekanResult, Error> {
+        let query = Query::new(self.client.config.clone(), "", false);
+        completion::complete(c_args, &query).await
+    }
+
+    async fn run_shell(&mut self, _s_args: &ShArgs) -> Result<WekanResult, Error> {
+        let mut shell = Shell::new(self.client.clone(), &self.global_options);
+        shell.run().await
+    }
+
     fn verify_id_length(&self, id: String) -> Result<bool, Error> {
         if id.len() == 17 {
             Ok(true)
@@ -601,3 +742,100 @@ impl ReadContext for UserConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{command::WekanParser, tests::mocks::Mock};
+    use clap::Parser;
+
+    fn dry_runner(line: &str) -> Runner {
+        let parser = WekanParser::try_parse_from(shell_words::split(line).unwrap()).unwrap();
+        let mut config = UserConfig::mock();
+        config.dry_run = true;
+        Runner {
+            client: LoginClient::new(config),
+            format: String::from("terminal"),
+            display: CliDisplay::new(Vec::new()),
+            subcommands: parser.command,
+            global_options: parser.delegate,
+            #[cfg(feature = "store")]
+            workspace: Vec::new(),
+        }
+    }
+
+    async fn dry_run(line: &str) -> Result<WekanResult, Error> {
+        dry_runner(line).dispatch().await
+    }
+
+    #[tokio::test]
+    async fn stop_writes_of_a_dry_run() {
+        for line in [
+            "wekan-cli board create new-board",
+            "wekan-cli card -b fake-board-title-1 -l fake-list-title-1 -n fake-card-title-1 update -t new",
+            "wekan-cli board -n fake-board-title-1 rm -y",
+        ] {
+            match dry_run(line).await {
+                Err(Error::Core(CoreError::DryRun(_d))) => {}
+                r => panic!("'{}' was not stopped: {:?}", line, r),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn leave_a_dry_run_unchanged() {
+        for line in [
+            "wekan-cli list -b fake-board-title-1 -n fake-list-title-1 archive",
+            "wekan-cli board -n fake-board-title-1 rm -y",
+        ] {
+            let res = dry_runner(line).run_checked().await.unwrap();
+            assert!(res.is_unchanged(), "'{}' looks changed", line);
+        }
+    }
+
+    #[tokio::test]
+    async fn carry_on_with_a_dry_run_copy() {
+        for line in [
+            "wekan-cli board -n fake-board-title-1 cp new-board",
+            "wekan-cli list -b fake-board-title-1 -n fake-list-title-1 cp new-list",
+        ] {
+            match dry_run(line).await {
+                Ok(r) => assert_eq!(r.get_msg(), "Dry run, nothing was sent"),
+                r => panic!("'{}' was stopped: {:?}", line, r),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_a_csv_import() {
+        #[cfg(feature = "store")]
+        let card = "store-fake-card-title-1";
+        #[cfg(not(feature = "store"))]
+        let card = "fake-card-title-1";
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            format!(
+                "title,list\n{},fake-list-title-2\nNew,fake-list-title-1\n",
+                card
+            ),
+        )
+        .unwrap();
+        let line = format!(
+            "wekan-cli card -b fake-board-title-1 import {}",
+            file.path().display()
+        );
+        let res = dry_run(&line).await.unwrap();
+        assert_eq!(
+            res.get_msg(),
+            format!(
+                concat!(
+                    "Dry run, nothing was changed:\n",
+                    "update '{}' and move it to 'fake-list-title-2'\n",
+                    "create 'New' in 'fake-list-title-1'"
+                ),
+                card
+            )
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use crate::{
+    card::record::label_keys,
+    error::{CliError, Error, Transform},
+    resolver::Query,
+    result::WekanResult,
+};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use clap::Args as ClapArgs;
+use futures::future::join_all;
+use log::{info, trace};
+use std::str::FromStr;
+use wekan_common::artifact::{
+    board::Details as BDetails,
+    card::Details as CDetails,
+    common::{AType, Artifact, MostDetails},
+};
+use wekan_core::client::{BoardApi, CardApi, Client};
+
+#[cfg(test)]
+use crate::tests::mocks::Artifacts;
+#[cfg(not(test))]
+use wekan_core::http::operation::Artifacts;
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Search cards on all boards",
+    long_about = "Search the cards of every accessible board and print the path of each match"
+)]
+pub struct Args {
+    /// Text to search for, case insensitive
+    pub text: String,
+    #[clap(
+        long = "in",
+        value_delimiter = ',',
+        default_value = "title,description",
+        help = "Fields to search in: title, description"
+    )]
+    pub fields: Vec<Field>,
+    #[clap(long, help = "Label name or color the card needs to have")]
+    pub label: Option<String>,
+    #[clap(long, help = "User id of an assignee, 'me' for the logged in user")]
+    pub assignee: Option<String>,
+    #[clap(long, parse(try_from_str = parse_day), help = "Format: Gregorian Day in format (YYYY-MM-DD)")]
+    pub due_before: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Description,
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "title" => Ok(Field::Title),
+            "description" => Ok(Field::Description),
+            _ => Err(String::from("Field needs to be one of title, description")),
+        }
+    }
+}
+
+fn parse_day(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_e| String::from("Not a correct date format YYYY-MM-DD"))
+}
+
+/// Day of a date as returned by the API, e.g. '2022-10-12T10:00:00.000Z'.
+pub fn day(date: &str) -> Option<NaiveDate> {
+    date.get(0..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+/// Point in time of a date as returned by the API. Dates without time start at midnight.
+pub fn datetime(date: &str) -> Option<DateTime<Utc>> {
+    match date.parse::<DateTime<Utc>>() {
+        Ok(d) => Some(d),
+        Err(_e) => day(date)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| Utc.from_utc_datetime(&d)),
+    }
+}
+
+/// One card with its location. Details and label names are only requested if needed.
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub board: Artifact,
+    pub list: Artifact,
+    pub card: Artifact,
+    pub details: Option<CDetails>,
+    /// Names and colors of the labels.
+    pub labels: Vec<String>,
+}
+
+impl Hit {
+    pub fn path(&self) -> String {
+        format!(
+            "/{}/{}/{}",
+            self.board.title, self.list.title, self.card.title
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    pub text: String,
+    pub fields: Vec<Field>,
+    pub label: Option<String>,
+    pub assignee: Option<String>,
+    pub due_before: Option<NaiveDate>,
+}
+
+impl Filter {
+    pub fn new(args: &Args, user_id: &str) -> Self {
+        Self {
+            text: args.text.to_lowercase(),
+            fields: args.fields.to_owned(),
+            label: args.label.to_owned(),
+            assignee: args.assignee.as_ref().map(|a| match a.as_str() {
+                "me" => user_id.to_string(),
+                a => a.to_string(),
+            }),
+            due_before: args.due_before,
+        }
+    }
+
+    pub fn needs_details(&self) -> bool {
+        self.fields.contains(&Field::Description)
+            || self.label.is_some()
+            || self.assignee.is_some()
+            || self.due_before.is_some()
+    }
+
+    pub fn matches(&self, hit: &Hit) -> bool {
+        let description = match &hit.details {
+            Some(d) => d.get_description(),
+            None => String::new(),
+        };
+        let text = self.fields.iter().any(|f| match f {
+            Field::Title => hit.card.title.to_lowercase().contains(&self.text),
+            Field::Description => description.to_lowercase().contains(&self.text),
+        });
+        let label = match &self.label {
+            Some(l) => hit.labels.contains(l),
+            None => true,
+        };
+        let assignee = match (&self.assignee, &hit.details) {
+            (Some(a), Some(d)) => d.get_assignees().contains(a),
+            (Some(_a), None) => false,
+            (None, _d) => true,
+        };
+        let due = match (&self.due_before, &hit.details) {
+            (Some(before), Some(d)) => matches!(day(&d.get_due_at()), Some(due) if &due < before),
+            (Some(_b), None) => false,
+            (None, _d) => true,
+        };
+        text && label && assignee && due
+    }
+}
+
+/// One board by name or all accessible boards.
+pub async fn boards(query: &mut Query<'_>, name: Option<&str>) -> Result<Vec<Artifact>, Error> {
+    match name {
+        Some(n) => match query.find_board_id(n).await {
+            Ok(id) => Ok(vec![Artifact {
+                _id: id,
+                title: n.to_string(),
+                r#type: AType::Board,
+            }]),
+            Err(_e) => Err(CliError::new_msg("Board name not found").as_enum()),
+        },
+        None => query.inquire(AType::Board, None, None, None, false).await,
+    }
+}
+
+/// Boards are scanned concurrently. Lists and cards come from the store while it is fresh.
+/// Boards which fail to load are skipped and counted.
+pub async fn scan(query: &Query<'_>, boards: &[Artifact], details: bool) -> (Vec<Hit>, usize) {
+    let scans = join_all(boards.iter().map(|b| scan_board(query, b, details))).await;
+    let mut hits = Vec::new();
+    let mut failed = 0;
+    for (board, scan) in boards.iter().zip(scans) {
+        match scan {
+            Ok(h) => hits.extend(h),
+            Err(e) => {
+                trace!("{}: {:?}", board.title, e);
+                failed += 1;
+            }
+        }
+    }
+    (hits, failed)
+}
+
+async fn scan_board(query: &Query<'_>, board: &Artifact, details: bool) -> Result<Vec<Hit>, Error> {
+    let lists = query
+        .inquire(AType::List, Some(&board._id), None, None, false)
+        .await?;
+    scan_lists(query, board, lists, details).await
+}
+
+/// Cards of some lists of a board, details are only requested for these.
+pub async fn scan_lists(
+    query: &Query<'_>,
+    board: &Artifact,
+    lists: Vec<Artifact>,
+    details: bool,
+) -> Result<Vec<Hit>, Error> {
+    let labels = if details {
+        let mut client = <Client as BoardApi>::new(query.config.clone());
+        client.get_one::<BDetails>(&board._id).await?.get_labels()
+    } else {
+        Vec::new()
+    };
+    let mut hits = Vec::new();
+    for list in lists {
+        let cards = query
+            .inquire(AType::Card, Some(&board._id), Some(&list._id), None, false)
+            .await?;
+        let mut client = <Client as CardApi>::new(query.config.clone(), &board._id, &list._id);
+        for card in cards {
+            let (card_details, label_keys) = if details {
+                let d = client.get_one::<CDetails>(&card._id).await?;
+                let keys = label_keys(&d.get_label_ids(), &labels);
+                (Some(d), keys)
+            } else {
+                (None, Vec::new())
+            };
+            hits.push(Hit {
+                board: board.to_owned(),
+                list: list.to_owned(),
+                card,
+                details: card_details,
+                labels: label_keys,
+            });
+        }
+    }
+    Ok(hits)
+}
+
+pub async fn run(args: &Args, query: &Query<'_>, user_id: &str) -> Result<WekanResult, Error> {
+    info!("search");
+    let filter = Filter::new(args, user_id);
+    let boards = query.inquire(AType::Board, None, None, None, false).await?;
+    let (hits, failed) = scan(query, &boards, filter.needs_details()).await;
+    let mut output = hits
+        .iter()
+        .filter(|h| filter.matches(h))
+        .map(|h| h.path() + "\n")
+        .collect::<String>();
+    if failed > 0 {
+        output.push_str(&format!("{} boards could not be searched\n", failed));
+    }
+    if output.is_empty() {
+        return CliError::new_msg(&format!("No card matches '{}'", args.text)).err();
+    }
+    WekanResult::new_msg(&output).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::{Subcommand, WekanParser},
+        tests::mocks::Mock,
+    };
+    use clap::Parser;
+    use wekan_common::artifact::tests::MockDetails;
+    use wekan_core::config::UserConfig;
+
+    fn args(text: &str) -> Args {
+        Args {
+            text: text.to_string(),
+            fields: vec![Field::Title],
+            label: None,
+            assignee: None,
+            due_before: None,
+        }
+    }
+
+    fn hit(title: &str) -> Hit {
+        Hit {
+            board: <Artifact as MockDetails>::mock("b", "Board", "board"),
+            list: <Artifact as MockDetails>::mock("l", "Todo", "list"),
+            card: <Artifact as MockDetails>::mock("c", title, "card"),
+            details: Some(<CDetails as MockDetails>::mock(
+                "c",
+                title,
+                "2022-10-12T10:00:00.000Z",
+            )),
+            labels: vec![String::from("bug")],
+        }
+    }
+
+    #[test]
+    fn filter_cards() {
+        let filter = Filter::new(&args("WRITE"), "me-id");
+        assert!(!filter.needs_details());
+        assert!(filter.matches(&hit("Write tests")));
+        assert!(!filter.matches(&hit("Review")));
+        let mut a = args("write");
+        a.label = Some(String::from("bug"));
+        a.due_before = parse_day("2022-10-13").ok();
+        let filter = Filter::new(&a, "me-id");
+        assert!(filter.needs_details());
+        assert!(filter.matches(&hit("Write tests")));
+        a.due_before = parse_day("2022-10-12").ok();
+        assert!(!Filter::new(&a, "me-id").matches(&hit("Write tests")));
+        a.due_before = None;
+        a.assignee = Some(String::from("me"));
+        let filter = Filter::new(&a, "me-id");
+        assert_eq!(filter.assignee, Some(String::from("me-id")));
+        assert!(!filter.matches(&hit("Write tests")));
+        assert_eq!(hit("Write").path(), "/Board/Todo/Write");
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(Field::from_str("description"), Ok(Field::Description));
+        assert!(Field::from_str("labels").is_err());
+        assert_eq!(
+            day("2022-10-12T10:00:00.000Z"),
+            parse_day("2022-10-12").ok()
+        );
+        assert_eq!(day(""), None);
+        let parser = WekanParser::try_parse_from(["wekan-cli", "search", "x"]).unwrap();
+        match parser.command {
+            Subcommand::Search(a) => assert_eq!(a.fields, vec![Field::Title, Field::Description]),
+            _ => panic!("Not a search"),
+        }
+        let parser =
+            WekanParser::try_parse_from(["wekan-cli", "search", "x", "--in", "description"])
+                .unwrap();
+        match parser.command {
+            Subcommand::Search(a) => assert_eq!(a.fields, vec![Field::Description]),
+            _ => panic!("Not a search"),
+        }
+    }
+
+    #[tokio::test]
+    async fn search_all_boards() {
+        let query = Query::new(UserConfig::mock(), "", true);
+        let res = run(&args("title-1"), &query, "me-id").await.unwrap();
+        assert_eq!(
+            res.get_msg(),
+            concat!(
+                "/fake-board-title-1/fake-list-title-1/fake-card-title-1\n",
+                "/fake-board-title-1/fake-list-title-2/fake-card-title-1\n",
+                "/fake-board-title-2/fake-list-title-1/fake-card-title-1\n",
+                "/fake-board-title-2/fake-list-title-2/fake-card-title-1\n",
+            )
+        );
+        assert!(run(&args("nothing"), &query, "me-id").await.is_err());
+    }
+}
@@ -0,0 +1,629 @@
+use crate::{
+    error::{CliError, Error, Transform},
+    journal::{self, CardState, Change},
+    result::WekanResult,
+};
+use clap::Args as ClapArgs;
+use log::{debug, info, trace};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use wekan_common::{
+    artifact::{
+        board::Details as BDetails,
+        card::Details as CDetails,
+        checklist::Details as ChDetails,
+        common::{AType, Artifact},
+    },
+    http::{
+        artifact::{CreateArtifact, ResponseOk},
+        card::{CreateCard, MoveCard, UpdateCard},
+        checklist::CreateChecklist,
+    },
+};
+use wekan_core::{
+    client::{BoardApi, CardApi, ChecklistApi, Client, ListApi, SwimlaneApi},
+    error::Error as CoreError,
+};
+
+#[cfg(test)]
+use crate::tests::mocks::{Artifacts, Operation};
+#[cfg(not(test))]
+use wekan_core::http::operation::{Artifacts, Operation};
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Serve requests over JSON-RPC",
+    long_about = "Answer JSON-RPC 2.0 requests, one per line, with one long-lived client and cache"
+)]
+pub struct Args {
+    #[clap(
+        long,
+        parse(from_flag),
+        help = "Read requests from stdin and write responses to stdout"
+    )]
+    pub stdio: bool,
+}
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const NOT_FOUND: i32 = -32001;
+const REQUEST_FAILED: i32 = -32002;
+const NOT_ALLOWED: i32 = -32003;
+const DRY_RUN: i32 = -32004;
+const LOCAL_FAILED: i32 = -32005;
+
+#[derive(Deserialize, Debug)]
+pub struct Request {
+    pub jsonrpc: Option<String>,
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Params,
+}
+
+/// Artifacts are addressed by title or id, like on the command line.
+#[derive(Deserialize, Debug, Default)]
+pub struct Params {
+    pub board: Option<String>,
+    pub list: Option<String>,
+    pub card: Option<String>,
+    pub checklist: Option<String>,
+    pub swimlane: Option<String>,
+    pub to: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub due_at: Option<String>,
+    pub end_at: Option<String>,
+    pub sort: Option<f32>,
+    #[serde(default)]
+    pub items: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Response {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: &str) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// The details of an error are only traced, the client gets a stable message per kind.
+impl From<CoreError> for RpcError {
+    fn from(e: CoreError) -> Self {
+        trace!("{:?}", e);
+        match e {
+            CoreError::Http(_) => Self::new(REQUEST_FAILED, "Request to the server failed"),
+            CoreError::Constraint(_) => Self::new(NOT_ALLOWED, "Request is not allowed"),
+            CoreError::DryRun(d) => Self::new(DRY_RUN, &format!("Dry run: {}", d)),
+            CoreError::Io(_) => Self::new(LOCAL_FAILED, "Reading or writing a file failed"),
+            CoreError::Yaml(_) | CoreError::Json(_) => {
+                Self::new(REQUEST_FAILED, "Response of the server could not be parsed")
+            }
+        }
+    }
+}
+
+impl From<Error> for RpcError {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Core(c) => Self::from(c),
+            Error::Cli(c) => Self::new(REQUEST_FAILED, &c.message),
+            Error::Input(i) => Self::new(INVALID_PARAMS, &i.message),
+            e => {
+                trace!("{:?}", e);
+                Self::new(LOCAL_FAILED, "Reading or writing a local file failed")
+            }
+        }
+    }
+}
+
+/// Keeps one client and the children of every requested artifact until they change.
+pub struct Server {
+    client: Client,
+    user_id: String,
+    cache: HashMap<String, Vec<Artifact>>,
+    running: bool,
+}
+
+impl Server {
+    pub fn new(client: Client, user_id: &str) -> Self {
+        Self {
+            client,
+            user_id: user_id.to_string(),
+            cache: HashMap::new(),
+            running: true,
+        }
+    }
+
+    pub async fn run_stdio(&mut self) -> Result<WekanResult, Error> {
+        info!("run_stdio");
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+        while self.running {
+            let line = match lines.next_line().await? {
+                Some(l) => l,
+                None => break,
+            };
+            if let Some(response) = self.handle(&line).await {
+                stdout.write_all(response.as_bytes()).await?;
+                stdout.write_all(b"\n").await?;
+                stdout.flush().await?;
+            }
+        }
+        WekanResult::new_msg("").ok()
+    }
+
+    /// Returns the serialized response, notifications without id are not answered.
+    pub async fn handle(&mut self, line: &str) -> Option<String> {
+        if line.trim().is_empty() {
+            return None;
+        }
+        let response = match serde_json::from_str::<Value>(line) {
+            Err(e) => Self::response(Value::Null, Err(RpcError::new(PARSE_ERROR, &e.to_string()))),
+            Ok(v) => {
+                let id = v.get("id").cloned();
+                match serde_json::from_value::<Request>(v) {
+                    Err(e) => Self::response(
+                        id.unwrap_or(Value::Null),
+                        Err(RpcError::new(INVALID_REQUEST, &e.to_string())),
+                    ),
+                    Ok(r) => {
+                        trace!("{:?}", r);
+                        let result = self.dispatch(&r.method, &r.params).await;
+                        r.id.as_ref()?;
+                        Self::response(r.id.unwrap_or(Value::Null), result)
+                    }
+                }
+            }
+        };
+        serde_json::to_string(&response).ok()
+    }
+
+    fn response(id: Value, result: Result<Value, RpcError>) -> Response {
+        match result {
+            Ok(r) => Response {
+                jsonrpc: "2.0",
+                id,
+                result: Some(r),
+                error: None,
+            },
+            Err(e) => Response {
+                jsonrpc: "2.0",
+                id,
+                result: None,
+                error: Some(e),
+            },
+        }
+    }
+
+    async fn dispatch(&mut self, method: &str, params: &Params) -> Result<Value, RpcError> {
+        debug!("dispatch {}", method);
+        match method {
+            "board.ls" => self.board_ls().await,
+            "board.details" => self.board_details(params).await,
+            "list.ls" => self.list_ls(params).await,
+            "list.create" => self.list_create(params).await,
+            "swimlane.ls" => self.swimlane_ls(params).await,
+            "card.ls" => self.card_ls(params).await,
+            "card.details" => self.card_details(params).await,
+            "card.create" => self.card_create(params).await,
+            "card.update" => self.card_update(params).await,
+            "card.move" => self.card_move(params).await,
+            "card.rm" => self.card_remove(params).await,
+            "checklist.ls" => self.checklist_ls(params).await,
+            "checklist.details" => self.checklist_details(params).await,
+            "checklist.create" => self.checklist_create(params).await,
+            "cache.clear" => {
+                self.cache.clear();
+                Ok(Value::Bool(true))
+            }
+            "shutdown" => {
+                self.running = false;
+                Ok(Value::Null)
+            }
+            m => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                &format!("Method '{}' not found", m),
+            )),
+        }
+    }
+
+    async fn board_ls(&mut self) -> Result<Value, RpcError> {
+        let boards = self.children(AType::Board, "board").await?;
+        Ok(json!(boards))
+    }
+
+    async fn board_details(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let board_id = self.board_id(params).await?;
+        BoardApi::set_base(&mut self.client, "boards/");
+        to_value(self.client.get_one::<BDetails>(&board_id).await)
+    }
+
+    async fn list_ls(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let board_id = self.board_id(params).await?;
+        Ok(json!(self.lists(&board_id).await?))
+    }
+
+    async fn list_create(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let board_id = self.board_id(params).await?;
+        let title = required(&params.title, "title")?;
+        ListApi::set_base(&mut self.client, &board_id);
+        let body = CreateArtifact {
+            _id: String::new(),
+            title: title.to_string(),
+        };
+        let created = self
+            .client
+            .create::<CreateArtifact, ResponseOk>(&body)
+            .await;
+        self.cache.remove(&format!("list:{}", board_id));
+        to_value(created)
+    }
+
+    async fn swimlane_ls(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let board_id = self.board_id(params).await?;
+        Ok(json!(self.swimlanes(&board_id).await?))
+    }
+
+    async fn card_ls(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, list_id) = self.list_id(params).await?;
+        Ok(json!(self.cards(&board_id, &list_id).await?))
+    }
+
+    async fn card_details(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, list_id, card_id) = self.card_id(params).await?;
+        CardApi::set_base(&mut self.client, &board_id, &list_id);
+        to_value(self.client.get_one::<CDetails>(&card_id).await)
+    }
+
+    async fn card_create(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, list_id) = self.list_id(params).await?;
+        let title = required(&params.title, "title")?;
+        let swimlanes = self.swimlanes(&board_id).await?;
+        let swimlane_name = params.swimlane.as_deref().unwrap_or("Default");
+        let swimlane_id = find(&swimlanes, swimlane_name, "swimlane")?;
+        CardApi::set_base(&mut self.client, &board_id, &list_id);
+        let body = CreateCard {
+            _id: String::new(),
+            author_id: self.user_id.to_owned(),
+            members: None,
+            assignees: None,
+            title: title.to_string(),
+            description: params.description.to_owned().unwrap_or_default(),
+            swimlane_id,
+        };
+        let created = self.client.create::<CreateCard, ResponseOk>(&body).await;
+        self.cache.remove(&format!("card:{}:{}", board_id, list_id));
+        to_value(created)
+    }
+
+    async fn card_update(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, list_id, card_id) = self.card_id(params).await?;
+        CardApi::set_base(&mut self.client, &board_id, &list_id);
+        let card = self.card_state(&card_id).await?;
+        let summary = format!("update card '{}'", card.title);
+        let body = UpdateCard {
+            _id: card_id,
+            title: params.title.to_owned(),
+            description: params.description.to_owned(),
+            due_at: params.due_at.to_owned(),
+            end_at: params.end_at.to_owned(),
+            sort: params.sort,
+            labels: None,
+            assignees: None,
+        };
+        let updated = self.client.put::<UpdateCard, ResponseOk>(&body).await;
+        self.cache.remove(&format!("card:{}:{}", board_id, list_id));
+        if updated.is_ok() {
+            self.record(&summary, Change::Update { card }).await;
+        }
+        to_value(updated)
+    }
+
+    async fn card_move(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, list_id, card_id) = self.card_id(params).await?;
+        let to = required(&params.to, "to")?;
+        let lists = self.lists(&board_id).await?;
+        let destination_id = find(&lists, to, "list")?;
+        CardApi::set_base(&mut self.client, &board_id, &list_id);
+        let card = self.card_state(&card_id).await?;
+        let summary = format!("mv card '{}'", card.title);
+        let body = MoveCard {
+            _id: card_id,
+            list_id: destination_id.to_owned(),
+            board_id: None,
+            swimlane_id: None,
+            sort: None,
+        };
+        let moved = self.client.put::<MoveCard, ResponseOk>(&body).await;
+        self.cache.remove(&format!("card:{}:{}", board_id, list_id));
+        self.cache
+            .remove(&format!("card:{}:{}", board_id, destination_id));
+        if moved.is_ok() {
+            let change = Change::Move {
+                card,
+                board_id,
+                list_id: destination_id,
+            };
+            self.record(&summary, change).await;
+        }
+        to_value(moved)
+    }
+
+    async fn card_remove(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, list_id, card_id) = self.card_id(params).await?;
+        CardApi::set_base(&mut self.client, &board_id, &list_id);
+        let summary = format!("rm card '{}'", required(&params.card, "card")?);
+        let change = journal::removal(
+            &self.client.config,
+            AType::Card,
+            &board_id,
+            &list_id,
+            &card_id,
+        )
+        .await
+        .map_err(RpcError::from)?;
+        let removed = self.client.delete::<ResponseOk>(&card_id).await;
+        self.cache.remove(&format!("card:{}:{}", board_id, list_id));
+        if removed.is_ok() {
+            self.record(&summary, change).await;
+        }
+        to_value(removed)
+    }
+
+    async fn checklist_ls(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, _list_id, card_id) = self.card_id(params).await?;
+        Ok(json!(self.checklists(&board_id, &card_id).await?))
+    }
+
+    async fn checklist_details(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, _list_id, card_id) = self.card_id(params).await?;
+        let name = required(&params.checklist, "checklist")?;
+        let checklists = self.checklists(&board_id, &card_id).await?;
+        let checklist_id = find(&checklists, name, "checklist")?;
+        ChecklistApi::set_base(&mut self.client, &board_id, &card_id);
+        to_value(self.client.get_one::<ChDetails>(&checklist_id).await)
+    }
+
+    async fn checklist_create(&mut self, params: &Params) -> Result<Value, RpcError> {
+        let (board_id, _list_id, card_id) = self.card_id(params).await?;
+        let title = required(&params.title, "title")?;
+        ChecklistApi::set_base(&mut self.client, &board_id, &card_id);
+        let body = CreateChecklist {
+            _id: String::new(),
+            title: title.to_string(),
+            items: params.items.to_owned(),
+        };
+        let created = self
+            .client
+            .create::<CreateChecklist, ResponseOk>(&body)
+            .await;
+        self.cache
+            .remove(&format!("checklist:{}:{}", board_id, card_id));
+        to_value(created)
+    }
+
+    async fn board_id(&mut self, params: &Params) -> Result<String, RpcError> {
+        let name = required(&params.board, "board")?;
+        let boards = self.children(AType::Board, "board").await?;
+        find(&boards, name, "board")
+    }
+
+    async fn list_id(&mut self, params: &Params) -> Result<(String, String), RpcError> {
+        let board_id = self.board_id(params).await?;
+        let name = required(&params.list, "list")?;
+        let lists = self.lists(&board_id).await?;
+        let list_id = find(&lists, name, "list")?;
+        Ok((board_id, list_id))
+    }
+
+    async fn card_id(&mut self, params: &Params) -> Result<(String, String, String), RpcError> {
+        let (board_id, list_id) = self.list_id(params).await?;
+        let name = required(&params.card, "card")?;
+        let cards = self.cards(&board_id, &list_id).await?;
+        let card_id = find(&cards, name, "card")?;
+        Ok((board_id, list_id, card_id))
+    }
+
+    async fn lists(&mut self, board_id: &str) -> Result<Vec<Artifact>, RpcError> {
+        ListApi::set_base(&mut self.client, board_id);
+        self.children(AType::List, &format!("list:{}", board_id))
+            .await
+    }
+
+    async fn swimlanes(&mut self, board_id: &str) -> Result<Vec<Artifact>, RpcError> {
+        SwimlaneApi::set_base(&mut self.client, board_id);
+        self.children(AType::Swimlane, &format!("swimlane:{}", board_id))
+            .await
+    }
+
+    async fn cards(&mut self, board_id: &str, list_id: &str) -> Result<Vec<Artifact>, RpcError> {
+        CardApi::set_base(&mut self.client, board_id, list_id);
+        self.children(AType::Card, &format!("card:{}:{}", board_id, list_id))
+            .await
+    }
+
+    async fn checklists(
+        &mut self,
+        board_id: &str,
+        card_id: &str,
+    ) -> Result<Vec<Artifact>, RpcError> {
+        ChecklistApi::set_base(&mut self.client, board_id, card_id);
+        self.children(
+            AType::Checklist,
+            &format!("checklist:{}:{}", board_id, card_id),
+        )
+        .await
+    }
+
+    /// The base of the client has to point to the requested children already.
+    /// The client needs to be based on the list of the card.
+    async fn card_state(&mut self, card_id: &str) -> Result<CardState, RpcError> {
+        match self.client.get_one::<CDetails>(card_id).await {
+            Ok(d) => Ok(CardState::new(card_id, &d)),
+            Err(e) => Err(RpcError::from(e)),
+        }
+    }
+
+    async fn record(&self, summary: &str, change: Change) {
+        journal::record(&self.client.config, summary, change).await
+    }
+
+    async fn children(&mut self, t: AType, key: &str) -> Result<Vec<Artifact>, RpcError> {
+        if let Some(c) = self.cache.get(key) {
+            trace!("cache hit {}", key);
+            return Ok(c.to_owned());
+        }
+        if t == AType::Board {
+            let base = format!("users/{}/boards", self.user_id);
+            BoardApi::set_base(&mut self.client, &base);
+        }
+        match self.client.get_all(t).await {
+            Ok(children) => {
+                self.cache.insert(key.to_string(), children.to_owned());
+                Ok(children)
+            }
+            Err(e) => Err(RpcError::from(e)),
+        }
+    }
+}
+
+fn required<'a>(value: &'a Option<String>, name: &str) -> Result<&'a str, RpcError> {
+    match value {
+        Some(v) => Ok(v),
+        None => Err(RpcError::new(
+            INVALID_PARAMS,
+            &format!("Parameter '{}' is missing", name),
+        )),
+    }
+}
+
+fn find(artifacts: &[Artifact], name: &str, kind: &str) -> Result<String, RpcError> {
+    match artifacts.iter().find(|a| a.title == name || a._id == name) {
+        Some(a) => Ok(a._id.to_owned()),
+        None => Err(RpcError::new(
+            NOT_FOUND,
+            &format!("{} '{}' not found", kind, name),
+        )),
+    }
+}
+
+fn to_value<T: Serialize>(result: Result<T, CoreError>) -> Result<Value, RpcError> {
+    match result {
+        Ok(r) => serde_json::to_value(r).map_err(|e| RpcError::new(REQUEST_FAILED, &e.to_string())),
+        Err(e) => Err(RpcError::from(e)),
+    }
+}
+
+pub async fn run(args: &Args, client: Client, user_id: &str) -> Result<WekanResult, Error> {
+    if !args.stdio {
+        return CliError::new_msg("Only '--stdio' is supported at the moment").err();
+    }
+    let mut server = Server::new(client, user_id);
+    server.run_stdio().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+
+    async fn call(server: &mut Server, request: &str) -> Value {
+        serde_json::from_str(&server.handle(request).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn list_boards_and_cache() {
+        let mut server = Server::new(Client::mock(), "123");
+        let res = call(
+            &mut server,
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "board.ls"}"#,
+        )
+        .await;
+        assert_eq!(res["id"], 1);
+        assert_eq!(res["result"][1]["title"], "fake-board-title-2");
+        assert!(server.cache.contains_key("board"));
+    }
+
+    #[tokio::test]
+    async fn move_card_by_title() {
+        let mut server = Server::new(Client::mock(), "123");
+        let res = call(
+            &mut server,
+            r#"{"jsonrpc": "2.0", "id": "m", "method": "card.move", "params": {
+                "board": "fake-board-title-1", "list": "fake-list-title-1",
+                "card": "fake-card-title-2", "to": "fake-list-title-2"}}"#,
+        )
+        .await;
+        assert_eq!(res["id"], "m");
+        assert_eq!(res["result"]["_id"], "fake-card-id-2");
+        assert!(!server
+            .cache
+            .contains_key("card:fake-board-id-1:fake-list-id-1"));
+    }
+
+    #[tokio::test]
+    async fn report_errors() {
+        let mut server = Server::new(Client::mock(), "123");
+        let res = call(&mut server, "{").await;
+        assert_eq!(res["error"]["code"], PARSE_ERROR);
+        let res = call(
+            &mut server,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "nope"}"#,
+        )
+        .await;
+        assert_eq!(res["error"]["code"], METHOD_NOT_FOUND);
+        let res = call(
+            &mut server,
+            r#"{"jsonrpc": "2.0", "id": 3, "method": "card.ls", "params": {"board": "missing"}}"#,
+        )
+        .await;
+        assert_eq!(res["error"]["code"], NOT_FOUND);
+        assert!(server
+            .handle(r#"{"jsonrpc": "2.0", "method": "shutdown"}"#)
+            .await
+            .is_none());
+        assert!(!server.running);
+    }
+
+    #[test]
+    fn map_errors() {
+        let e = RpcError::from(CliError::new_msg("Failed to remove card").as_enum());
+        assert_eq!(e, RpcError::new(REQUEST_FAILED, "Failed to remove card"));
+        let e = RpcError::from(Error::Io(std::io::Error::from(
+            std::io::ErrorKind::NotFound,
+        )));
+        assert_eq!(e.code, LOCAL_FAILED);
+        assert!(!e.message.contains("NotFound"));
+        let e = RpcError::from(CoreError::DryRun(wekan_core::error::DryRun {
+            method: String::from("DELETE"),
+            url: String::from("http://localhost/api/cards/1"),
+            body: None,
+        }));
+        assert_eq!(
+            e,
+            RpcError::new(DRY_RUN, "Dry run: DELETE http://localhost/api/cards/1")
+        );
+    }
+}
@@ -0,0 +1,557 @@
+use crate::{
+    command::{Args as RArgs, Subcommand, WekanParser},
+    config::alias::expand_words,
+    display::CliDisplay,
+    error::{CliError, Error, Transform},
+    resolver::Query,
+    result::WekanResult,
+    runner::Runner,
+};
+use clap::{Args as ClapArgs, ErrorKind, Parser};
+use log::{info, trace};
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    validate::Validator, Context as LineContext, Editor, Helper,
+};
+use std::{future::Future, pin::Pin};
+use wekan_common::artifact::common::{AType, Artifact};
+use wekan_core::{client::LoginClient, persistence::config::Butler};
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Start an interactive shell",
+    long_about = "Change into boards, lists and cards with 'cd' and run commands relative to them"
+)]
+pub struct Args {}
+
+const HELP: &str = "cd NAME | .. | /   change into a board, list or card
+pwd                  show the current location
+ls                   list the children of the current location
+create TITLE         create a child of the current location
+details, update, mv, archive, rm, ...
+                     run the command on the current location
+board, list, card, checklist, table, ...
+                     run any other wekan-cli command as is
+exit                 leave the shell";
+
+/// Commands which address the children of the current location, all others address the location itself.
+const CHILD_COMMANDS: [&str; 2] = ["ls", "create"];
+const COMMANDS: [&str; 14] = [
+    "cd", "pwd", "help", "exit", "ls", "create", "details", "update", "mv", "archive", "rm",
+    "inspect", "export", "table",
+];
+/// Commands which are handed to the parser without the location.
+const PASSTHROUGH: [&str; 9] = [
+    "board",
+    "list",
+    "card",
+    "checklist",
+    "table",
+    "get",
+    "describe",
+    "apply",
+    "config",
+];
+
+/// The location of the shell as stack of resolved artifacts: board, list and card.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub stack: Vec<Artifact>,
+}
+
+impl Context {
+    pub fn path(&self) -> String {
+        String::from("/")
+            + &self
+                .stack
+                .iter()
+                .map(|a| a.title.to_owned())
+                .collect::<Vec<String>>()
+                .join("/")
+    }
+
+    pub fn child_type(&self) -> Option<AType> {
+        match self.stack.len() {
+            0 => Some(AType::Board),
+            1 => Some(AType::List),
+            2 => Some(AType::Card),
+            _ => None,
+        }
+    }
+
+    fn id(&self, index: usize) -> Option<&str> {
+        self.stack.get(index).map(|a| a._id.as_str())
+    }
+
+    /// Prefixes the words with the artifact command and the name options of the location.
+    pub fn expand(&self, words: &[String]) -> Vec<String> {
+        let first = match words.first() {
+            Some(f) => f.as_str(),
+            None => return Vec::new(),
+        };
+        if PASSTHROUGH.contains(&first) {
+            if first == "table" && words.len() == 1 && !self.stack.is_empty() {
+                return vec![first.to_string(), self.stack[0].title.to_owned()];
+            }
+            return words.to_vec();
+        }
+        let titles = self
+            .stack
+            .iter()
+            .map(|a| a.title.as_str())
+            .collect::<Vec<&str>>();
+        let prefix = match (CHILD_COMMANDS.contains(&first), titles.as_slice()) {
+            (_, []) => vec!["board"],
+            (true, [b]) => vec!["list", "-b", b],
+            (false, [b]) => vec!["board", "-n", b],
+            (true, [b, l]) => vec!["card", "-b", b, "-l", l],
+            (false, [b, l]) => vec!["list", "-b", b, "-n", l],
+            (true, [b, l, c, ..]) => vec!["checklist", "-b", b, "-l", l, "-c", c],
+            (false, [b, l, c, ..]) => vec!["card", "-b", b, "-l", l, "-n", c],
+        };
+        let mut argv = prefix
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<String>>();
+        argv.extend(words.iter().cloned());
+        argv
+    }
+}
+
+/// Completes commands and the titles of the children of the current location.
+#[derive(Debug, Default)]
+pub struct ShellHelper {
+    pub names: Vec<String>,
+    pub lists: Vec<String>,
+}
+
+impl ShellHelper {
+    pub fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let line = &line[..pos];
+        let start = if line.matches('"').count() % 2 == 1 {
+            line.rfind('"').unwrap_or(0)
+        } else {
+            line.rfind(' ').map(|i| i + 1).unwrap_or(0)
+        };
+        let prefix = line[start..].trim_start_matches('"');
+        let pool: Vec<&str> = if line[..start].trim().is_empty() {
+            COMMANDS.to_vec()
+        } else if line.starts_with("mv ") {
+            self.lists.iter().map(|l| l.as_str()).collect()
+        } else {
+            self.names.iter().map(|n| n.as_str()).collect()
+        };
+        let found = pool
+            .iter()
+            .filter(|c| c.starts_with(prefix))
+            .map(|c| {
+                if c.contains(|ch: char| ch.is_whitespace() || ch == '"' || ch == '\'') {
+                    shell_words::quote(c).to_string()
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect();
+        (start, found)
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &LineContext<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+impl Highlighter for ShellHelper {}
+impl Validator for ShellHelper {}
+impl Helper for ShellHelper {}
+
+pub struct Shell<'a> {
+    pub client: LoginClient,
+    pub global_options: &'a RArgs,
+    pub context: Context,
+    pub running: bool,
+    stale: bool,
+}
+
+impl<'a> Shell<'a> {
+    pub fn new(client: LoginClient, global_options: &'a RArgs) -> Self {
+        Self {
+            client,
+            global_options,
+            context: Context::default(),
+            running: true,
+            stale: true,
+        }
+    }
+
+    pub async fn run(&mut self) -> Result<WekanResult, Error> {
+        info!("shell");
+        let mut editor = match Editor::<ShellHelper>::new() {
+            Ok(e) => e,
+            Err(e) => return CliError::new_msg(&format!("Shell not available: {}", e)).err(),
+        };
+        editor.set_helper(Some(ShellHelper::default()));
+        let history = self.client.config.get_path() + "shell_history";
+        if editor.load_history(&history).is_err() {
+            trace!("No shell history found");
+        }
+        while self.running {
+            if self.stale {
+                let (names, lists) = self.completions().await;
+                if let Some(h) = editor.helper_mut() {
+                    h.names = names;
+                    h.lists = lists;
+                }
+                self.stale = false;
+            }
+            match editor.readline(&format!("wekan:{}> ", self.context.path())) {
+                Ok(line) => {
+                    editor.add_history_entry(line.as_str());
+                    match self.handle(&line).await {
+                        Ok(r) => self.print_result(r),
+                        Err(e) => print_error(e),
+                    }
+                }
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(e) => return CliError::new_msg(&e.to_string()).err(),
+            }
+        }
+        if editor.save_history(&history).is_err() {
+            trace!("Shell history not saved");
+        }
+        WekanResult::new_msg("Bye").ok()
+    }
+
+    pub async fn handle(&mut self, line: &str) -> Result<WekanResult, Error> {
+        trace!("{}", line);
+        let words = match shell_words::split(line) {
+            Ok(w) => w,
+            Err(_e) => return CliError::new_msg("Missing closing quote").err(),
+        };
+        match words.first().map(|w| w.as_str()) {
+            None => WekanResult::new_msg("").ok(),
+            Some("exit") | Some("quit") => {
+                self.running = false;
+                WekanResult::new_msg("").ok()
+            }
+            Some("help") => WekanResult::new_msg(HELP).ok(),
+            Some("pwd") => WekanResult::new_msg(&self.context.path()).ok(),
+            Some("cd") => {
+                let target = words.get(1).map(|t| t.as_str()).unwrap_or("/");
+                self.cd(target).await
+            }
+            Some(_) => self.delegate(&words).await,
+        }
+    }
+
+    pub async fn cd(&mut self, target: &str) -> Result<WekanResult, Error> {
+        match target {
+            "/" => self.context.stack.clear(),
+            ".." => {
+                self.context.stack.pop();
+            }
+            name => {
+                let child_type = match self.context.child_type() {
+                    Some(t) => t,
+                    None => {
+                        return CliError::new_msg("A card has no children to change into").err()
+                    }
+                };
+                let mut child = self.find_child(name, false).await?;
+                if child.is_none() {
+                    child = self.find_child(name, true).await?;
+                }
+                match child {
+                    Some(mut c) => {
+                        c.r#type = child_type;
+                        self.context.stack.push(c);
+                    }
+                    None => {
+                        return CliError::new_msg(&format!(
+                            "'{}' not found in {}",
+                            name,
+                            self.context.path()
+                        ))
+                        .err()
+                    }
+                }
+            }
+        };
+        self.stale = true;
+        WekanResult::new_msg(&self.context.path()).ok()
+    }
+
+    async fn find_child(&self, name: &str, fresh: bool) -> Result<Option<Artifact>, Error> {
+        Ok(self
+            .children(fresh)
+            .await?
+            .into_iter()
+            .find(|a| a.title == name))
+    }
+
+    async fn children(&self, fresh: bool) -> Result<Vec<Artifact>, Error> {
+        match self.context.child_type() {
+            Some(t) => {
+                self.query()
+                    .inquire(t, self.context.id(0), self.context.id(1), None, fresh)
+                    .await
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn completions(&self) -> (Vec<String>, Vec<String>) {
+        let titles = |artifacts: Result<Vec<Artifact>, Error>| match artifacts {
+            Ok(a) => a.into_iter().map(|a| a.title).collect(),
+            Err(_e) => Vec::new(),
+        };
+        let names = titles(self.children(false).await);
+        let lists = match self.context.id(0) {
+            Some(b) => titles(
+                self.query()
+                    .inquire(AType::List, Some(b), None, None, false)
+                    .await,
+            ),
+            None => Vec::new(),
+        };
+        (names, lists)
+    }
+
+    fn query(&self) -> Query<'static> {
+        Query::new(
+            self.client.config.clone(),
+            "",
+            self.global_options.deny_store(),
+        )
+    }
+
+    async fn delegate(&mut self, words: &[String]) -> Result<WekanResult, Error> {
+        let mut argv = vec![String::from("wekan-cli")];
+        argv.extend(global_argv(self.global_options));
+        argv.extend(
+            self.context
+                .expand(&expand_words(words.to_vec(), &self.client.config)),
+        );
+        trace!("{:?}", argv);
+        let parser = match WekanParser::try_parse_from(argv) {
+            Ok(p) => p,
+            Err(e) => match e.kind() {
+                ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => {
+                    return WekanResult::new_msg(&e.to_string()).ok()
+                }
+                _ => return CliError::new_msg(e.to_string().trim()).err(),
+            },
+        };
+        match parser.command {
+            Subcommand::Shell(_) | Subcommand::Serve(_) | Subcommand::Tui(_) => {
+                return CliError::new_msg("Not available inside the shell").err()
+            }
+            _ => {}
+        };
+        let mut runner = Runner {
+            client: self.client.clone(),
+            format: match parser.delegate.output_format {
+                Some(ref f) => f.to_owned(),
+                None => "terminal".to_string(),
+            },
+            display: CliDisplay::new(Vec::new()),
+            subcommands: parser.command,
+            global_options: parser.delegate,
+            #[cfg(feature = "store")]
+            workspace: Vec::new(),
+        };
+        // The runner runs the shell itself, the indirection breaks the recursive future type.
+        let future: Pin<Box<dyn Future<Output = Result<WekanResult, Error>> + '_>> =
+            Box::pin(runner.run());
+        let result = future.await?;
+        if !result.is_unchanged() {
+            self.follow(words).await?;
+        }
+        self.stale = true;
+        Ok(result)
+    }
+
+    /// Keeps the location in sync after the current artifact got moved, renamed or removed.
+    async fn follow(&mut self, words: &[String]) -> Result<(), Error> {
+        let first = words[0].as_str();
+        if self.context.stack.is_empty()
+            || CHILD_COMMANDS.contains(&first)
+            || PASSTHROUGH.contains(&first)
+        {
+            return Ok(());
+        }
+        match first {
+            "mv" if self.context.stack.len() == 3 => {
+                if let Some(list) = words.get(1) {
+                    let board_id = self.context.stack[0]._id.to_owned();
+                    let list_id = self.query().find_list_id(&board_id, list).await?;
+                    self.context.stack[1] = Artifact {
+                        _id: list_id,
+                        title: list.to_owned(),
+                        r#type: AType::List,
+                    };
+                }
+            }
+            "archive" | "rm" => {
+                self.context.stack.pop();
+            }
+            "update" => {
+                if let Some(i) = words.iter().position(|w| w == "-t" || w == "--title") {
+                    if let (Some(title), Some(current)) =
+                        (words.get(i + 1), self.context.stack.last_mut())
+                    {
+                        current.title = title.to_owned();
+                    }
+                }
+            }
+            _ => {}
+        };
+        Ok(())
+    }
+
+    fn print_result(&self, result: WekanResult) {
+        let msg = result.get_msg();
+        if !msg.trim().is_empty() {
+            println!("{}", msg.trim());
+        }
+        if !self.global_options.no_recommendations {
+            if let Some(w) = result.get_next_workflow() {
+                println!("Recommended workflow: {}", w.trim());
+            }
+        }
+    }
+}
+
+pub fn print_error(e: Error) {
+    match e {
+        Error::Cli(c) => eprintln!("{}", c.message),
+        Error::Input(i) => println!("{}", i.message),
+        e => eprintln!("{:?}", e),
+    }
+}
+
+/// Global options of the shell are handed to every command.
+pub fn global_argv(global_options: &RArgs) -> Vec<String> {
+    let mut argv = Vec::new();
+    if global_options.no_recommendations {
+        argv.push(String::from("-r"));
+    }
+    #[cfg(feature = "store")]
+    if global_options.no_store {
+        argv.push(String::from("-d"));
+    }
+    if let Some(o) = &global_options.output_format {
+        argv.push(String::from("-o"));
+        argv.push(o.to_owned());
+    }
+    if let Some(f) = &global_options.filter {
+        argv.push(String::from("-f"));
+        argv.push(f.to_owned());
+    }
+    if global_options.dry_run {
+        argv.push(String::from("--dry-run"));
+    }
+    argv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+    use wekan_common::artifact::tests::MockDetails;
+    use wekan_core::config::UserConfig;
+
+    fn context() -> Context {
+        Context {
+            stack: vec![
+                Artifact::mock("b-id", "my board", "board"),
+                Artifact::mock("l-id", "Todo", "list"),
+                Artifact::mock("c-id", "my card", "card"),
+            ],
+        }
+    }
+
+    fn words(w: &[&str]) -> Vec<String> {
+        w.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn expand_relative_commands() {
+        let mut context = context();
+        assert_eq!(context.path(), "/my board/Todo/my card");
+        assert_eq!(
+            context.expand(&words(&["mv", "Done"])),
+            words(&["card", "-b", "my board", "-l", "Todo", "-n", "my card", "mv", "Done"])
+        );
+        assert_eq!(
+            context.expand(&words(&["ls"])),
+            words(&[
+                "checklist",
+                "-b",
+                "my board",
+                "-l",
+                "Todo",
+                "-c",
+                "my card",
+                "ls"
+            ])
+        );
+        context.stack.pop();
+        assert_eq!(
+            context.expand(&words(&["create", "new card"])),
+            words(&["card", "-b", "my board", "-l", "Todo", "create", "new card"])
+        );
+        context.stack.pop();
+        assert_eq!(
+            context.expand(&words(&["details"])),
+            words(&["board", "-n", "my board", "details"])
+        );
+        assert_eq!(
+            context.expand(&words(&["table"])),
+            words(&["table", "my board"])
+        );
+        context.stack.pop();
+        assert_eq!(context.path(), "/");
+        assert_eq!(context.expand(&words(&["ls"])), words(&["board", "ls"]));
+    }
+
+    #[test]
+    fn complete_commands_and_titles() {
+        let helper = ShellHelper {
+            names: words(&["my board", "other"]),
+            lists: words(&["Todo", "Done"]),
+        };
+        assert_eq!(helper.candidates("de", 2), (0, words(&["details"])));
+        assert_eq!(helper.candidates("cd m", 4), (3, words(&["'my board'"])));
+        assert_eq!(
+            helper.candidates("cd \"my b", 8),
+            (3, words(&["'my board'"]))
+        );
+        assert_eq!(helper.candidates("mv D", 4), (3, words(&["Done"])));
+    }
+
+    #[tokio::test]
+    async fn change_location() {
+        let global_options = RArgs::mock();
+        let mut shell = Shell::new(LoginClient::new(UserConfig::mock()), &global_options);
+        let res = shell.handle("cd fake-board-title-1").await.unwrap();
+        assert_eq!(res.get_msg(), "/fake-board-title-1");
+        assert_eq!(shell.context.stack[0]._id, "fake-board-id-1");
+        assert!(shell.handle("cd missing").await.is_err());
+        shell.handle("cd ..").await.unwrap();
+        assert_eq!(shell.handle("pwd").await.unwrap().get_msg(), "/");
+        shell.handle("exit").await.unwrap();
+        assert!(!shell.running);
+    }
+}
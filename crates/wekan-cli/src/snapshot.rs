@@ -0,0 +1,867 @@
+use crate::{
+    card::record::{label_ids, label_names, Record},
+    error::{self, CliError, Error, Transform},
+    resolver::Query,
+    result::WekanResult,
+};
+use log::{info, trace};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use wekan_common::{
+    artifact::{
+        board::{Details as BDetails, Label},
+        card::Details as CDetails,
+        checklist::Details as ChDetails,
+        common::{AType, Artifact, Base, IdReturner, MostDetails, SortedArtifact},
+    },
+    http::{
+        artifact::{CreateArtifact, ResponseOk},
+        board::{CreateBoard, CreateLabel, CreatedBoard, CreatedLabel, NewLabel},
+        card::{CreateCard, UpdateCard},
+        checklist::{CreateChecklist, UpdateItem},
+    },
+};
+use wekan_core::{
+    client::{BoardApi, CardApi, ChecklistApi, ChecklistItemsApi, Client, ListApi, SwimlaneApi},
+    config::UserConfig,
+    error::Error as CoreError,
+};
+
+#[cfg(test)]
+use crate::tests::mocks::{Artifacts, Operation};
+#[cfg(not(test))]
+use wekan_core::http::operation::{Artifacts, Operation};
+
+/// Board snapshot with all children referenced by their original ids.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Snapshot {
+    pub title: String,
+    #[serde(default)]
+    pub lists: Vec<Entry>,
+    #[serde(default)]
+    pub swimlanes: Vec<Entry>,
+    #[serde(default)]
+    pub cards: Vec<CardEntry>,
+    #[serde(default)]
+    pub checklists: Vec<ChecklistEntry>,
+    /// Labels of the board which the cards refer to by name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<LabelEntry>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Entry {
+    pub _id: String,
+    pub title: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CardEntry {
+    pub _id: String,
+    pub title: String,
+    pub list_id: String,
+    pub swimlane_id: Option<String>,
+    pub description: Option<String>,
+    pub due_at: Option<String>,
+    pub end_at: Option<String>,
+    pub sort: Option<f32>,
+    #[serde(default)]
+    pub labels: Option<Vec<String>>,
+    /// Wekan user ids.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LabelEntry {
+    #[serde(default)]
+    pub name: String,
+    pub color: String,
+}
+
+impl LabelEntry {
+    /// Cards refer to unnamed labels by color.
+    pub fn display_name(&self) -> String {
+        if self.name.is_empty() {
+            self.color.to_owned()
+        } else {
+            self.name.to_owned()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ChecklistEntry {
+    pub _id: String,
+    pub card_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub items: Vec<String>,
+    /// Titles of the finished items.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub finished: Vec<String>,
+}
+
+impl Snapshot {
+    pub async fn read(path: &Path) -> Result<Self, Error> {
+        let content = tokio::fs::read(path).await?;
+        Ok(serde_yaml::from_slice::<Snapshot>(&content)?)
+    }
+
+    /// Reads the given lists of a live board with their cards and checklists.
+    /// With a card id only this card of the first list is read. Labels are kept by name
+    /// and the used ones are added to the snapshot.
+    pub async fn collect(
+        query: &Query<'_>,
+        board_id: &str,
+        title: &str,
+        lists: Vec<Artifact>,
+        card_id: Option<&str>,
+    ) -> Result<Self, Error> {
+        info!("collect_snapshot");
+        let mut board_client = <Client as BoardApi>::new(query.config.clone());
+        let labels = board_client
+            .get_one::<BDetails>(board_id)
+            .await?
+            .get_labels();
+        let swimlanes = query
+            .inquire(AType::Swimlane, Some(board_id), None, None, true)
+            .await?;
+        let mut snapshot = Snapshot {
+            title: title.to_string(),
+            lists: lists.iter().map(Entry::from).collect(),
+            swimlanes: swimlanes.iter().map(Entry::from).collect(),
+            ..Default::default()
+        };
+        let mut used_labels = Vec::new();
+        for list in &lists {
+            let card_ids = match card_id {
+                Some(id) => vec![id.to_string()],
+                None => query
+                    .inquire(AType::Card, Some(board_id), Some(&list._id), None, true)
+                    .await?
+                    .into_iter()
+                    .map(|c| c._id)
+                    .collect(),
+            };
+            let mut client = <Client as CardApi>::new(query.config.clone(), board_id, &list._id);
+            for id in card_ids {
+                let details = client.get_one::<CDetails>(&id).await?;
+                let swimlane_id = details.get_swimlane_id();
+                let names = label_names(&details.get_label_ids(), &labels);
+                used_labels.extend(details.get_label_ids());
+                snapshot.cards.push(CardEntry {
+                    _id: id.to_owned(),
+                    title: details.get_title(),
+                    list_id: list._id.to_owned(),
+                    swimlane_id: if swimlanes.iter().any(|s| s._id == swimlane_id) {
+                        Some(swimlane_id)
+                    } else {
+                        None
+                    },
+                    description: Record::get_optional(&details.get_description()),
+                    due_at: Record::get_optional(&details.get_due_at()),
+                    end_at: Record::get_optional(&details.get_end_at()),
+                    sort: Some(*details.get_sort()),
+                    labels: if names.is_empty() { None } else { Some(names) },
+                    members: None,
+                });
+                let mut checklist_client =
+                    <Client as ChecklistApi>::new(query.config.clone(), board_id, &id);
+                for c in checklist_client.get_all(AType::Checklist).await? {
+                    let checklist = checklist_client.get_one::<ChDetails>(&c._id).await?;
+                    let items = checklist.get_items();
+                    snapshot.checklists.push(ChecklistEntry {
+                        _id: c._id,
+                        card_id: id.to_owned(),
+                        title: checklist.title.to_owned(),
+                        items: items.iter().map(|i| i.title.to_owned()).collect(),
+                        finished: items
+                            .into_iter()
+                            .filter(|i| i.is_finished)
+                            .map(|i| i.title)
+                            .collect(),
+                    });
+                }
+            }
+            if card_id.is_some() {
+                break;
+            }
+        }
+        snapshot.labels = labels
+            .iter()
+            .filter(|l| used_labels.contains(&l.get_id()))
+            .map(|l| LabelEntry {
+                name: l.get_name(),
+                color: l.get_color(),
+            })
+            .collect();
+        Ok(snapshot)
+    }
+
+    /// Counts what is removed along with an artifact of the type, e.g. '2 lists, 5 cards'.
+    pub fn children(&self, r#type: &AType) -> String {
+        let items = self.checklists.iter().map(|c| c.items.len()).sum();
+        let counts = match r#type {
+            AType::Board => vec![
+                (self.lists.len(), "list"),
+                (self.cards.len(), "card"),
+                (self.checklists.len(), "checklist"),
+            ],
+            AType::List => vec![
+                (self.cards.len(), "card"),
+                (self.checklists.len(), "checklist"),
+            ],
+            AType::Card => vec![(self.checklists.len(), "checklist")],
+            AType::Checklist => vec![(items, "item")],
+            _ => Vec::new(),
+        };
+        counts
+            .into_iter()
+            .map(|(n, name)| match n {
+                1 => format!("1 {}", name),
+                n => format!("{} {}s", n, name),
+            })
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+impl From<&Artifact> for Entry {
+    fn from(artifact: &Artifact) -> Self {
+        Self {
+            _id: artifact._id.to_owned(),
+            title: artifact.title.to_owned(),
+        }
+    }
+}
+
+/// Recreates a snapshot on the server and keeps track of old and new ids.
+pub struct Restore {
+    config: UserConfig,
+    author_id: String,
+    ids: HashMap<String, String>,
+    labels: Vec<Label>,
+    missing_labels: Vec<String>,
+    unfinished_items: Vec<String>,
+}
+
+impl Restore {
+    pub fn new(config: UserConfig, author_id: &str) -> Self {
+        Self {
+            config,
+            author_id: author_id.to_string(),
+            ids: HashMap::new(),
+            labels: Vec::new(),
+            missing_labels: Vec::new(),
+            unfinished_items: Vec::new(),
+        }
+    }
+
+    pub async fn run(
+        &mut self,
+        snapshot: &Snapshot,
+        title: Option<String>,
+    ) -> Result<WekanResult, Error> {
+        info!("restore");
+        let title = title.unwrap_or_else(|| snapshot.title.to_owned());
+        self.create(snapshot, &title).await?;
+        if self.config.dry_run {
+            return WekanResult::new_unchanged("Dry run, nothing was sent").ok();
+        }
+        WekanResult::new_workflow(
+            &format!(
+                "Successfully imported board '{}' with {} lists, {} swimlanes, {} cards and {} checklists{}",
+                title,
+                snapshot.lists.len(),
+                snapshot.swimlanes.len(),
+                snapshot.cards.len(),
+                snapshot.checklists.len(),
+                self.left_out()
+            ),
+            "Show the board with the table subcommand",
+        )
+        .ok()
+    }
+
+    /// Creates a new board with everything of the snapshot and returns its id.
+    /// If a child fails the partial board is kept and reported with what is missing.
+    pub async fn create(&mut self, snapshot: &Snapshot, title: &str) -> Result<String, Error> {
+        let (board_id, default_swimlane_id) = self.create_board(title).await?;
+        match self
+            .create_children(snapshot, &board_id, &default_swimlane_id)
+            .await
+        {
+            Ok(()) => Ok(board_id),
+            Err(Error::Cli(c)) => Err(CliError::new_msg(&format!(
+                "{}\nBoard '{}' ({}) was created with {} only, remove it with 'board -n \"{}\" rm'",
+                c.message,
+                title,
+                board_id,
+                self.progress(snapshot, true),
+                title
+            ))
+            .as_enum()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn create_children(
+        &mut self,
+        snapshot: &Snapshot,
+        board_id: &str,
+        default_swimlane_id: &str,
+    ) -> Result<(), Error> {
+        for swimlane in &snapshot.swimlanes {
+            if swimlane.title == "Default" {
+                self.ids
+                    .insert(swimlane._id.to_owned(), default_swimlane_id.to_owned());
+            } else {
+                let mut client = <Client as SwimlaneApi>::new(self.config.clone(), board_id);
+                let id = self.create_artifact(&mut client, &swimlane.title).await?;
+                self.ids.insert(swimlane._id.to_owned(), id);
+            }
+        }
+        for list in &snapshot.lists {
+            let mut client = <Client as ListApi>::new(self.config.clone(), board_id);
+            let id = self.create_artifact(&mut client, &list.title).await?;
+            self.ids.insert(list._id.to_owned(), id);
+        }
+        self.copy_children(snapshot, board_id, default_swimlane_id)
+            .await
+    }
+
+    /// Creates the cards and checklists of a snapshot on an existing board.
+    /// Lists and swimlanes of the snapshot need to be mapped before.
+    pub async fn copy_into(
+        &mut self,
+        snapshot: &Snapshot,
+        board_id: &str,
+        default_swimlane_id: &str,
+    ) -> Result<(), Error> {
+        match self
+            .copy_children(snapshot, board_id, default_swimlane_id)
+            .await
+        {
+            Err(Error::Cli(c)) => Err(CliError::new_msg(&format!(
+                "{}\nOnly {} were copied",
+                c.message,
+                self.progress(snapshot, false)
+            ))
+            .as_enum()),
+            r => r,
+        }
+    }
+
+    async fn copy_children(
+        &mut self,
+        snapshot: &Snapshot,
+        board_id: &str,
+        default_swimlane_id: &str,
+    ) -> Result<(), Error> {
+        if !snapshot.labels.is_empty() || snapshot.cards.iter().any(|c| c.labels.is_some()) {
+            self.create_labels(board_id, snapshot).await?;
+        }
+        for card in &snapshot.cards {
+            let id = self
+                .create_card(board_id, default_swimlane_id, card)
+                .await?;
+            self.ids.insert(card._id.to_owned(), id);
+        }
+        for checklist in &snapshot.checklists {
+            let id = self.create_checklist(board_id, checklist).await?;
+            self.ids.insert(checklist._id.to_owned(), id);
+        }
+        Ok(())
+    }
+
+    /// What of the snapshot has been created, e.g. '2 of 3 cards and 0 of 1 checklists'.
+    fn progress(&self, snapshot: &Snapshot, with_board: bool) -> String {
+        let count = |ids: Vec<&String>| {
+            format!(
+                "{} of {}",
+                ids.iter().filter(|id| self.ids.contains_key(**id)).count(),
+                ids.len()
+            )
+        };
+        let cards = format!(
+            "{} cards and {} checklists",
+            count(snapshot.cards.iter().map(|c| &c._id).collect()),
+            count(snapshot.checklists.iter().map(|c| &c._id).collect())
+        );
+        if with_board {
+            format!(
+                "{} swimlanes, {} lists, {}",
+                count(snapshot.swimlanes.iter().map(|s| &s._id).collect()),
+                count(snapshot.lists.iter().map(|l| &l._id).collect()),
+                cards
+            )
+        } else {
+            cards
+        }
+    }
+
+    /// Creates the labels of the snapshot which the board doesn't have yet. Labels which fail
+    /// to be created are reported as missing.
+    async fn create_labels(&mut self, board_id: &str, snapshot: &Snapshot) -> Result<(), Error> {
+        let mut client = <Client as BoardApi>::new(self.config.clone());
+        if !is_placeholder(board_id) {
+            self.labels = client.get_one::<BDetails>(board_id).await?.get_labels();
+        }
+        for label in &snapshot.labels {
+            let (_ids, missing) = label_ids(&[label.display_name()], &self.labels);
+            if missing.is_empty() {
+                continue;
+            }
+            let body = CreateLabel {
+                board_id: board_id.to_string(),
+                label: NewLabel {
+                    name: label.name.to_owned(),
+                    color: label.color.to_owned(),
+                },
+            };
+            let id = match client.put::<CreateLabel, CreatedLabel>(&body).await {
+                Ok(l) => l.get_id(),
+                Err(CoreError::DryRun(d)) => {
+                    eprintln!("{}", d);
+                    placeholder(&format!("label '{}'", label.display_name()))
+                }
+                Err(e) => {
+                    trace!("{:?}", e);
+                    continue;
+                }
+            };
+            self.labels.push(Label::new(&id, &label.name, &label.color));
+        }
+        Ok(())
+    }
+
+    /// Maps an artifact of the snapshot onto an existing one.
+    pub fn map(&mut self, old_id: &str, new_id: &str) {
+        self.ids.insert(old_id.to_string(), new_id.to_string());
+    }
+
+    /// Maps the swimlanes of the snapshot by title onto an existing board, unknown ones onto
+    /// 'Default' or the first swimlane. Returns the id of the swimlane used as default.
+    pub async fn map_swimlanes(
+        &mut self,
+        query: &Query<'_>,
+        snapshot: &Snapshot,
+        board_id: &str,
+    ) -> Result<String, Error> {
+        let swimlanes = query
+            .inquire(AType::Swimlane, Some(board_id), None, None, true)
+            .await?;
+        let default_id = match swimlanes
+            .iter()
+            .find(|s| s.title == "Default")
+            .or_else(|| swimlanes.first())
+        {
+            Some(s) => s._id.to_owned(),
+            None => return Err(CliError::new_msg("Target board has no swimlane").as_enum()),
+        };
+        for swimlane in &snapshot.swimlanes {
+            let id = match swimlanes.iter().find(|s| s.title == swimlane.title) {
+                Some(s) => s._id.to_owned(),
+                None => default_id.to_owned(),
+            };
+            self.map(&swimlane._id, &id);
+        }
+        Ok(default_id)
+    }
+
+    pub fn report(&self, copied: &str, snapshot: &Snapshot) -> Result<WekanResult, Error> {
+        if self.config.dry_run {
+            return WekanResult::new_unchanged("Dry run, nothing was sent").ok();
+        }
+        WekanResult::new_msg(&format!(
+            "Successfully copied {} with {} cards and {} checklists{}",
+            copied,
+            snapshot.cards.len(),
+            snapshot.checklists.len(),
+            self.left_out()
+        ))
+        .ok()
+    }
+
+    /// One line per kind of detail which could not be restored.
+    fn left_out(&self) -> String {
+        let mut msg = String::new();
+        let missing = self.get_missing_labels();
+        if !missing.is_empty() {
+            msg.push_str(&format!(
+                "\nLabels not available on the target board: {}",
+                missing.join(", ")
+            ));
+        }
+        if !self.unfinished_items.is_empty() {
+            msg.push_str(&format!(
+                "\nChecklist items which could not be finished: {}",
+                self.unfinished_items.join(", ")
+            ));
+        }
+        msg
+    }
+
+    pub fn get_new_id(&self, old_id: &str) -> Option<&String> {
+        self.ids.get(old_id)
+    }
+
+    /// Labels which are not available on the target board and were left out.
+    pub fn get_missing_labels(&self) -> Vec<String> {
+        let mut missing = self.missing_labels.to_owned();
+        missing.sort();
+        missing.dedup();
+        missing
+    }
+
+    async fn create_board(&mut self, title: &str) -> Result<(String, String), Error> {
+        let mut client = <Client as BoardApi>::new(self.config.clone());
+        let body = CreateBoard {
+            _id: String::new(),
+            title: title.to_string(),
+            owner: self.author_id.to_owned(),
+            permission: Some(String::from("private")),
+            color: None,
+            is_admin: None,
+            is_active: None,
+            is_no_comments: None,
+            is_comment_only: None,
+            is_worker: None,
+        };
+        match client.create::<CreateBoard, CreatedBoard>(&body).await {
+            Ok(b) => {
+                trace!("{:?}", b);
+                Ok((b.get_id(), b.get_default_swimlane_id()))
+            }
+            Err(CoreError::DryRun(d)) => {
+                eprintln!("{}", d);
+                Ok((
+                    placeholder(&format!("board '{}'", title)),
+                    placeholder("swimlane 'Default'"),
+                ))
+            }
+            Err(e) => Err(failed("board", e)),
+        }
+    }
+
+    pub async fn create_artifact(
+        &mut self,
+        client: &mut Client,
+        title: &str,
+    ) -> Result<String, Error> {
+        let body = CreateArtifact {
+            _id: String::new(),
+            title: title.to_string(),
+        };
+        let result = client
+            .create::<CreateArtifact, ResponseOk>(&body)
+            .await
+            .map(|o| o.get_id());
+        created(&format!("'{}'", title), result)
+    }
+
+    async fn create_card(
+        &mut self,
+        board_id: &str,
+        default_swimlane_id: &str,
+        card: &CardEntry,
+    ) -> Result<String, Error> {
+        let list_id = self.lookup(&card.list_id)?;
+        let swimlane_id = match &card.swimlane_id {
+            Some(s) => self.lookup(s)?,
+            None => default_swimlane_id.to_string(),
+        };
+        let mut client = <Client as CardApi>::new(self.config.clone(), board_id, &list_id);
+        let body = CreateCard {
+            _id: String::new(),
+            author_id: self.author_id.to_owned(),
+            members: card.members.to_owned(),
+            assignees: None,
+            title: card.title.to_owned(),
+            description: card.description.to_owned().unwrap_or_default(),
+            swimlane_id,
+        };
+        let result = client
+            .create::<CreateCard, ResponseOk>(&body)
+            .await
+            .map(|o| o.get_id());
+        let id = created(&format!("card '{}'", card.title), result)?;
+        let labels = match &card.labels {
+            Some(names) => {
+                let (ids, missing) = label_ids(names, &self.labels);
+                self.missing_labels.extend(missing);
+                if ids.is_empty() {
+                    None
+                } else {
+                    Some(ids)
+                }
+            }
+            None => None,
+        };
+        if card.due_at.is_some() || card.end_at.is_some() || card.sort.is_some() || labels.is_some()
+        {
+            let update_card = UpdateCard {
+                _id: id.to_owned(),
+                title: None,
+                description: None,
+                due_at: card.due_at.to_owned(),
+                end_at: card.end_at.to_owned(),
+                sort: card.sort,
+                labels,
+                assignees: None,
+            };
+            match client.put::<UpdateCard, ResponseOk>(&update_card).await {
+                Ok(_o) => {}
+                Err(CoreError::DryRun(d)) => eprintln!("{}", d),
+                Err(e) => {
+                    // The card exists already, so it counts as created.
+                    self.ids.insert(card._id.to_owned(), id);
+                    trace!("{:?}", e);
+                    return Err(CliError::new_msg(&format!(
+                        "Failed to set the dates, sort or labels of card '{}'",
+                        card.title
+                    ))
+                    .as_enum());
+                }
+            }
+        }
+        Ok(id)
+    }
+
+    async fn create_checklist(
+        &mut self,
+        board_id: &str,
+        checklist: &ChecklistEntry,
+    ) -> Result<String, Error> {
+        let card_id = self.lookup(&checklist.card_id)?;
+        let mut client = <Client as ChecklistApi>::new(self.config.clone(), board_id, &card_id);
+        let body = CreateChecklist {
+            _id: String::new(),
+            title: checklist.title.to_owned(),
+            items: checklist.items.to_owned(),
+        };
+        let result = client
+            .create::<CreateChecklist, ResponseOk>(&body)
+            .await
+            .map(|o| o.get_id());
+        let id = created(&format!("checklist '{}'", checklist.title), result)?;
+        // The items of a checklist which a dry run didn't create are unknown.
+        if !checklist.finished.is_empty() && !is_placeholder(&id) {
+            self.finish_items(&mut client, board_id, &card_id, &id, &checklist.finished)
+                .await?;
+        }
+        Ok(id)
+    }
+
+    /// Wekan creates items unfinished, so the finished ones are checked afterwards.
+    /// Every title checks one item, in the order of the checklist.
+    async fn finish_items(
+        &mut self,
+        client: &mut Client,
+        board_id: &str,
+        card_id: &str,
+        checklist_id: &str,
+        finished: &[String],
+    ) -> Result<(), Error> {
+        let mut titles = finished.to_vec();
+        let items = client.get_one::<ChDetails>(checklist_id).await?.get_items();
+        let mut item_client = <Client as ChecklistItemsApi>::new(
+            self.config.clone(),
+            board_id,
+            card_id,
+            checklist_id,
+        );
+        for item in items {
+            let position = match titles.iter().position(|t| t == &item.title) {
+                Some(p) => p,
+                None => continue,
+            };
+            titles.remove(position);
+            let body = UpdateItem {
+                _id: item._id,
+                is_finished: true,
+            };
+            if let Err(e) = item_client.put::<UpdateItem, ResponseOk>(&body).await {
+                trace!("{:?}", e);
+                self.unfinished_items.push(item.title);
+            }
+        }
+        self.unfinished_items.extend(titles);
+        Ok(())
+    }
+
+    fn lookup(&self, old_id: &str) -> Result<String, Error> {
+        match self.ids.get(old_id) {
+            Some(id) => Ok(id.to_owned()),
+            None => Err(CliError::new_msg(&format!(
+                "Snapshot references unknown artifact '{}'",
+                old_id
+            ))
+            .as_enum()),
+        }
+    }
+}
+
+/// Errors are named by what failed.
+fn failed(what: &str, e: CoreError) -> Error {
+    error::failed(&format!("Failed to create {}", what), e)
+}
+
+/// A dry run prints the request and goes on with a placeholder for the id it would return,
+/// so the requests for the children can be printed as well.
+fn created(what: &str, result: Result<String, CoreError>) -> Result<String, Error> {
+    match result {
+        Ok(id) => Ok(id),
+        Err(CoreError::DryRun(d)) => {
+            eprintln!("{}", d);
+            Ok(placeholder(what))
+        }
+        Err(e) => Err(failed(what, e)),
+    }
+}
+
+fn placeholder(what: &str) -> String {
+    format!("<new {}>", what)
+}
+
+fn is_placeholder(id: &str) -> bool {
+    id.starts_with("<new ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+
+    fn snapshot() -> Snapshot {
+        serde_yaml::from_str(concat!(
+            "title: Client board\n",
+            "lists:\n",
+            "  - _id: old-list-id\n",
+            "    title: Todo\n",
+            "swimlanes:\n",
+            "  - _id: old-swimlane-id\n",
+            "    title: Default\n",
+            "cards:\n",
+            "  - _id: old-card-id\n",
+            "    title: Kick-off\n",
+            "    list_id: old-list-id\n",
+            "    swimlane_id: old-swimlane-id\n",
+            "    due_at: 2022-10-12\n",
+            "checklists:\n",
+            "  - _id: old-checklist-id\n",
+            "    card_id: old-card-id\n",
+            "    title: Preparation\n",
+            "    items: [Invite, Agenda]\n",
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn count_children() {
+        let snapshot = snapshot();
+        assert_eq!(
+            snapshot.children(&AType::Board),
+            "1 list, 1 card, 1 checklist"
+        );
+        assert_eq!(snapshot.children(&AType::Checklist), "2 items");
+        assert_eq!(snapshot.children(&AType::Swimlane), "");
+    }
+
+    #[tokio::test]
+    async fn restore_snapshot() {
+        let mut restore = Restore::new(UserConfig::mock(), "123");
+        let res = restore
+            .run(&snapshot(), Some(String::from("New client")))
+            .await
+            .unwrap();
+        assert_eq!(
+            res.get_msg(),
+            "Successfully imported board 'New client' with 1 lists, 1 swimlanes, 1 cards and 1 checklists"
+        );
+        assert_eq!(
+            restore.get_new_id("old-swimlane-id"),
+            Some(&String::from("default"))
+        );
+        assert_eq!(
+            restore.get_new_id("old-card-id"),
+            Some(&String::from("fake-ok-id"))
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_snapshot_dry_run() {
+        let mut config = UserConfig::mock();
+        config.dry_run = true;
+        let mut restore = Restore::new(config, "123");
+        let res = restore.run(&snapshot(), None).await.unwrap();
+        assert_eq!(res.get_msg(), "Dry run, nothing was sent");
+        assert_eq!(
+            restore.get_new_id("old-list-id"),
+            Some(&String::from("<new 'Todo'>"))
+        );
+        assert_eq!(
+            restore.get_new_id("old-card-id"),
+            Some(&String::from("<new card 'Kick-off'>"))
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_unknown_reference() {
+        let mut s = snapshot();
+        s.cards[0].list_id = String::from("missing-list-id");
+        let mut restore = Restore::new(UserConfig::mock(), "123");
+        match restore.run(&s, None).await {
+            Err(Error::Cli(c)) => assert!(c.message.ends_with(
+                "was created with 1 of 1 swimlanes, 1 of 1 lists, 0 of 1 cards and 0 of 1 checklists only, remove it with 'board -n \"Client board\" rm'"
+            )),
+            r => panic!("{:?}", r),
+        }
+    }
+
+    #[tokio::test]
+    async fn copy_card() {
+        let query = Query::new(UserConfig::mock(), "", true);
+        let list = Artifact {
+            _id: String::from("fake-list-id-1"),
+            title: String::from("fake-list-title-1"),
+            r#type: AType::List,
+        };
+        let snapshot = Snapshot::collect(
+            &query,
+            "fake-board-id-1",
+            "fake-board-title-1",
+            vec![list],
+            Some("fake-card-id-1"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(snapshot.cards.len(), 1);
+        assert_eq!(snapshot.checklists.len(), 2);
+        assert_eq!(snapshot.checklists[0].card_id, "fake-card-id-1");
+        assert_eq!(snapshot.checklists[0].finished, vec!["fake-item-title-1"]);
+        let mut restore = Restore::new(UserConfig::mock(), "123");
+        restore.map("fake-list-id-1", "fake-list-id-2");
+        let swimlane_id = restore
+            .map_swimlanes(&query, &snapshot, "fake-board-id-2")
+            .await
+            .unwrap();
+        restore
+            .copy_into(&snapshot, "fake-board-id-2", &swimlane_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            restore.get_new_id("fake-card-id-1"),
+            Some(&String::from("fake-ok-id"))
+        );
+        assert_eq!(
+            restore
+                .report("card 'a' to 'b'", &snapshot)
+                .unwrap()
+                .get_msg(),
+            "Successfully copied card 'a' to 'b' with 1 cards and 2 checklists"
+        );
+    }
+}
@@ -43,9 +43,17 @@ pub struct Details {}
 #[derive(ClapArgs, Debug, Clone)]
 #[clap(
     about = "Remove an artifact",
-    long_about = "Remove an artifact. This action can not be reverted"
+    long_about = "Remove an artifact after confirming its path and children. Revert it with 'undo' while the local journal has it"
 )]
-pub struct Remove {}
+pub struct Remove {
+    #[clap(
+        short = 'y',
+        long,
+        parse(from_flag),
+        help = "Remove without confirmation"
+    )]
+    pub yes: bool,
+}
 
 #[derive(ClapArgs, Debug, Clone)]
 #[clap(
@@ -123,8 +131,8 @@ pub struct Apply {
 
 #[derive(ClapArgs, Debug, Clone)]
 #[clap(
-    about = "Archive an artifact but currently not available. See --help",
-    long_about = "Currently the API doesn't support this action. Maybe in the future :)"
+    about = "Archive an artifact",
+    long_about = "Archive an artifact or restore it with '--restore'. Browse archived artifacts with 'archive ls'"
 )]
 pub struct Archive {
     #[clap(
@@ -4,9 +4,15 @@ use std::fmt::Debug;
 use wekan_common::{
     artifact::common::{AType, Artifact},
     artifact::tests::{MockDetails, MockResponse, MockReturn},
-    http::artifact::{Deleted, IdResponse, RequestBody},
+    http::{
+        artifact::{Deleted, IdResponse, RequestBody},
+        board::Export,
+    },
+};
+use wekan_core::{
+    client::Client,
+    error::{DryRun, Error},
 };
-use wekan_core::{client::Client, error::Error};
 
 pub mod mocks {
     use super::*;
@@ -14,6 +20,9 @@ pub mod mocks {
     use wekan_core::config::{MandatoryConfig, UserConfig};
     #[async_trait]
     pub trait Operation {
+        fn is_dry_run(&self) -> bool {
+            false
+        }
         async fn create<
             U: RequestBody,
             T: MockResponse + Send + Debug + DeserializeOwned + 'static,
@@ -21,22 +30,43 @@ pub mod mocks {
             &mut self,
             _body: &U,
         ) -> Result<T, Error> {
+            if self.is_dry_run() {
+                return dry_run("POST", "");
+            }
             Ok(T::mock())
         }
         async fn delete<T: Deleted + MockReturn + IdResponse>(
             &mut self,
             id: &str,
         ) -> Result<T, Error> {
+            if self.is_dry_run() {
+                return dry_run("DELETE", id);
+            }
             Ok(T::success(Some(id.to_string())))
         }
         async fn put<B: RequestBody, T: MockReturn + IdResponse>(
             &mut self,
             body: &B,
         ) -> Result<T, Error> {
+            if self.is_dry_run() {
+                return dry_run("PUT", &body.get_id());
+            }
             Ok(T::success(Some(body.get_id())))
         }
     }
-    impl Operation for Client {}
+    impl Operation for Client {
+        fn is_dry_run(&self) -> bool {
+            self.config.dry_run
+        }
+    }
+
+    fn dry_run<T>(method: &str, id: &str) -> Result<T, Error> {
+        Err(Error::DryRun(DryRun {
+            method: method.to_string(),
+            url: id.to_string(),
+            body: None,
+        }))
+    }
 
     #[async_trait]
     pub trait Artifacts {
@@ -49,6 +79,9 @@ pub mod mocks {
         ) -> Result<T, Error> {
             Ok(T::mock())
         }
+        async fn get_export(&mut self, _board_id: &str) -> Result<Export, Error> {
+            Ok(Export::mock())
+        }
     }
     impl Artifacts for Client {}
 
@@ -0,0 +1,383 @@
+use crate::{
+    command::Args as RArgs,
+    error::{CliError, Error, Transform},
+    resolver::Query,
+    result::WekanResult,
+};
+use chrono::{SecondsFormat, Utc};
+use clap::Args as ClapArgs;
+use futures::future::{select, Either};
+use log::{info, trace};
+use serde::Serialize;
+use std::{collections::BTreeMap, time::Duration};
+use wekan_common::artifact::{
+    card::Details as CDetails,
+    common::{AType, BaseDetails},
+};
+use wekan_core::client::{CardApi, Client};
+
+#[cfg(test)]
+use crate::tests::mocks::Artifacts;
+#[cfg(not(test))]
+use wekan_core::http::operation::Artifacts;
+
+#[derive(ClapArgs, Debug, Clone)]
+#[clap(
+    about = "Watch a board for changes",
+    long_about = "Poll the lists and cards of a board and print created, moved, updated and removed cards"
+)]
+pub struct Args {
+    /// Board name
+    pub name: String,
+    #[clap(
+        short,
+        long,
+        default_value = "30s",
+        parse(try_from_str = parse_duration),
+        help = "Time between two polls, e.g. 30s, 5m or 1h"
+    )]
+    pub interval: Duration,
+    #[clap(long, parse(from_flag), help = "Print one JSON object per line")]
+    pub json: bool,
+}
+
+/// Durations are given as a number with the unit s, m, h or d. Without unit seconds are assumed.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(String::from("Unit needs to be one of s, m, h or d")),
+    };
+    match number.parse::<u64>() {
+        Ok(n) if n > 0 => Ok(Duration::from_secs(n * seconds)),
+        _ => Err(String::from(
+            "Duration needs to be a positive number, e.g. 30s",
+        )),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardState {
+    pub title: String,
+    pub list_id: String,
+    pub list: String,
+    pub modified_at: String,
+    pub date_last_activity: String,
+}
+
+/// Cards of one poll by id.
+pub type State = BTreeMap<String, CardState>;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event {
+    Created {
+        id: String,
+        title: String,
+        list: String,
+    },
+    Moved {
+        id: String,
+        title: String,
+        from: String,
+        to: String,
+    },
+    Updated {
+        id: String,
+        title: String,
+        list: String,
+    },
+    /// Cards which are not listed anymore are archived or deleted.
+    Removed {
+        id: String,
+        title: String,
+        list: String,
+    },
+}
+
+impl Event {
+    pub fn to_text(&self) -> String {
+        match self {
+            Event::Created { title, list, .. } => format!("created '{}' in '{}'", title, list),
+            Event::Moved {
+                title, from, to, ..
+            } => format!("moved '{}' from '{}' to '{}'", title, from, to),
+            Event::Updated { title, list, .. } => format!("updated '{}' in '{}'", title, list),
+            Event::Removed { title, list, .. } => format!("removed '{}' from '{}'", title, list),
+        }
+    }
+
+    pub fn to_json(&self, at: &str) -> Result<String, Error> {
+        let mut value =
+            serde_json::to_value(self).map_err(|e| CliError::new_msg(&e.to_string()).as_enum())?;
+        value["at"] = serde_json::Value::String(at.to_string());
+        Ok(value.to_string())
+    }
+}
+
+/// A card which moved and changed at the same time is only reported as moved.
+pub fn compare(previous: &State, current: &State) -> Vec<Event> {
+    let mut events = Vec::new();
+    for (id, card) in current {
+        match previous.get(id) {
+            None => events.push(Event::Created {
+                id: id.to_owned(),
+                title: card.title.to_owned(),
+                list: card.list.to_owned(),
+            }),
+            Some(p) if p.list_id != card.list_id => events.push(Event::Moved {
+                id: id.to_owned(),
+                title: card.title.to_owned(),
+                from: p.list.to_owned(),
+                to: card.list.to_owned(),
+            }),
+            Some(p)
+                if p.modified_at != card.modified_at
+                    || p.date_last_activity != card.date_last_activity =>
+            {
+                events.push(Event::Updated {
+                    id: id.to_owned(),
+                    title: card.title.to_owned(),
+                    list: card.list.to_owned(),
+                })
+            }
+            Some(_p) => {}
+        }
+    }
+    for (id, card) in previous {
+        if !current.contains_key(id) {
+            events.push(Event::Removed {
+                id: id.to_owned(),
+                title: card.title.to_owned(),
+                list: card.list.to_owned(),
+            });
+        }
+    }
+    events
+}
+
+pub struct Runner<'a> {
+    pub args: Args,
+    pub client: Client,
+    pub global_options: &'a RArgs,
+}
+
+impl<'a> Runner<'a> {
+    pub fn new(args: Args, client: Client, global_options: &'a RArgs) -> Self {
+        Self {
+            args,
+            client,
+            global_options,
+        }
+    }
+
+    /// Prints the events of every poll until interrupted. Failed polls are reported and retried.
+    pub async fn run(&mut self) -> Result<WekanResult, Error> {
+        info!("run_watch");
+        let board_id = self.find_board_id().await?;
+        let mut previous = self.load(&board_id).await?;
+        eprintln!(
+            "Watching '{}' with {} cards every {}s",
+            self.args.name,
+            previous.len(),
+            self.args.interval.as_secs()
+        );
+        let mut ctrl_c = Box::pin(tokio::signal::ctrl_c());
+        let mut interval = tokio::time::interval(self.args.interval);
+        // The first tick completes immediately.
+        interval.tick().await;
+        loop {
+            let poll = Box::pin(async {
+                interval.tick().await;
+                self.load(&board_id).await
+            });
+            let loaded = match select(ctrl_c.as_mut(), poll).await {
+                Either::Left(_c) => return WekanResult::new_msg("Stopped watching").ok(),
+                Either::Right((l, _c)) => l,
+            };
+            let current = match loaded {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("Request failed: {:?}", e);
+                    continue;
+                }
+            };
+            let at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+            for event in compare(&previous, &current) {
+                trace!("{:?}", event);
+                if self.args.json {
+                    println!("{}", event.to_json(&at)?);
+                } else {
+                    println!("{} {}", at, event.to_text());
+                }
+            }
+            previous = current;
+        }
+    }
+
+    async fn find_board_id(&self) -> Result<String, Error> {
+        let mut query = self.query("");
+        match query.find_board_id(&self.args.name).await {
+            Ok(id) => Ok(id),
+            Err(_e) => Err(CliError::new_msg("Board name not found").as_enum()),
+        }
+    }
+
+    /// Lists and cards are always requested from the server, the store would hide changes.
+    /// Details of every card are requested for their modification dates.
+    pub async fn load(&mut self, board_id: &str) -> Result<State, Error> {
+        let query = self.query("");
+        let lists = query
+            .inquire(AType::List, Some(board_id), None, None, true)
+            .await?;
+        let mut state = State::new();
+        for list in lists {
+            let cards = query
+                .inquire(AType::Card, Some(board_id), Some(&list._id), None, true)
+                .await?;
+            CardApi::set_base(&mut self.client, board_id, &list._id);
+            for card in cards {
+                let details = self.client.get_one::<CDetails>(&card._id).await?;
+                state.insert(
+                    card._id.to_owned(),
+                    CardState {
+                        title: card.title.to_owned(),
+                        list_id: list._id.to_owned(),
+                        list: list.title.to_owned(),
+                        modified_at: details.get_modified_at(),
+                        date_last_activity: details.get_date_last_activity(),
+                    },
+                );
+            }
+        }
+        Ok(state)
+    }
+
+    fn query<'b>(&self, filter: &'b str) -> Query<'b> {
+        Query::new(
+            self.client.config.clone(),
+            filter,
+            self.global_options.deny_store(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::Mock;
+
+    fn card(title: &str, list: &str, modified_at: &str) -> CardState {
+        CardState {
+            title: title.to_string(),
+            list_id: format!("{}-id", list),
+            list: list.to_string(),
+            modified_at: modified_at.to_string(),
+            date_last_activity: String::new(),
+        }
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(86400)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("5w").is_err());
+        assert!(parse_duration("s").is_err());
+    }
+
+    #[test]
+    fn compare_states() {
+        let mut previous = State::new();
+        previous.insert(String::from("a"), card("Write", "Todo", "1"));
+        previous.insert(String::from("b"), card("Test", "Todo", "1"));
+        previous.insert(String::from("c"), card("Ship", "Todo", "1"));
+        previous.insert(String::from("d"), card("Idle", "Todo", "1"));
+        let mut current = State::new();
+        current.insert(String::from("a"), card("Write", "Done", "2"));
+        current.insert(String::from("b"), card("Test", "Todo", "2"));
+        current.insert(String::from("d"), card("Idle", "Todo", "1"));
+        current.insert(String::from("e"), card("Review", "Todo", "2"));
+        assert_eq!(
+            compare(&previous, &current),
+            vec![
+                Event::Moved {
+                    id: String::from("a"),
+                    title: String::from("Write"),
+                    from: String::from("Todo"),
+                    to: String::from("Done"),
+                },
+                Event::Updated {
+                    id: String::from("b"),
+                    title: String::from("Test"),
+                    list: String::from("Todo"),
+                },
+                Event::Created {
+                    id: String::from("e"),
+                    title: String::from("Review"),
+                    list: String::from("Todo"),
+                },
+                Event::Removed {
+                    id: String::from("c"),
+                    title: String::from("Ship"),
+                    list: String::from("Todo"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn render_events() {
+        let event = Event::Moved {
+            id: String::from("a"),
+            title: String::from("Write"),
+            from: String::from("Todo"),
+            to: String::from("Done"),
+        };
+        assert_eq!(event.to_text(), "moved 'Write' from 'Todo' to 'Done'");
+        assert_eq!(
+            event.to_json("2022-10-12T10:00:00Z").unwrap(),
+            r#"{"at":"2022-10-12T10:00:00Z","event":"moved","from":"Todo","id":"a","title":"Write","to":"Done"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn load_board() {
+        #[cfg(feature = "store")]
+        let r_args = RArgs::mock_with(false, false, "", "");
+        #[cfg(not(feature = "store"))]
+        let r_args = RArgs::mock_with(false, "", "");
+        let mut runner = Runner::new(
+            Args {
+                name: String::from("fake-board-title-1"),
+                interval: Duration::from_secs(30),
+                json: false,
+            },
+            Client::mock(),
+            &r_args,
+        );
+        let board_id = runner.find_board_id().await.unwrap();
+        let mut state = runner.load(&board_id).await.unwrap();
+        assert_eq!(state.len(), 2);
+        assert!(compare(&state, &state).is_empty());
+        state.get_mut("fake-card-id-1").unwrap().modified_at = String::from("earlier");
+        let current = runner.load(&board_id).await.unwrap();
+        assert_eq!(
+            compare(&state, &current),
+            vec![Event::Updated {
+                id: String::from("fake-card-id-1"),
+                title: String::from("fake-card-title-1"),
+                list: String::from("fake-list-title-2"),
+            }]
+        );
+    }
+}
@@ -15,6 +15,28 @@ pub struct Label {
     color: String,
 }
 
+impl Label {
+    pub fn new(id: &str, name: &str, color: &str) -> Self {
+        Self {
+            _id: id.to_string(),
+            name: name.to_string(),
+            color: color.to_string(),
+        }
+    }
+    pub fn get_name(&self) -> String {
+        self.name.to_owned()
+    }
+    pub fn get_color(&self) -> String {
+        self.color.to_owned()
+    }
+}
+
+impl IdReturner for Label {
+    fn get_id(&self) -> String {
+        self._id.to_owned()
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Deserialize, Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -99,6 +121,18 @@ pub struct Details {
     sort: Option<f32>,
 }
 
+impl Details {
+    pub fn is_archived(&self) -> bool {
+        self.archived
+    }
+    pub fn get_labels(&self) -> Vec<Label> {
+        match &self.labels {
+            Some(l) => l.to_owned(),
+            None => Vec::new(),
+        }
+    }
+}
+
 trait DetailsSettings {
     fn is_subtasks(&self) -> bool;
     fn is_attachments(&self) -> bool;
@@ -231,6 +265,11 @@ impl SortedArtifact for Details {
 impl StoreTrait for Details {}
 impl RequestBody for Details {}
 impl WekanDisplay for Details {}
+impl super::common::Elisp for Details {
+    fn to_elisp(&self) -> String {
+        super::common::to_elisp_alist(self)
+    }
+}
 impl DeserializeExt for Details {}
 impl super::common::WekanDisplayExt for Details {}
 impl crate::http::artifact::DetailsResponse for Details {}
@@ -83,7 +83,44 @@ pub struct Details {
     card_number: Option<f64>,
 }
 
-impl Details {}
+impl Details {
+    pub fn is_archived(&self) -> bool {
+        self.archived.unwrap_or(false)
+    }
+    pub fn get_swimlane_id(&self) -> String {
+        self.swimlane_id.to_owned()
+    }
+    pub fn get_start_at(&self) -> Option<String> {
+        self.start_at.to_owned()
+    }
+    pub fn get_received_at(&self) -> Option<String> {
+        self.received_at.to_owned()
+    }
+    pub fn get_date_last_activity(&self) -> String {
+        self.date_last_activity.to_owned()
+    }
+    pub fn get_assignees(&self) -> Vec<String> {
+        match &self.assignees {
+            Some(a) => a.to_owned(),
+            None => Vec::new(),
+        }
+    }
+    pub fn is_overtime(&self) -> bool {
+        self.is_overtime.unwrap_or(false)
+    }
+    pub fn get_members(&self) -> Vec<String> {
+        match &self.members {
+            Some(m) => m.to_owned(),
+            None => Vec::new(),
+        }
+    }
+    pub fn get_label_ids(&self) -> Vec<String> {
+        match &self.label_ids {
+            Some(l) => l.to_owned(),
+            None => Vec::new(),
+        }
+    }
+}
 impl Base for Details {
     fn get_title(&self) -> String {
         self.title.to_owned()
@@ -210,6 +247,11 @@ impl MockDetails for Details {
     }
 }
 impl WekanDisplay for Details {}
+impl super::common::Elisp for Details {
+    fn to_elisp(&self) -> String {
+        super::common::to_elisp_alist(self)
+    }
+}
 #[cfg(feature = "test")]
 impl MockResponse for Details {
     fn mock() -> Self {
@@ -17,11 +17,32 @@ pub struct Details {
     created_at: Option<String>,
     modified_at: Option<String>,
     finished_at: Option<String>,
+    archived: Option<bool>,
+    archived_at: Option<String>,
     sort: Option<f32>,
     r#type: Option<String>,
+    #[serde(default)]
+    items: Vec<Item>,
 }
 
-impl Details {}
+#[allow(dead_code)]
+#[derive(Deserialize, Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    pub _id: String,
+    pub title: String,
+    #[serde(default)]
+    pub is_finished: bool,
+}
+
+impl Details {
+    pub fn is_archived(&self) -> bool {
+        self.archived.unwrap_or(false)
+    }
+    pub fn get_items(&self) -> Vec<Item> {
+        self.items.to_owned()
+    }
+}
 impl Base for Details {
     fn get_title(&self) -> String {
         self.title.to_owned()
@@ -43,7 +64,7 @@ impl IdReturner for Details {
 
 impl BaseDetails for Details {
     fn get_archive_at(&self) -> Option<&String> {
-        None
+        self.archived_at.as_ref()
     }
 
     fn get_modified_at(&self) -> String {
@@ -98,12 +119,31 @@ impl MockDetails for Details {
             created_at: Some(date.to_string()),
             modified_at: Some(date.to_string()),
             finished_at: Some(date.to_string()),
+            archived: None,
+            archived_at: None,
             sort: None,
             r#type: Some(AType::Checklist.to_string()),
+            items: vec![
+                Item {
+                    _id: String::from("fake-item-id-1"),
+                    title: String::from("fake-item-title-1"),
+                    is_finished: true,
+                },
+                Item {
+                    _id: String::from("fake-item-id-2"),
+                    title: String::from("fake-item-title-2"),
+                    is_finished: false,
+                },
+            ],
         }
     }
 }
 impl WekanDisplay for Details {}
+impl super::common::Elisp for Details {
+    fn to_elisp(&self) -> String {
+        super::common::to_elisp_alist(self)
+    }
+}
 #[cfg(feature = "test")]
 impl MockResponse for Details {
     fn mock() -> Self {
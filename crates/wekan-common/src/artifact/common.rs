@@ -176,9 +176,77 @@ pub trait Elisp {
 
 impl Elisp for Artifact {
     fn to_elisp(&self) -> String {
-        format!("((_id . {}) (title . {})) ", self._id, self.title)
+        format!(
+            "((_id . {}) (title . {}))",
+            elisp_string(&self._id),
+            elisp_string(&self.title)
+        )
     }
 }
+
+impl Elisp for Vec<Artifact> {
+    fn to_elisp(&self) -> String {
+        elisp_list(self.iter().map(|a| a.to_elisp()))
+    }
+}
+
+/// Quotes a string so that the elisp reader returns it unchanged.
+pub fn elisp_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn elisp_list(forms: impl Iterator<Item = String>) -> String {
+    format!("({})", forms.collect::<Vec<String>>().join(" "))
+}
+
+/// Serializes any details response into an alist with the API field names as keys.
+pub fn to_elisp_alist<T: Serialize>(details: &T) -> String {
+    match serde_yaml::to_value(details) {
+        Ok(v) => value_to_elisp(&v),
+        Err(_e) => String::from("nil"),
+    }
+}
+
+fn value_to_elisp(value: &serde_yaml::Value) -> String {
+    match value {
+        serde_yaml::Value::Null => String::from("nil"),
+        serde_yaml::Value::Bool(b) => String::from(if *b { "t" } else { "nil" }),
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::String(s) => elisp_string(s),
+        serde_yaml::Value::Sequence(seq) => elisp_list(seq.iter().map(value_to_elisp)),
+        serde_yaml::Value::Mapping(m) => {
+            elisp_list(m.iter().filter_map(|(k, v)| match k.as_str() {
+                Some("type") if is_serde_tag(v) => None,
+                Some(k) => Some(format!("({} . {})", elisp_symbol(k), value_to_elisp(v))),
+                None => None,
+            }))
+        }
+    }
+}
+
+/// The serde tag of a struct is its name, e.g. 'Details', while the 'type' fields of
+/// Wekan like 'board' or 'cardType-card' start lowercase and are kept.
+fn is_serde_tag(value: &serde_yaml::Value) -> bool {
+    match value.as_str() {
+        Some(s) => {
+            s.starts_with(|c: char| c.is_ascii_uppercase())
+                && s.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+        None => false,
+    }
+}
+
+fn elisp_symbol(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
 impl std::fmt::Display for Artifact {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}    {}", self._id, self.title)
@@ -282,7 +350,10 @@ pub trait DeserializeExt:
     serde::de::DeserializeOwned + std::fmt::Debug + std::marker::Send + 'static
 {
 }
-pub trait WekanDisplayExt: DeserializeExt + StoreTrait + BaseDetails + WekanDisplay {}
+pub trait WekanDisplayExt:
+    DeserializeExt + StoreTrait + BaseDetails + WekanDisplay + Elisp
+{
+}
 pub trait SerializeExt: serde::Serialize + std::fmt::Debug + Clone + std::marker::Send {}
 
 impl DeserializeExt for Artifact {}
@@ -1,7 +1,7 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[allow(dead_code)]
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub struct Details {
     card_id: String,
@@ -12,3 +12,9 @@ pub struct Details {
     modified_at: String,
     sort: i8,
 }
+
+impl super::common::Elisp for Details {
+    fn to_elisp(&self) -> String {
+        super::common::to_elisp_alist(self)
+    }
+}
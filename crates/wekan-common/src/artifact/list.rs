@@ -39,6 +39,12 @@ impl Base for Details {
     }
 }
 
+impl Details {
+    pub fn is_archived(&self) -> bool {
+        self.archived
+    }
+}
+
 impl IdReturner for Details {
     fn get_id(&self) -> String {
         self._id.to_owned()
@@ -76,6 +82,11 @@ impl SortedArtifact for Details {
 impl StoreTrait for Details {}
 impl RequestBody for Details {}
 impl WekanDisplay for Details {}
+impl super::common::Elisp for Details {
+    fn to_elisp(&self) -> String {
+        super::common::to_elisp_alist(self)
+    }
+}
 impl DeserializeExt for Details {}
 impl super::common::WekanDisplayExt for Details {}
 impl crate::http::artifact::DetailsResponse for Details {}
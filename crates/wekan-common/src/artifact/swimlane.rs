@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::http::artifact::RequestBody;
+
 use super::common::{
     AType, Base, DeserializeExt, IdReturner, SortedArtifact, StoreTrait, WekanDisplay,
 };
@@ -36,6 +38,15 @@ impl Base for Details {
     }
 }
 
+impl Details {
+    pub fn is_archived(&self) -> bool {
+        self.archived
+    }
+    pub fn get_archived_at(&self) -> String {
+        self.archived_at.to_owned()
+    }
+}
+
 impl IdReturner for Details {
     fn get_id(&self) -> String {
         String::new()
@@ -56,7 +67,13 @@ impl SortedArtifact for Details {
     }
 }
 impl StoreTrait for Details {}
+impl RequestBody for Details {}
 impl WekanDisplay for Details {}
+impl super::common::Elisp for Details {
+    fn to_elisp(&self) -> String {
+        super::common::to_elisp_alist(self)
+    }
+}
 impl DeserializeExt for Details {}
 
 #[cfg(feature = "test")]
@@ -21,6 +21,21 @@ pub struct CreateArtifact {
     pub title: String,
 }
 
+/// Archives or restores a board, list, swimlane or checklist.
+#[derive(Serialize, Debug, Clone)]
+pub struct ArchiveArtifact {
+    pub _id: String,
+    pub archive: bool,
+    pub archive_at: String,
+}
+
+impl IdReturner for ArchiveArtifact {
+    fn get_id(&self) -> String {
+        self._id.to_owned()
+    }
+}
+impl RequestBody for ArchiveArtifact {}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ResponseOk {
     pub _id: String,
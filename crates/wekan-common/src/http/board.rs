@@ -1,9 +1,9 @@
-use super::artifact::RequestBody;
+use super::artifact::{IdResponse, RequestBody};
 use crate::artifact::common::IdReturner;
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "test")]
-use crate::artifact::tests::MockResponse;
+use crate::artifact::tests::{MockResponse, MockReturn};
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize)]
@@ -35,6 +35,17 @@ pub struct CreatedBoard {
     default_swimlane_id: String,
 }
 
+impl IdReturner for CreatedBoard {
+    fn get_id(&self) -> String {
+        self._id.to_owned()
+    }
+}
+impl CreatedBoard {
+    pub fn get_default_swimlane_id(&self) -> String {
+        self.default_swimlane_id.to_owned()
+    }
+}
+
 impl RequestBody for CreateBoard {}
 impl crate::artifact::common::DeserializeExt for CreatedBoard {}
 #[cfg(feature = "test")]
@@ -46,3 +57,131 @@ impl MockResponse for CreatedBoard {
         }
     }
 }
+
+/// Board as answered by 'boards/ID/export', the only request which includes archived children.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Export {
+    #[serde(default)]
+    pub _id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    #[serde(default)]
+    pub lists: Vec<Exported>,
+    #[serde(default)]
+    pub swimlanes: Vec<Exported>,
+    #[serde(default)]
+    pub cards: Vec<Exported>,
+    #[serde(default)]
+    pub checklists: Vec<Exported>,
+}
+
+/// Child of an exported board, cards refer to their list and checklists to their card.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Exported {
+    pub _id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    #[serde(default)]
+    pub list_id: Option<String>,
+    #[serde(default)]
+    pub card_id: Option<String>,
+}
+
+impl crate::artifact::common::DeserializeExt for Export {}
+#[cfg(feature = "test")]
+impl MockResponse for Export {
+    fn mock() -> Self {
+        let exported =
+            |id: &str, archived: bool, list_id: Option<&str>, card_id: Option<&str>| Exported {
+                _id: String::from(id),
+                title: id.replace("-id-", "-title-"),
+                archived,
+                archived_at: match archived {
+                    true => Some(String::from("2022-10-12T10:00:00.000Z")),
+                    false => None,
+                },
+                list_id: list_id.map(String::from),
+                card_id: card_id.map(String::from),
+            };
+        Self {
+            _id: String::from("fake-board-id-1"),
+            title: String::from("fake-board-title-1"),
+            archived: false,
+            archived_at: None,
+            lists: vec![
+                exported("fake-list-id-1", false, None, None),
+                exported("fake-list-id-2", true, None, None),
+            ],
+            swimlanes: vec![exported("fake-swimlane-id-1", true, None, None)],
+            cards: vec![
+                exported("fake-card-id-1", false, Some("fake-list-id-1"), None),
+                exported("fake-card-id-2", true, Some("fake-list-id-1"), None),
+                exported("fake-card-id-3", true, Some("fake-list-id-2"), None),
+            ],
+            checklists: vec![
+                exported("fake-checklist-id-1", true, None, Some("fake-card-id-1")),
+                exported("fake-checklist-id-2", true, None, Some("fake-card-id-2")),
+            ],
+        }
+    }
+}
+
+/// Adds a label to a board with 'PUT boards/ID/labels'.
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateLabel {
+    #[serde(skip)]
+    pub board_id: String,
+    pub label: NewLabel,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NewLabel {
+    pub name: String,
+    pub color: String,
+}
+
+impl IdReturner for CreateLabel {
+    fn get_id(&self) -> String {
+        self.board_id.to_owned() + "/labels"
+    }
+}
+impl RequestBody for CreateLabel {}
+
+/// Wekan answers a new label with its id only.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct CreatedLabel {
+    pub _id: String,
+}
+
+impl IdReturner for CreatedLabel {
+    fn get_id(&self) -> String {
+        self._id.to_owned()
+    }
+}
+impl IdResponse for CreatedLabel {}
+impl crate::artifact::common::DeserializeExt for CreatedLabel {}
+#[cfg(feature = "test")]
+impl MockReturn for CreatedLabel {
+    fn success<T: IdResponse>(_body: Option<T>) -> Self {
+        Self::mock()
+    }
+}
+#[cfg(feature = "test")]
+impl MockResponse for CreatedLabel {
+    fn mock() -> Self {
+        Self {
+            _id: String::from("fake-label-id"),
+        }
+    }
+}
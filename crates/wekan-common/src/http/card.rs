@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 pub struct CreateCard {
     pub _id: String,
     pub author_id: String,
-    pub members: Option<String>,
+    pub members: Option<Vec<String>>,
     pub assignees: Option<String>,
     pub title: String,
     pub description: String,
@@ -38,6 +38,12 @@ impl Card {
 pub struct MoveCard {
     pub _id: String,
     pub list_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub board_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swimlane_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<f32>,
 }
 impl RequestBody for MoveCard {}
 impl IdReturner for MoveCard {
@@ -56,6 +62,8 @@ pub struct UpdateCard {
     pub end_at: Option<String>,
     pub sort: Option<f32>,
     pub labels: Option<Vec<String>>,
+    #[serde(default)]
+    pub assignees: Option<Vec<String>>,
 }
 impl RequestBody for UpdateCard {}
 impl IdReturner for UpdateCard {
@@ -0,0 +1,32 @@
+use super::artifact::RequestBody;
+use crate::artifact::common::IdReturner;
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub struct CreateChecklist {
+    pub _id: String,
+    pub title: String,
+    pub items: Vec<String>,
+}
+impl RequestBody for CreateChecklist {}
+impl IdReturner for CreateChecklist {
+    fn get_id(&self) -> String {
+        self._id.to_owned()
+    }
+}
+
+/// Checks or unchecks a checklist item.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateItem {
+    #[serde(skip)]
+    pub _id: String,
+    pub is_finished: bool,
+}
+impl RequestBody for UpdateItem {}
+impl IdReturner for UpdateItem {
+    fn get_id(&self) -> String {
+        self._id.to_owned()
+    }
+}
@@ -1,4 +1,5 @@
 pub mod artifact;
 pub mod board;
 pub mod card;
+pub mod checklist;
 pub mod common;
@@ -2,6 +2,7 @@ use crate::persistence::config::PersistenceConfig as PConfig;
 use async_trait::async_trait;
 use log::debug;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use wekan_common::validation::authentication::Token;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -15,6 +16,12 @@ pub struct UserConfig {
     pub address: NetworkAddress,
     pub context: Option<String>,
     pub usertoken: Option<Token>,
+    /// Shortcuts for commands, expanded before parsing.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// Print write requests instead of sending them. Never stored.
+    #[serde(skip)]
+    pub dry_run: bool,
 }
 
 #[async_trait]
@@ -48,6 +55,8 @@ impl OptionalConfig for UserConfig {
                 address: self.address.clone(),
                 usertoken: self.usertoken.clone(),
                 context: self.context.clone(),
+                aliases: self.aliases.clone(),
+                dry_run: false,
             }
         })
         .await;
@@ -125,6 +134,8 @@ impl MandatoryConfig for UserConfig {
             },
             context: None,
             usertoken: None,
+            aliases: BTreeMap::new(),
+            dry_run: false,
         }
     }
 
@@ -7,6 +7,25 @@ pub enum Error {
     Constraint(Constraint),
     Io(std::io::Error),
     Yaml(serde_yaml::Error),
+    Json(serde_json::Error),
+    DryRun(DryRun),
+}
+
+/// A write request which wasn't sent because of the dry run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRun {
+    pub method: String,
+    pub url: String,
+    pub body: Option<String>,
+}
+
+impl std::fmt::Display for DryRun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.body {
+            Some(b) => write!(f, "{} {} {}", self.method, self.url, b),
+            None => write!(f, "{} {}", self.method, self.url),
+        }
+    }
 }
 
 impl From<ReqError> for Error {
@@ -26,6 +45,12 @@ impl From<serde_yaml::Error> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Json(error)
+    }
+}
+
 #[derive(Debug)]
 pub struct CoreOk {
     pub name: String,
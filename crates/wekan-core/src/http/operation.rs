@@ -1,11 +1,11 @@
 use super::util::SatisfyType;
 #[cfg(feature = "store")]
+use crate::{client::Client, persistence::store::Store};
 use crate::{
-    client::Client,
-    config::{ConfigRequester, UserConfig},
-    persistence::store::Store,
+    config::{ArtifactApi, ConfigRequester, UserConfig},
+    error::{DryRun, Error},
+    http::client::HttpClient,
 };
-use crate::{config::ArtifactApi, error::Error, http::client::HttpClient};
 use async_trait::async_trait;
 use log::{info, trace};
 #[cfg(feature = "store")]
@@ -15,7 +15,10 @@ use std::marker::Send;
 use wekan_common::artifact::common::StoreTrait;
 use wekan_common::{
     artifact::common::{AType, Artifact, Base, DeserializeExt},
-    http::artifact::{Deleted, RequestBody},
+    http::{
+        artifact::{Deleted, RequestBody},
+        board::Export,
+    },
 };
 #[cfg(feature = "store")]
 impl Store for UserConfig {}
@@ -62,6 +65,17 @@ pub trait Artifacts: Operation + ConfigRequester<UserConfig> {
         }
     }
 
+    /// Lists and cards requests leave out archived artifacts, the export of the board does not.
+    async fn get_export(&mut self, board_id: &str) -> Result<Export, Error> {
+        let token = match &self.get_config().usertoken {
+            Some(t) => t.token.to_string(),
+            None => String::new(),
+        };
+        let url = self.get_artifact_url(&(board_id.to_owned() + "/export?authToken=" + &token));
+        info!("get_export {}", board_id);
+        self.get_request::<Export>(&url).await
+    }
+
     #[cfg(feature = "store")]
     async fn update_store<'de, T: StoreTrait + Deserialize<'de>>(
         config: UserConfig,
@@ -72,6 +86,8 @@ pub trait Artifacts: Operation + ConfigRequester<UserConfig> {
             address: config.address.clone(),
             usertoken: config.usertoken.clone(),
             context: config.context.clone(),
+            aliases: config.aliases.clone(),
+            dry_run: false,
         };
         <UserConfig as Store>::write_into_context::<T>(&config, body.to_owned(), id).await
     }
@@ -105,21 +121,38 @@ pub trait Artifacts: Operation {
             Err(e) => Err(e),
         }
     }
+
+    /// Lists and cards requests leave out archived artifacts, the export of the board does not.
+    async fn get_export(&mut self, board_id: &str) -> Result<Export, Error> {
+        let token = match &self.get_config().usertoken {
+            Some(t) => t.token.to_string(),
+            None => String::new(),
+        };
+        let url = self.get_artifact_url(&(board_id.to_owned() + "/export?authToken=" + &token));
+        info!("get_export {}", board_id);
+        self.get_request::<Export>(&url).await
+    }
 }
 
 #[async_trait]
-pub trait Operation: ArtifactApi + HttpClient {
+pub trait Operation: ArtifactApi + HttpClient + ConfigRequester<UserConfig> {
     async fn create<U: RequestBody, T: Send + DeserializeExt + 'static>(
         &mut self,
         body: &U,
     ) -> Result<T, Error> {
         let r = self.get_artifacts_url().to_owned();
         info!("create {}", r);
+        if self.get_config().dry_run {
+            return dry_run("POST", &r, Some(serde_json::to_string(body)?));
+        }
         self.post_request(&r, body).await
     }
     async fn delete<T: Deleted + DeserializeExt>(&mut self, id: &str) -> Result<T, Error> {
         let url = self.get_artifact_url(id);
         info!("delete {}", url);
+        if self.get_config().dry_run {
+            return dry_run("DELETE", &url, None);
+        }
         self.delete_request(&url).await
     }
     async fn put<B: RequestBody, T: Send + DeserializeExt + 'static>(
@@ -128,6 +161,40 @@ pub trait Operation: ArtifactApi + HttpClient {
     ) -> Result<T, Error> {
         let url = self.get_artifact_url(&body.get_id());
         info!("put {}", url);
+        if self.get_config().dry_run {
+            return dry_run("PUT", &url, Some(serde_json::to_string(body)?));
+        }
         self.put_request(&url, body).await
     }
 }
+
+/// Stops before the request is sent, callers must not continue with the response.
+fn dry_run<T>(method: &str, url: &str, body: Option<String>) -> Result<T, Error> {
+    Err(Error::DryRun(DryRun {
+        method: method.to_string(),
+        url: url.to_string(),
+        body,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wekan_common::http::artifact::ResponseOk;
+
+    #[test]
+    fn dry_run_request() {
+        let put = DryRun {
+            method: String::from("PUT"),
+            url: String::from("http://localhost:8080/api/boards/1"),
+            body: Some(String::from("{}")),
+        };
+        assert_eq!(put.to_string(), "PUT http://localhost:8080/api/boards/1 {}");
+        match dry_run::<ResponseOk>("DELETE", "http://localhost:8080/api/boards/1", None) {
+            Err(Error::DryRun(d)) => {
+                assert_eq!(d.to_string(), "DELETE http://localhost:8080/api/boards/1")
+            }
+            _ => panic!("Request was not stopped"),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-;; -*- lexical-binding: t -*-
+;;; wekan.el --- Browse Wekan boards with wekan-cli -*- lexical-binding: t -*-
 ;;
 ;; wekan.el is an emacs client to be used in combination with wekan-cli to
 ;; interact with a WekanBoard https://wekan.github.io/.
@@ -17,37 +17,216 @@
 ;; You should have received a copy of the GNU Affero General Public License
 ;; along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+;;; Commentary:
+;;
+;; Every command calls wekan-cli with `-o elisp' and reads the printed plist
+;; `(:exit-code N :data FORM :next-workflow W)' or `(:exit-code N :error MSG)'.
+;; Log in with `wekan-cli config' first.
+;;
+;; M-x wekan-boards lists all boards, RET opens a board.
+;; In a board buffer RET shows a card, `m' moves it to another list and `g'
+;; refreshes the board.
+
+;;; Code:
+
+(require 'subr-x)
+(require 'tabulated-list)
+
+(defgroup wekan nil
+  "Emacs client for wekan-cli."
+  :group 'tools)
 
 (defcustom wekan-binary "wekan-cli"
-"wekan binary name."
+  "wekan binary name."
   :group 'wekan
   :type 'string)
 
-(defcustom wekan-board "board"
-"board command"
+(defcustom wekan-extra-args nil
+  "Global options put in front of every wekan-cli call, e.g. (\"-d\") to skip the store."
   :group 'wekan
-    :type 'string)
-
-(defun wekan-run-cmd (cmd args)
-  "Run a wekan cmd."
-  (let ((use-name (name cmd)))
-    (start-process-shell-command
-        use-name
-        use-name
-        (format "%s %s" wekan-binary use-name)
-        )
-      )
-    )
-
-
-;; (wekan-board-ls)
-(defun wekan-board-ls ()
-    (interactive)
-    (wekan-run-cmd
-        (wekan-binary)
-        wekan-board
-        "ls")
-    )
+  :type '(repeat string))
+
+(defvar-local wekan--board nil
+  "Title of the board shown in the current buffer.")
+
+(defvar-local wekan--lists nil
+  "Lists of the board shown in the current buffer, as returned by `table'.")
+
+(defun wekan--alist-get (key alist)
+  "Return the value of KEY in ALIST or nil."
+  (cdr (assq key alist)))
+
+(defun wekan-run-cmd (&rest args)
+  "Run wekan-cli with ARGS and return the data of the result plist.
+Signal a `user-error' if the command failed."
+  (with-temp-buffer
+    (let* ((status (apply #'process-file wekan-binary nil (list t nil) nil
+                          (append wekan-extra-args '("-o" "elisp" "-r") args)))
+           (result (condition-case nil
+                       (progn (goto-char (point-min)) (read (current-buffer)))
+                     (error nil))))
+      (cond
+       ((not (listp result))
+        (user-error "wekan-cli returned no result (exit %s)" status))
+       ((plist-get result :error)
+        (user-error "wekan-cli: %s" (plist-get result :error)))
+       ((plist-member result :data) (plist-get result :data))
+       (t (plist-get result :message))))))
+
+;;;; Boards
+
+(define-derived-mode wekan-boards-mode tabulated-list-mode "Wekan boards"
+  "Major mode listing all boards of the logged in user."
+  (setq tabulated-list-format [("Title" 40 t) ("ID" 20 nil)])
+  (setq tabulated-list-sort-key '("Title" . nil))
+  (add-hook 'tabulated-list-revert-hook #'wekan--boards-refresh nil t)
+  (tabulated-list-init-header))
+
+(define-key wekan-boards-mode-map (kbd "RET") #'wekan-boards-open)
+
+(defun wekan--boards-refresh ()
+  "Request the boards again."
+  (setq tabulated-list-entries
+        (mapcar (lambda (board)
+                  (let ((id (wekan--alist-get '_id board)))
+                    (list id (vector (wekan--alist-get 'title board) id))))
+                (wekan-run-cmd "board" "ls"))))
+
+;;;###autoload
+(defun wekan-boards ()
+  "Show all boards."
+  (interactive)
+  (let ((buffer (get-buffer-create "*wekan-boards*")))
+    (with-current-buffer buffer
+      (wekan-boards-mode)
+      (wekan--boards-refresh)
+      (tabulated-list-print))
+    (pop-to-buffer-same-window buffer)))
+
+(defalias 'wekan-board-ls #'wekan-boards)
+
+(defun wekan-boards-open ()
+  "Open the board at point."
+  (interactive)
+  (let ((entry (tabulated-list-get-entry)))
+    (unless entry
+      (user-error "No board at point"))
+    (wekan-board-open (aref entry 0))))
+
+;;;; Board
+
+(defvar wekan-board-mode-map
+  (let ((map (make-sparse-keymap)))
+    (define-key map (kbd "RET") #'wekan-card-open)
+    (define-key map (kbd "m") #'wekan-card-move)
+    (define-key map (kbd "g") #'wekan-board-refresh)
+    (define-key map (kbd "n") #'wekan-next-card)
+    (define-key map (kbd "p") #'wekan-previous-card)
+    map)
+  "Keymap for `wekan-board-mode'.")
+
+(define-derived-mode wekan-board-mode special-mode "Wekan board"
+  "Major mode showing the lists and cards of one board.")
+
+;;;###autoload
+(defun wekan-board-open (board)
+  "Show the lists and cards of BOARD."
+  (interactive
+   (list (completing-read "Board: "
+                          (mapcar (lambda (b) (wekan--alist-get 'title b))
+                                  (wekan-run-cmd "board" "ls")))))
+  (let ((buffer (get-buffer-create (format "*wekan: %s*" board))))
+    (with-current-buffer buffer
+      (wekan-board-mode)
+      (setq wekan--board board)
+      (wekan-board-refresh))
+    (pop-to-buffer-same-window buffer)))
+
+(defun wekan-board-refresh ()
+  "Request the board of the current buffer again."
+  (interactive)
+  (unless wekan--board
+    (user-error "Not in a wekan board buffer"))
+  (let ((line (line-number-at-pos))
+        (inhibit-read-only t))
+    (setq wekan--lists (wekan-run-cmd "table" wekan--board))
+    (erase-buffer)
+    (insert (propertize wekan--board 'face 'bold) "\n")
+    (dolist (list wekan--lists)
+      (let ((list-title (wekan--alist-get 'title list)))
+        (insert "\n" (propertize list-title 'face 'font-lock-keyword-face) "\n")
+        (dolist (card (wekan--alist-get 'cards list))
+          (insert (propertize (concat "  " (wekan--alist-get 'title card))
+                              'wekan-list list-title
+                              'wekan-card (wekan--alist-get 'title card))
+                  "\n"))))
+    (goto-char (point-min))
+    (forward-line (1- line))))
+
+(defun wekan--card-at-point ()
+  "Return (LIST . CARD) titles of the card at point."
+  (let ((card (get-text-property (point) 'wekan-card)))
+    (unless card
+      (user-error "No card at point"))
+    (cons (get-text-property (point) 'wekan-list) card)))
+
+(defun wekan--goto-card (direction)
+  "Move DIRECTION lines until a card is reached, stay put if there is none."
+  (let ((start (point)))
+    (forward-line direction)
+    (while (and (not (get-text-property (point) 'wekan-card))
+                (zerop (forward-line direction))))
+    (unless (get-text-property (point) 'wekan-card)
+      (goto-char start))))
+
+(defun wekan-next-card ()
+  "Move to the next card."
+  (interactive)
+  (wekan--goto-card 1))
+
+(defun wekan-previous-card ()
+  "Move to the previous card."
+  (interactive)
+  (wekan--goto-card -1))
+
+;;;; Card
+
+(defun wekan-card-open ()
+  "Show the details of the card at point."
+  (interactive)
+  (let* ((board wekan--board)
+         (at-point (wekan--card-at-point))
+         (details (wekan-run-cmd "card" "-b" board "-l" (car at-point)
+                                 "-n" (cdr at-point) "details"))
+         (buffer (get-buffer-create (format "*wekan card: %s*" (cdr at-point)))))
+    (with-current-buffer buffer
+      (let ((inhibit-read-only t))
+        (erase-buffer)
+        (special-mode)
+        (insert (propertize (or (wekan--alist-get 'title details) "") 'face 'bold) "\n\n")
+        (insert "Board: " board "\n")
+        (insert "List:  " (car at-point) "\n")
+        (dolist (field '((dueAt . "Due:   ") (endAt . "Done:  ") (startAt . "Start: ")))
+          (let ((value (wekan--alist-get (car field) details)))
+            (when (and value (not (string-empty-p value)))
+              (insert (cdr field) value "\n"))))
+        (let ((description (wekan--alist-get 'description details)))
+          (when (and description (not (string-empty-p description)))
+            (insert "\n" description "\n")))
+        (goto-char (point-min))))
+    (display-buffer buffer)))
+
+(defun wekan-card-move (target)
+  "Move the card at point to the list TARGET."
+  (interactive
+   (list (completing-read "Move to list: "
+                          (mapcar (lambda (l) (wekan--alist-get 'title l)) wekan--lists)
+                          nil t)))
+  (let ((at-point (wekan--card-at-point)))
+    (wekan-run-cmd "card" "-b" wekan--board "-l" (car at-point)
+                   "-n" (cdr at-point) "mv" target)
+    (wekan-board-refresh)
+    (message "Moved '%s' to '%s'" (cdr at-point) target)))
 
 (provide 'wekan)
-;:: wekan.el ends here
+;;; wekan.el ends here